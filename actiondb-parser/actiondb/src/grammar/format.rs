@@ -0,0 +1,90 @@
+use matcher::compiled_pattern::{CompiledPattern, TokenType};
+use parsers::{Parser, OptionalParameter};
+use super::{escape_literal, escape_group_literal, escape_string};
+
+// These parameters are written without their names before the optional
+// ones. The end string of GREEDY is the literal after it, so it's skipped.
+const POSITIONAL_PARAMETERS: &'static [&'static str] = &["set", "quotes", "regex"];
+const SKIPPED_PARAMETERS: &'static [&'static str] = &["end_string"];
+
+// Writes `pattern` in the pattern grammar, so parsing the returned string
+// results in the same pattern.
+pub fn format_pattern(pattern: &CompiledPattern) -> String {
+    let mut buffer = String::new();
+    format_tokens(&mut buffer, pattern, false);
+    buffer
+}
+
+fn format_tokens(buffer: &mut String, pattern: &CompiledPattern, in_group: bool) {
+    for token in pattern {
+        match *token {
+            TokenType::Literal(ref literal) if in_group => {
+                buffer.push_str(&escape_group_literal(literal))
+            }
+            TokenType::Literal(ref literal) => buffer.push_str(&escape_literal(literal)),
+            TokenType::Parser(ref parser) => format_parser(buffer, &**parser),
+            TokenType::Alternation(ref alternatives) => {
+                format_group(buffer, alternatives.iter(), false)
+            }
+            TokenType::Optional(ref optional) => {
+                match optional.first() {
+                    Some(&TokenType::Alternation(ref alternatives)) if optional.len() == 1 => {
+                        format_group(buffer, alternatives.iter(), true)
+                    }
+                    _ => format_group(buffer, Some(optional).into_iter(), true),
+                }
+            }
+        }
+    }
+}
+
+fn format_group<'a, I>(buffer: &mut String, alternatives: I, optional: bool)
+    where I: Iterator<Item = &'a CompiledPattern>
+{
+    buffer.push_str("%{(");
+    for (index, alternative) in alternatives.enumerate() {
+        if index > 0 {
+            buffer.push('|');
+        }
+        format_tokens(buffer, alternative, true);
+    }
+    buffer.push(')');
+    if optional {
+        buffer.push('?');
+    }
+    buffer.push('}');
+}
+
+fn format_parser(buffer: &mut String, parser: &Parser) {
+    let mut positional = Vec::new();
+    let mut optional = Vec::new();
+    for parameter in parser.parameters() {
+        match parameter {
+            OptionalParameter::Str(name, _) if SKIPPED_PARAMETERS.contains(&name) => {}
+            OptionalParameter::Str("set", value) => positional.push(format!("\"{}\"", value)),
+            OptionalParameter::Str(name, value) if POSITIONAL_PARAMETERS.contains(&name) => {
+                positional.push(escape_string(&value))
+            }
+            OptionalParameter::Int(name, value) => optional.push(format!("{}={}", name, value)),
+            OptionalParameter::Float(name, value) => optional.push(format!("{}={}", name, value)),
+            OptionalParameter::Str(name, value) => {
+                optional.push(format!("{}={}", name, escape_string(&value)))
+            }
+            OptionalParameter::Bool(name, value) => optional.push(format!("{}={}", name, value)),
+        }
+    }
+
+    buffer.push_str("%{");
+    buffer.push_str(parser.kind());
+    positional.extend(optional);
+    if !positional.is_empty() {
+        buffer.push('(');
+        buffer.push_str(&positional.join(", "));
+        buffer.push(')');
+    }
+    if let Some(name) = parser.name() {
+        buffer.push(':');
+        buffer.push_str(name);
+    }
+    buffer.push('}');
+}
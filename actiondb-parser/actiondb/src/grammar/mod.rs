@@ -1,7 +1,27 @@
+pub use self::format::format_pattern;
+
 #[cfg(test)]
 mod test;
 pub mod parser;
+mod format;
 
 pub fn unescape_literal(literal: &str) -> String {
     literal.replace(r#"\%\{"#, "%{")
 }
+
+pub fn unescape_group_literal(literal: &str) -> String {
+    unescape_literal(literal).replace(r#"\|"#, "|").replace(r#"\)"#, ")")
+}
+
+pub fn escape_literal(literal: &str) -> String {
+    literal.replace("%{", r#"\%\{"#)
+}
+
+pub fn escape_group_literal(literal: &str) -> String {
+    escape_literal(literal).replace("|", r#"\|"#).replace(")", r#"\)"#)
+}
+
+// Quotes `value` as a parser parameter, e.g. the format of TIMESTAMP.
+pub fn escape_string(value: &str) -> String {
+    format!("\"{}\"", value.replace("\\", "\\\\").replace("\"", "\\\""))
+}
@@ -1,7 +1,7 @@
 // Generated by rust-peg. Do not edit.
 #![allow(non_snake_case, unused)]
-use matcher::compiled_pattern::{CompiledPattern, TokenType};
-use parsers::{SetParser, IntParser, Parser, OptionalParameter, GreedyParser};
+use matcher::compiled_pattern::{self, CompiledPattern, TokenType};
+use parsers::{SetParser, IntParser, Parser, OptionalParameter, GreedyParser, TimestampParser};
 use grammar;
 use utils;
 use parsers::ParserFactory;
@@ -160,10 +160,22 @@ fn parse_pattern<'input, F: ParserFactory>(input: &'input str,
                 Matched(pos, pieces) => {
                     {
                         let match_str = &input[start_pos..pos];
-                        Matched(pos, {
+                        match {
                             let mut pieces = pieces;
-                            utils::flatten_vec(pieces)
-                        })
+                            let pattern = utils::flatten_vec(pieces);
+                            if compiled_pattern::count_variants(&pattern) >
+                               compiled_pattern::MAX_VARIANT_COUNT {
+                                Err(compiled_pattern::MAX_VARIANT_COUNT_EXPECTED)
+                            } else {
+                                Ok(pattern)
+                            }
+                        } {
+                            Ok(res) => Matched(pos, res),
+                            Err(expected) => {
+                                state.mark_failure(pos, expected);
+                                Failed
+                            }
+                        }
                     }
                 }
                 Failed => Failed,
@@ -180,7 +192,166 @@ fn parse_pattern_piece<'input, F: ParserFactory>(input: &'input str,
         match choice_res {
             Matched(pos, value) => Matched(pos, value),
             Failed => {
-                let choice_res = parse_piece_literal::<F>(input, state, pos);
+                let choice_res = parse_piece_group::<F>(input, state, pos);
+                match choice_res {
+                    Matched(pos, value) => Matched(pos, value),
+                    Failed => {
+                        let choice_res = parse_piece_literal::<F>(input, state, pos);
+                        match choice_res {
+                            Matched(pos, value) => Matched(pos, value),
+                            Failed => parse_piece_parser::<F>(input, state, pos),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+fn parse_piece_group<'input, F: ParserFactory>(input: &'input str,
+                                               state: &mut ParseState<'input>,
+                                               pos: usize)
+                                               -> RuleResult<Vec<TokenType>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_PARSER_BEGIN::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    let seq_res = parse_GROUP_BEGIN::<F>(input, state, pos);
+                    match seq_res {
+                        Matched(pos, _) => {
+                            let seq_res = {
+                                let mut repeat_pos = pos;
+                                let mut repeat_value = vec![];
+                                loop {
+                                    let pos = repeat_pos;
+                                    let pos = if !repeat_value.is_empty() {
+                                        let sep_res = parse_GROUP_SEPARATOR::<F>(input, state, pos);
+                                        match sep_res {
+                                            Matched(newpos, _) => newpos,
+                                            Failed => break,
+                                        }
+                                    } else {
+                                        pos
+                                    };
+                                    let step_res = parse_group_alternative::<F>(input, state, pos);
+                                    match step_res {
+                                        Matched(newpos, value) => {
+                                            repeat_pos = newpos;
+                                            repeat_value.push(value);
+                                        }
+                                        Failed => {
+                                            break;
+                                        }
+                                    }
+                                }
+                                Matched(repeat_pos, repeat_value)
+                            };
+                            match seq_res {
+                                Matched(pos, alternatives) => {
+                                    let seq_res = parse_GROUP_END::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, _) => {
+                                            let seq_res = match parse_GROUP_OPTIONAL::<F>(
+                                                input, state, pos,
+                                            ) {
+                                                Matched(newpos, value) => {
+                                                    Matched(newpos, Some(value))
+                                                }
+                                                Failed => Matched(pos, None),
+                                            };
+                                            match seq_res {
+                                                Matched(pos, optional) => {
+                                                    let seq_res =
+                                                        parse_PARSER_END::<F>(input, state, pos);
+                                                    match seq_res {
+                                                        Matched(pos, _) => {
+                                                            let match_str = &input[start_pos..pos];
+                                                            Matched(pos, {
+                                                                let mut alternatives = alternatives;
+                                                                let group = if optional.is_none() {
+                                                                    TokenType::Alternation(
+                                                                        alternatives,
+                                                                    )
+                                                                } else if alternatives.len() == 1 {
+                                                                    TokenType::Optional(
+                                                                        alternatives.pop().unwrap(),
+                                                                    )
+                                                                } else {
+                                                                    TokenType::Optional(vec![
+                                                                        TokenType::Alternation(
+                                                                            alternatives,
+                                                                        ),
+                                                                    ])
+                                                                };
+                                                                vec![group]
+                                                            })
+                                                        }
+                                                        Failed => Failed,
+                                                    }
+                                                }
+                                                Failed => Failed,
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_group_alternative<'input, F: ParserFactory>(input: &'input str,
+                                                     state: &mut ParseState<'input>,
+                                                     pos: usize)
+                                                     -> RuleResult<CompiledPattern> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = {
+                let mut repeat_pos = pos;
+                let mut repeat_value = vec![];
+                loop {
+                    let pos = repeat_pos;
+                    let step_res = parse_group_piece::<F>(input, state, pos);
+                    match step_res {
+                        Matched(newpos, value) => {
+                            repeat_pos = newpos;
+                            repeat_value.push(value);
+                        }
+                        Failed => {
+                            break;
+                        }
+                    }
+                }
+                Matched(repeat_pos, repeat_value)
+            };
+            match seq_res {
+                Matched(pos, pieces) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { utils::flatten_vec(pieces) })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_group_piece<'input, F: ParserFactory>(input: &'input str,
+                                               state: &mut ParseState<'input>,
+                                               pos: usize)
+                                               -> RuleResult<Vec<TokenType>> {
+    {
+        let choice_res = parse_piece_group::<F>(input, state, pos);
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let choice_res = parse_group_literal::<F>(input, state, pos);
                 match choice_res {
                     Matched(pos, value) => Matched(pos, value),
                     Failed => parse_piece_parser::<F>(input, state, pos),
@@ -189,6 +360,27 @@ fn parse_pattern_piece<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
+fn parse_group_literal<'input, F: ParserFactory>(input: &'input str,
+                                                 state: &mut ParseState<'input>,
+                                                 pos: usize)
+                                                 -> RuleResult<Vec<TokenType>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_group_literal_chars::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, literal) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, {
+                        let unescaped_literal = grammar::unescape_group_literal(literal);
+                        vec![TokenType::Literal(unescaped_literal)]
+                    })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
 fn parse_piece_literal<'input, F: ParserFactory>(input: &'input str,
                                                  state: &mut ParseState<'input>,
                                                  pos: usize)
@@ -258,7 +450,76 @@ fn parse_parser<'input, F: ParserFactory>(input: &'input str,
         let choice_res = parse_parser_SET::<F>(input, state, pos);
         match choice_res {
             Matched(pos, value) => Matched(pos, value),
-            Failed => parse_parser_INT::<F>(input, state, pos),
+            Failed => {
+                let choice_res = parse_parser_INT::<F>(input, state, pos);
+                match choice_res {
+                    Matched(pos, value) => Matched(pos, value),
+                    Failed => {
+                        let choice_res = parse_parser_NUMBER::<F>(input, state, pos);
+                        match choice_res {
+                            Matched(pos, value) => Matched(pos, value),
+                            Failed => {
+                                let choice_res = parse_parser_QSTRING::<F>(input, state, pos);
+                                match choice_res {
+                                    Matched(pos, value) => Matched(pos, value),
+                                    Failed => {
+                                        let choice_res =
+                                            parse_parser_TIMESTAMP::<F>(input, state, pos);
+                                        match choice_res {
+                                            Matched(pos, value) => Matched(pos, value),
+                                            Failed => {
+                                                let choice_res =
+                                                    parse_parser_REGEX::<F>(input, state, pos);
+                                                match choice_res {
+                                                    Matched(pos, value) => Matched(pos, value),
+                                                    Failed => {
+                                                        let choice_res =
+                                                            parse_parser_KV::<F>(input, state, pos);
+                                                        match choice_res {
+                                                            Matched(pos, value) => {
+                                                                Matched(pos, value)
+                                                            }
+                                                            Failed => {
+                                                                let choice_res =
+                                                                    parse_parser_IPV4::<F>(
+                                                                        input, state, pos,
+                                                                    );
+                                                                match choice_res {
+                                                                    Matched(pos, value) => {
+                                                                        Matched(pos, value)
+                                                                    }
+                                                                    Failed => {
+                                                                        let choice_res =
+                                                                            parse_parser_IPV6::<F>(
+                                                                                input, state, pos,
+                                                                            );
+                                                                        match choice_res {
+                                                                            Matched(pos, value) => {
+                                                                                Matched(pos, value)
+                                                                            }
+                                                                            Failed => {
+                                                                                parse_parser_IPADDR::<
+                                                                                    F,
+                                                                                >(
+                                                                                    input, state, pos
+                                                                                )
+                                                                            }
+                                                                        }
+                                                                    }
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
         }
     }
 }
@@ -546,89 +807,42 @@ fn parse_parser_INT_optional_params<'input, F: ParserFactory>
         }
     }
 }
-fn parse_parser_GREEDY<'input, F: ParserFactory>(input: &'input str,
+fn parse_parser_NUMBER<'input, F: ParserFactory>(input: &'input str,
                                                  state: &mut ParseState<'input>,
                                                  pos: usize)
-                                                 -> RuleResult<Vec<TokenType>> {
+                                                 -> RuleResult<Box<Parser>> {
     {
         let start_pos = pos;
         {
-            let seq_res = parse_PARSER_BEGIN::<F>(input, state, pos);
+            let seq_res = {
+                let choice_res = parse_NUMBER::<F>(input, state, pos);
+                match choice_res {
+                    Matched(pos, value) => Matched(pos, value),
+                    Failed => parse_FLOAT::<F>(input, state, pos),
+                }
+            };
             match seq_res {
                 Matched(pos, _) => {
+                    let seq_res = match parse_parser_NUMBER_optional_params::<F>(input, state, pos)
                     {
-                        let seq_res = parse_GREEDY::<F>(input, state, pos);
-                        match seq_res {
-                            Matched(pos, _) => {
-                                {
-                                    let seq_res = match parse_parser_name::<F>(input, state, pos) {
-                                        Matched(newpos, value) => {
-                                            Matched(newpos, Some(value))
-                                        }
-                                        Failed => {
-                                            Matched(pos, None)
-                                        }
-                                    };
-                                    match seq_res {
-                                        Matched(pos, name) => {
-                                            {
-                                                let seq_res = parse_PARSER_END::<F>(input,
-                                                                                    state,
-                                                                                    pos);
-                                                match seq_res {
-                                                    Matched(pos, _) => {
-                                                        {
-                                                            let seq_res =
-                                                                match parse_literal::<F>(input,
-                                                                                         state,
-                                                                                         pos) {
-                                                                    Matched(newpos, value) => {
-                                                                        Matched(newpos, Some(value))
-                                                                    }
-                                                                    Failed => {
-                                                                        Matched(pos, None)
-                                                                    }
-                                                                };
-                                                            match seq_res {
-                                                                Matched(pos, end_string) => {
-                                                                    {
-                                                                        let match_str =
-                                                                            &input[start_pos..pos];
-                                                                        Matched(pos,
-                                                                                {
-                                                                                    let mut tokens =
-                                                                                        Vec::new();
-                                                                                    let parser =
-                                                                                        F::new_greedy(name,
-                                                                                                      end_string);
-                                                                                    tokens.push(TokenType::Parser(parser));
-                                                                                    let end_string =
-                                                                                        end_string.map(|string|
-                                                                                                           string.to_owned());
-                                                                                    if let Some(end_string)
-                                                                                           =
-                                                                                           end_string
-                                                                                           {
-                                                                                        tokens.push(TokenType::Literal(end_string));
-                                                                                    }
-                                                                                    tokens
-                                                                                })
-                                                                    }
-                                                                }
-                                                                Failed => Failed,
-                                                            }
-                                                        }
-                                                    }
-                                                    Failed => Failed,
-                                                }
-                                            }
-                                        }
-                                        Failed => Failed,
-                                    }
+                        Matched(newpos, value) => Matched(newpos, Some(value)),
+                        Failed => Matched(pos, None),
+                    };
+                    match seq_res {
+                        Matched(pos, opt_params) => {
+                            let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                                Matched(newpos, value) => Matched(newpos, Some(value)),
+                                Failed => Matched(pos, None),
+                            };
+                            match seq_res {
+                                Matched(pos, name) => {
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, { F::new_number(name, opt_params) })
                                 }
+                                Failed => Failed,
                             }
-                            Failed => Failed,
                         }
+                        Failed => Failed,
                     }
                 }
                 Failed => Failed,
@@ -636,36 +850,981 @@ fn parse_parser_GREEDY<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
-fn parse_parser_BASE_optional_param<'input, F: ParserFactory>
-                                                              (input: &'input str,
-                                                               state: &mut ParseState<'input>,
-                                                               pos: usize)
-                                                               -> RuleResult<OptionalParameter<'input>> {
+fn parse_parser_NUMBER_optional_params<'input, F: ParserFactory>(input: &'input str,
+                                                                 state: &mut ParseState<'input>,
+                                                                 pos: usize)
+                                                                 -> RuleResult<Vec<OptionalParameter<'input>>> {
     {
-        let choice_res = {
-            let start_pos = pos;
-            {
-                let seq_res = parse_MIN_LEN::<F>(input, state, pos);
-                match seq_res {
-                    Matched(pos, name) => {
-                        {
-                            let seq_res = slice_eq(input, state, pos, "=");
+        let start_pos = pos;
+        {
+            let seq_res = parse_PARSER_PARAMS_BEGIN::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    let seq_res = {
+                        let mut repeat_pos = pos;
+                        let mut repeat_value = vec![];
+                        loop {
+                            let pos = repeat_pos;
+                            let pos = if !repeat_value.is_empty() {
+                                let sep_res = parse_comma::<F>(input, state, pos);
+                                match sep_res {
+                                    Matched(newpos, _) => newpos,
+                                    Failed => break,
+                                }
+                            } else {
+                                pos
+                            };
+                            let step_res =
+                                parse_parser_NUMBER_optional_param::<F>(input, state, pos);
+                            match step_res {
+                                Matched(newpos, value) => {
+                                    repeat_pos = newpos;
+                                    repeat_value.push(value);
+                                }
+                                Failed => {
+                                    break;
+                                }
+                            }
+                        }
+                        Matched(repeat_pos, repeat_value)
+                    };
+                    match seq_res {
+                        Matched(pos, params) => {
+                            let seq_res = parse_PARSER_PARAMS_END::<F>(input, state, pos);
                             match seq_res {
                                 Matched(pos, _) => {
-                                    {
-                                        let seq_res = parse_int::<F>(input, state, pos);
-                                        match seq_res {
-                                            Matched(pos, value) => {
-                                                {
-                                                    let match_str = &input[start_pos..pos];
-                                                    Matched(pos, {
-                                                        OptionalParameter::Int(name, value)
-                                                    })
-                                                }
-                                            }
-                                            Failed => Failed,
-                                        }
-                                    }
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, { params })
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_NUMBER_optional_param<'input, F: ParserFactory>(input: &'input str,
+                                                                state: &mut ParseState<'input>,
+                                                                pos: usize)
+                                                                -> RuleResult<OptionalParameter<'input>> {
+    {
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = parse_MIN::<F>(input, state, pos);
+                match seq_res {
+                    Matched(pos, name) => {
+                        let seq_res = slice_eq(input, state, pos, "=");
+                        match seq_res {
+                            Matched(pos, _) => {
+                                let seq_res = parse_float::<F>(input, state, pos);
+                                match seq_res {
+                                    Matched(pos, value) => {
+                                        let match_str = &input[start_pos..pos];
+                                        Matched(pos, { OptionalParameter::Float(name, value) })
+                                    }
+                                    Failed => Failed,
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                    Failed => Failed,
+                }
+            }
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let start_pos = pos;
+                {
+                    let seq_res = parse_MAX::<F>(input, state, pos);
+                    match seq_res {
+                        Matched(pos, name) => {
+                            let seq_res = slice_eq(input, state, pos, "=");
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    let seq_res = parse_float::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, value) => {
+                                            let match_str = &input[start_pos..pos];
+                                            Matched(pos, { OptionalParameter::Float(name, value) })
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+            }
+        }
+    }
+}
+fn parse_parser_QSTRING<'input, F: ParserFactory>(input: &'input str,
+                                                  state: &mut ParseState<'input>,
+                                                  pos: usize)
+                                                  -> RuleResult<Box<Parser>> {
+    {
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = parse_QSTRING::<F>(input, state, pos);
+                match seq_res {
+                    Matched(pos, _) => {
+                        let seq_res = parse_PARSER_PARAMS_BEGIN::<F>(input, state, pos);
+                        match seq_res {
+                            Matched(pos, _) => {
+                                let seq_res = parse_escaped_string::<F>(input, state, pos);
+                                match seq_res {
+                                    Matched(pos, quotes) => {
+                                        let seq_res = match parse_parser_QSTRING_optional_params::<F>(
+                                            input, state, pos,
+                                        ) {
+                                            Matched(newpos, value) => Matched(newpos, Some(value)),
+                                            Failed => Matched(pos, None),
+                                        };
+                                        match seq_res {
+                                            Matched(pos, opt_params) => {
+                                                let seq_res =
+                                                    parse_PARSER_PARAMS_END::<F>(input, state, pos);
+                                                match seq_res {
+                                                    Matched(pos, _) => {
+                                                        let seq_res = match parse_parser_name::<F>(
+                                                            input, state, pos,
+                                                        ) {
+                                                            Matched(newpos, value) => {
+                                                                Matched(newpos, Some(value))
+                                                            }
+                                                            Failed => Matched(pos, None),
+                                                        };
+                                                        match seq_res {
+                                                            Matched(pos, name) => {
+                                                                let match_str =
+                                                                    &input[start_pos..pos];
+                                                                Matched(pos, {
+                                                                    F::new_qstring(
+                                                                        &quotes, name, opt_params,
+                                                                    )
+                                                                })
+                                                            }
+                                                            Failed => Failed,
+                                                        }
+                                                    }
+                                                    Failed => Failed,
+                                                }
+                                            }
+                                            Failed => Failed,
+                                        }
+                                    }
+                                    Failed => Failed,
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                    Failed => Failed,
+                }
+            }
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let start_pos = pos;
+                {
+                    let seq_res = parse_QSTRING::<F>(input, state, pos);
+                    match seq_res {
+                        Matched(pos, _) => {
+                            let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                                Matched(newpos, value) => Matched(newpos, Some(value)),
+                                Failed => Matched(pos, None),
+                            };
+                            match seq_res {
+                                Matched(pos, name) => {
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, { F::new_qstring("\"\"", name, None) })
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+            }
+        }
+    }
+}
+fn parse_parser_QSTRING_optional_params<'input, F: ParserFactory>(input: &'input str,
+                                                                  state: &mut ParseState<'input>,
+                                                                  pos: usize)
+                                                                  -> RuleResult<Vec<OptionalParameter<'input>>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_comma::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    let seq_res = {
+                        let mut repeat_pos = pos;
+                        let mut repeat_value = vec![];
+                        loop {
+                            let pos = repeat_pos;
+                            let pos = if !repeat_value.is_empty() {
+                                let sep_res = parse_comma::<F>(input, state, pos);
+                                match sep_res {
+                                    Matched(newpos, _) => newpos,
+                                    Failed => break,
+                                }
+                            } else {
+                                pos
+                            };
+                            let step_res =
+                                parse_parser_QSTRING_optional_param::<F>(input, state, pos);
+                            match step_res {
+                                Matched(newpos, value) => {
+                                    repeat_pos = newpos;
+                                    repeat_value.push(value);
+                                }
+                                Failed => {
+                                    break;
+                                }
+                            }
+                        }
+                        Matched(repeat_pos, repeat_value)
+                    };
+                    match seq_res {
+                        Matched(pos, params) => {
+                            let match_str = &input[start_pos..pos];
+                            Matched(pos, { params })
+                        }
+                        Failed => Failed,
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_QSTRING_optional_param<'input, F: ParserFactory>(input: &'input str,
+                                                                 state: &mut ParseState<'input>,
+                                                                 pos: usize)
+                                                                 -> RuleResult<OptionalParameter<'input>> {
+    {
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = parse_ESCAPE::<F>(input, state, pos);
+                match seq_res {
+                    Matched(pos, name) => {
+                        let seq_res = slice_eq(input, state, pos, "=");
+                        match seq_res {
+                            Matched(pos, _) => {
+                                let seq_res = parse_escape_character::<F>(input, state, pos);
+                                match seq_res {
+                                    Matched(pos, value) => {
+                                        let match_str = &input[start_pos..pos];
+                                        Matched(pos, { OptionalParameter::Str(name, value) })
+                                    }
+                                    Failed => Failed,
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                    Failed => Failed,
+                }
+            }
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let start_pos = pos;
+                {
+                    let seq_res = parse_UNESCAPE::<F>(input, state, pos);
+                    match seq_res {
+                        Matched(pos, name) => {
+                            let seq_res = slice_eq(input, state, pos, "=");
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    let seq_res = parse_bool::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, value) => {
+                                            let match_str = &input[start_pos..pos];
+                                            Matched(pos, { OptionalParameter::Bool(name, value) })
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+            }
+        }
+    }
+}
+fn parse_escape_character<'input, F: ParserFactory>(input: &'input str,
+                                                     state: &mut ParseState<'input>,
+                                                     pos: usize)
+                                                     -> RuleResult<String> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_escaped_string::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, value) => {
+                    let match_str = &input[start_pos..pos];
+                    match {
+                        if value.chars().count() == 1 {
+                            Ok(value)
+                        } else {
+                            Err("single escape character")
+                        }
+                    } {
+                        Ok(res) => Matched(pos, res),
+                        Err(expected) => {
+                            state.mark_failure(pos, expected);
+                            Failed
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_TIMESTAMP<'input, F: ParserFactory>(input: &'input str,
+                                                    state: &mut ParseState<'input>,
+                                                    pos: usize)
+                                                    -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_TIMESTAMP::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    let seq_res =
+                        match parse_parser_TIMESTAMP_optional_params::<F>(input, state, pos) {
+                            Matched(newpos, value) => Matched(newpos, Some(value)),
+                            Failed => Matched(pos, None),
+                        };
+                    match seq_res {
+                        Matched(pos, opt_params) => {
+                            let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                                Matched(newpos, value) => Matched(newpos, Some(value)),
+                                Failed => Matched(pos, None),
+                            };
+                            match seq_res {
+                                Matched(pos, name) => {
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, { F::new_timestamp(name, opt_params) })
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_TIMESTAMP_optional_params<'input, F: ParserFactory>(input: &'input str,
+                                                                    state: &mut ParseState<'input>,
+                                                                    pos: usize)
+                                                                    -> RuleResult<Vec<OptionalParameter<'input>>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_PARSER_PARAMS_BEGIN::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    let seq_res = {
+                        let mut repeat_pos = pos;
+                        let mut repeat_value = vec![];
+                        loop {
+                            let pos = repeat_pos;
+                            let pos = if !repeat_value.is_empty() {
+                                let sep_res = parse_comma::<F>(input, state, pos);
+                                match sep_res {
+                                    Matched(newpos, _) => newpos,
+                                    Failed => break,
+                                }
+                            } else {
+                                pos
+                            };
+                            let step_res =
+                                parse_parser_TIMESTAMP_optional_param::<F>(input, state, pos);
+                            match step_res {
+                                Matched(newpos, value) => {
+                                    repeat_pos = newpos;
+                                    repeat_value.push(value);
+                                }
+                                Failed => {
+                                    break;
+                                }
+                            }
+                        }
+                        Matched(repeat_pos, repeat_value)
+                    };
+                    match seq_res {
+                        Matched(pos, params) => {
+                            let seq_res = parse_PARSER_PARAMS_END::<F>(input, state, pos);
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, { params })
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_TIMESTAMP_optional_param<'input, F: ParserFactory>(input: &'input str,
+                                                                   state: &mut ParseState<'input>,
+                                                                   pos: usize)
+                                                                   -> RuleResult<OptionalParameter<'input>> {
+    {
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = parse_FORMAT::<F>(input, state, pos);
+                match seq_res {
+                    Matched(pos, name) => {
+                        let seq_res = slice_eq(input, state, pos, "=");
+                        match seq_res {
+                            Matched(pos, _) => {
+                                let seq_res = parse_timestamp_format::<F>(input, state, pos);
+                                match seq_res {
+                                    Matched(pos, value) => {
+                                        let match_str = &input[start_pos..pos];
+                                        Matched(pos, { OptionalParameter::Str(name, value) })
+                                    }
+                                    Failed => Failed,
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                    Failed => Failed,
+                }
+            }
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let start_pos = pos;
+                {
+                    let seq_res = parse_NORMALIZE::<F>(input, state, pos);
+                    match seq_res {
+                        Matched(pos, name) => {
+                            let seq_res = slice_eq(input, state, pos, "=");
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    let seq_res = parse_bool::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, value) => {
+                                            let match_str = &input[start_pos..pos];
+                                            Matched(pos, { OptionalParameter::Bool(name, value) })
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+            }
+        }
+    }
+}
+fn parse_timestamp_format<'input, F: ParserFactory>(input: &'input str,
+                                                    state: &mut ParseState<'input>,
+                                                    pos: usize)
+                                                    -> RuleResult<String> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_escaped_string::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, value) => {
+                    let match_str = &input[start_pos..pos];
+                    match {
+                        if TimestampParser::is_valid_format(&value) {
+                            Ok(value)
+                        } else {
+                            Err("valid timestamp format")
+                        }
+                    } {
+                        Ok(res) => Matched(pos, res),
+                        Err(expected) => {
+                            state.mark_failure(pos, expected);
+                            Failed
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_REGEX<'input, F: ParserFactory>(input: &'input str,
+                                                state: &mut ParseState<'input>,
+                                                pos: usize)
+                                                -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_REGEX::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    let seq_res = parse_PARSER_PARAMS_BEGIN::<F>(input, state, pos);
+                    match seq_res {
+                        Matched(pos, _) => {
+                            let seq_res = parse_escaped_string::<F>(input, state, pos);
+                            match seq_res {
+                                Matched(pos, regex) => {
+                                    let seq_res = parse_PARSER_PARAMS_END::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, _) => {
+                                            let seq_res =
+                                                match parse_parser_name::<F>(input, state, pos) {
+                                                    Matched(newpos, value) => {
+                                                        Matched(newpos, Some(value))
+                                                    }
+                                                    Failed => Matched(pos, None),
+                                                };
+                                            match seq_res {
+                                                Matched(pos, name) => {
+                                                    let match_str = &input[start_pos..pos];
+                                                    match {
+                                                        F::new_regex(&regex, name)
+                                                            .map_err(|_| "valid regular expression")
+                                                    } {
+                                                        Ok(res) => Matched(pos, res),
+                                                        Err(expected) => {
+                                                            state.mark_failure(pos, expected);
+                                                            Failed
+                                                        }
+                                                    }
+                                                }
+                                                Failed => Failed,
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_KV<'input, F: ParserFactory>(input: &'input str,
+                                             state: &mut ParseState<'input>,
+                                             pos: usize)
+                                             -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_KV::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    let seq_res = match parse_parser_KV_optional_params::<F>(input, state, pos) {
+                        Matched(newpos, value) => Matched(newpos, Some(value)),
+                        Failed => Matched(pos, None),
+                    };
+                    match seq_res {
+                        Matched(pos, opt_params) => {
+                            let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                                Matched(newpos, value) => Matched(newpos, Some(value)),
+                                Failed => Matched(pos, None),
+                            };
+                            match seq_res {
+                                Matched(pos, name) => {
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, { F::new_kv(name, opt_params) })
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_KV_optional_params<'input, F: ParserFactory>(input: &'input str,
+                                                             state: &mut ParseState<'input>,
+                                                             pos: usize)
+                                                             -> RuleResult<Vec<OptionalParameter<'input>>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_PARSER_PARAMS_BEGIN::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    let seq_res = {
+                        let mut repeat_pos = pos;
+                        let mut repeat_value = vec![];
+                        loop {
+                            let pos = repeat_pos;
+                            let pos = if !repeat_value.is_empty() {
+                                let sep_res = parse_comma::<F>(input, state, pos);
+                                match sep_res {
+                                    Matched(newpos, _) => newpos,
+                                    Failed => break,
+                                }
+                            } else {
+                                pos
+                            };
+                            let step_res = parse_parser_KV_optional_param::<F>(input, state, pos);
+                            match step_res {
+                                Matched(newpos, value) => {
+                                    repeat_pos = newpos;
+                                    repeat_value.push(value);
+                                }
+                                Failed => {
+                                    break;
+                                }
+                            }
+                        }
+                        Matched(repeat_pos, repeat_value)
+                    };
+                    match seq_res {
+                        Matched(pos, params) => {
+                            let seq_res = parse_PARSER_PARAMS_END::<F>(input, state, pos);
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, { params })
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_KV_optional_param<'input, F: ParserFactory>(input: &'input str,
+                                                            state: &mut ParseState<'input>,
+                                                            pos: usize)
+                                                            -> RuleResult<OptionalParameter<'input>> {
+    {
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = parse_PAIR_SEPARATOR::<F>(input, state, pos);
+                match seq_res {
+                    Matched(pos, name) => {
+                        let seq_res = slice_eq(input, state, pos, "=");
+                        match seq_res {
+                            Matched(pos, _) => {
+                                let seq_res = parse_separator::<F>(input, state, pos);
+                                match seq_res {
+                                    Matched(pos, value) => {
+                                        let match_str = &input[start_pos..pos];
+                                        Matched(pos, { OptionalParameter::Str(name, value) })
+                                    }
+                                    Failed => Failed,
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                    Failed => Failed,
+                }
+            }
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let start_pos = pos;
+                {
+                    let seq_res = parse_VALUE_SEPARATOR::<F>(input, state, pos);
+                    match seq_res {
+                        Matched(pos, name) => {
+                            let seq_res = slice_eq(input, state, pos, "=");
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    let seq_res = parse_separator::<F>(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, value) => {
+                                            let match_str = &input[start_pos..pos];
+                                            Matched(pos, { OptionalParameter::Str(name, value) })
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+            }
+        }
+    }
+}
+fn parse_separator<'input, F: ParserFactory>(input: &'input str,
+                                              state: &mut ParseState<'input>,
+                                              pos: usize)
+                                              -> RuleResult<String> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_escaped_string::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, value) => {
+                    let match_str = &input[start_pos..pos];
+                    match {
+                        if value.is_empty() {
+                            Err("non-empty separator")
+                        } else {
+                            Ok(value)
+                        }
+                    } {
+                        Ok(res) => Matched(pos, res),
+                        Err(expected) => {
+                            state.mark_failure(pos, expected);
+                            Failed
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_IPV4<'input, F: ParserFactory>(input: &'input str,
+                                               state: &mut ParseState<'input>,
+                                               pos: usize)
+                                               -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_IPV4::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                        Matched(newpos, value) => Matched(newpos, Some(value)),
+                        Failed => Matched(pos, None),
+                    };
+                    match seq_res {
+                        Matched(pos, name) => {
+                            let match_str = &input[start_pos..pos];
+                            Matched(pos, { F::new_ipv4(name) })
+                        }
+                        Failed => Failed,
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_IPV6<'input, F: ParserFactory>(input: &'input str,
+                                               state: &mut ParseState<'input>,
+                                               pos: usize)
+                                               -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_IPV6::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                        Matched(newpos, value) => Matched(newpos, Some(value)),
+                        Failed => Matched(pos, None),
+                    };
+                    match seq_res {
+                        Matched(pos, name) => {
+                            let match_str = &input[start_pos..pos];
+                            Matched(pos, { F::new_ipv6(name) })
+                        }
+                        Failed => Failed,
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_IPADDR<'input, F: ParserFactory>(input: &'input str,
+                                                 state: &mut ParseState<'input>,
+                                                 pos: usize)
+                                                 -> RuleResult<Box<Parser>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_IPADDR::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                        Matched(newpos, value) => Matched(newpos, Some(value)),
+                        Failed => Matched(pos, None),
+                    };
+                    match seq_res {
+                        Matched(pos, name) => {
+                            let match_str = &input[start_pos..pos];
+                            Matched(pos, { F::new_ipaddr(name) })
+                        }
+                        Failed => Failed,
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_GREEDY<'input, F: ParserFactory>(input: &'input str,
+                                                 state: &mut ParseState<'input>,
+                                                 pos: usize)
+                                                 -> RuleResult<Vec<TokenType>> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = parse_PARSER_BEGIN::<F>(input, state, pos);
+            match seq_res {
+                Matched(pos, _) => {
+                    {
+                        let seq_res = parse_GREEDY::<F>(input, state, pos);
+                        match seq_res {
+                            Matched(pos, _) => {
+                                {
+                                    let seq_res = match parse_parser_name::<F>(input, state, pos) {
+                                        Matched(newpos, value) => {
+                                            Matched(newpos, Some(value))
+                                        }
+                                        Failed => {
+                                            Matched(pos, None)
+                                        }
+                                    };
+                                    match seq_res {
+                                        Matched(pos, name) => {
+                                            {
+                                                let seq_res = parse_PARSER_END::<F>(input,
+                                                                                    state,
+                                                                                    pos);
+                                                match seq_res {
+                                                    Matched(pos, _) => {
+                                                        {
+                                                            let seq_res =
+                                                                match parse_literal::<F>(input,
+                                                                                         state,
+                                                                                         pos) {
+                                                                    Matched(newpos, value) => {
+                                                                        Matched(newpos, Some(value))
+                                                                    }
+                                                                    Failed => {
+                                                                        Matched(pos, None)
+                                                                    }
+                                                                };
+                                                            match seq_res {
+                                                                Matched(pos, end_string) => {
+                                                                    {
+                                                                        let match_str =
+                                                                            &input[start_pos..pos];
+                                                                        Matched(pos,
+                                                                                {
+                                                                                    let mut tokens =
+                                                                                        Vec::new();
+                                                                                    let parser =
+                                                                                        F::new_greedy(name,
+                                                                                                      end_string);
+                                                                                    tokens.push(TokenType::Parser(parser));
+                                                                                    let end_string =
+                                                                                        end_string.map(|string|
+                                                                                                           string.to_owned());
+                                                                                    if let Some(end_string)
+                                                                                           =
+                                                                                           end_string
+                                                                                           {
+                                                                                        tokens.push(TokenType::Literal(end_string));
+                                                                                    }
+                                                                                    tokens
+                                                                                })
+                                                                    }
+                                                                }
+                                                                Failed => Failed,
+                                                            }
+                                                        }
+                                                    }
+                                                    Failed => Failed,
+                                                }
+                                            }
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_parser_BASE_optional_param<'input, F: ParserFactory>
+                                                              (input: &'input str,
+                                                               state: &mut ParseState<'input>,
+                                                               pos: usize)
+                                                               -> RuleResult<OptionalParameter<'input>> {
+    {
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = parse_MIN_LEN::<F>(input, state, pos);
+                match seq_res {
+                    Matched(pos, name) => {
+                        {
+                            let seq_res = slice_eq(input, state, pos, "=");
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    {
+                                        let seq_res = parse_int::<F>(input, state, pos);
+                                        match seq_res {
+                                            Matched(pos, value) => {
+                                                {
+                                                    let match_str = &input[start_pos..pos];
+                                                    Matched(pos, {
+                                                        OptionalParameter::Int(name, value)
+                                                    })
+                                                }
+                                            }
+                                            Failed => Failed,
+                                        }
+                                    }
                                 }
                                 Failed => Failed,
                             }
@@ -757,6 +1916,150 @@ fn parse_MAX_LEN<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
+fn parse_MIN<'input, F: ParserFactory>(input: &'input str,
+                                       state: &mut ParseState<'input>,
+                                       pos: usize)
+                                       -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "min");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_MAX<'input, F: ParserFactory>(input: &'input str,
+                                       state: &mut ParseState<'input>,
+                                       pos: usize)
+                                       -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "max");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_ESCAPE<'input, F: ParserFactory>(input: &'input str,
+                                          state: &mut ParseState<'input>,
+                                          pos: usize)
+                                          -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "escape");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_UNESCAPE<'input, F: ParserFactory>(input: &'input str,
+                                            state: &mut ParseState<'input>,
+                                            pos: usize)
+                                            -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "unescape");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_FORMAT<'input, F: ParserFactory>(input: &'input str,
+                                          state: &mut ParseState<'input>,
+                                          pos: usize)
+                                          -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "format");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_NORMALIZE<'input, F: ParserFactory>(input: &'input str,
+                                             state: &mut ParseState<'input>,
+                                             pos: usize)
+                                             -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "normalize");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_PAIR_SEPARATOR<'input, F: ParserFactory>(input: &'input str,
+                                                  state: &mut ParseState<'input>,
+                                                  pos: usize)
+                                                  -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "pair_separator");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_VALUE_SEPARATOR<'input, F: ParserFactory>(input: &'input str,
+                                                   state: &mut ParseState<'input>,
+                                                   pos: usize)
+                                                   -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "value_separator");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
 fn parse_INT<'input, F: ParserFactory>(input: &'input str,
                                        state: &mut ParseState<'input>,
                                        pos: usize)
@@ -823,12 +2126,198 @@ fn parse_GREEDY<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
+fn parse_NUMBER<'input, F: ParserFactory>(input: &'input str,
+                                          state: &mut ParseState<'input>,
+                                          pos: usize)
+                                          -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "NUMBER");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_FLOAT<'input, F: ParserFactory>(input: &'input str,
+                                         state: &mut ParseState<'input>,
+                                         pos: usize)
+                                         -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "FLOAT");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_QSTRING<'input, F: ParserFactory>(input: &'input str,
+                                           state: &mut ParseState<'input>,
+                                           pos: usize)
+                                           -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "QSTRING");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_TIMESTAMP<'input, F: ParserFactory>(input: &'input str,
+                                             state: &mut ParseState<'input>,
+                                             pos: usize)
+                                             -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "TIMESTAMP");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_REGEX<'input, F: ParserFactory>(input: &'input str,
+                                         state: &mut ParseState<'input>,
+                                         pos: usize)
+                                         -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "REGEX");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_KV<'input, F: ParserFactory>(input: &'input str,
+                                      state: &mut ParseState<'input>,
+                                      pos: usize)
+                                      -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "KV");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_IPV4<'input, F: ParserFactory>(input: &'input str,
+                                        state: &mut ParseState<'input>,
+                                        pos: usize)
+                                        -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "IPV4");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_IPV6<'input, F: ParserFactory>(input: &'input str,
+                                        state: &mut ParseState<'input>,
+                                        pos: usize)
+                                        -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "IPV6");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_IPADDR<'input, F: ParserFactory>(input: &'input str,
+                                          state: &mut ParseState<'input>,
+                                          pos: usize)
+                                          -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "IPADDR");
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
 fn parse_PARSER_BEGIN<'input, F: ParserFactory>(input: &'input str,
                                                 state: &mut ParseState<'input>,
                                                 pos: usize)
                                                 -> RuleResult<()> {
     slice_eq(input, state, pos, "%{")
 }
+fn parse_GROUP_BEGIN<'input, F: ParserFactory>(input: &'input str,
+                                               state: &mut ParseState<'input>,
+                                               pos: usize)
+                                               -> RuleResult<()> {
+    slice_eq(input, state, pos, "(")
+}
+fn parse_GROUP_END<'input, F: ParserFactory>(input: &'input str,
+                                             state: &mut ParseState<'input>,
+                                             pos: usize)
+                                             -> RuleResult<()> {
+    slice_eq(input, state, pos, ")")
+}
+fn parse_GROUP_SEPARATOR<'input, F: ParserFactory>(input: &'input str,
+                                                   state: &mut ParseState<'input>,
+                                                   pos: usize)
+                                                   -> RuleResult<()> {
+    slice_eq(input, state, pos, "|")
+}
+fn parse_GROUP_OPTIONAL<'input, F: ParserFactory>(input: &'input str,
+                                                  state: &mut ParseState<'input>,
+                                                  pos: usize)
+                                                  -> RuleResult<()> {
+    slice_eq(input, state, pos, "?")
+}
 fn parse_PARSER_END<'input, F: ParserFactory>(input: &'input str,
                                               state: &mut ParseState<'input>,
                                               pos: usize)
@@ -1003,6 +2492,141 @@ fn parse_string<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
+fn parse_escaped_string<'input, F: ParserFactory>(input: &'input str,
+                                                  state: &mut ParseState<'input>,
+                                                  pos: usize)
+                                                  -> RuleResult<String> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = slice_eq(input, state, pos, "\"");
+            match seq_res {
+                Matched(pos, _) => {
+                    let seq_res = {
+                        let mut repeat_pos = pos;
+                        let mut repeat_value = vec![];
+                        loop {
+                            let pos = repeat_pos;
+                            let step_res = parse_escaped_string_char::<F>(input, state, pos);
+                            match step_res {
+                                Matched(newpos, value) => {
+                                    repeat_pos = newpos;
+                                    repeat_value.push(value);
+                                }
+                                Failed => {
+                                    break;
+                                }
+                            }
+                        }
+                        Matched(repeat_pos, repeat_value)
+                    };
+                    match seq_res {
+                        Matched(pos, chars) => {
+                            let seq_res = slice_eq(input, state, pos, "\"");
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, { chars.into_iter().collect() })
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_escaped_string_char<'input, F: ParserFactory>(input: &'input str,
+                                                       state: &mut ParseState<'input>,
+                                                       pos: usize)
+                                                       -> RuleResult<char> {
+    {
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = slice_eq(input, state, pos, "\\");
+                match seq_res {
+                    Matched(pos, _) => {
+                        let seq_res = if input.len() > pos {
+                            let (ch, next) = char_range_at(input, pos);
+                            match ch {
+                                '"' | '\\' => Matched(next, ()),
+                                _ => state.mark_failure(pos, "[\"\\\\]"),
+                            }
+                        } else {
+                            state.mark_failure(pos, "[\"\\\\]")
+                        };
+                        match seq_res {
+                            Matched(pos, _) => {
+                                let match_str = &input[start_pos..pos];
+                                Matched(pos, { match_str.chars().nth(1).unwrap() })
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                    Failed => Failed,
+                }
+            }
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let choice_res = {
+                    let start_pos = pos;
+                    {
+                        let seq_res = {
+                            let assert_res = if input.len() > pos {
+                                let (ch, next) = char_range_at(input, pos);
+                                match ch {
+                                    '"' | '\\' => Matched(next, ()),
+                                    _ => state.mark_failure(pos, "[\"\\\\]"),
+                                }
+                            } else {
+                                state.mark_failure(pos, "[\"\\\\]")
+                            };
+                            match assert_res {
+                                Failed => Matched(pos, ()),
+                                Matched(..) => Failed,
+                            }
+                        };
+                        match seq_res {
+                            Matched(pos, _) => {
+                                let seq_res = any_char(input, state, pos);
+                                match seq_res {
+                                    Matched(pos, _) => {
+                                        let match_str = &input[start_pos..pos];
+                                        Matched(pos, { match_str.chars().next().unwrap() })
+                                    }
+                                    Failed => Failed,
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                };
+                match choice_res {
+                    Matched(pos, value) => Matched(pos, value),
+                    Failed => {
+                        let start_pos = pos;
+                        {
+                            let seq_res = slice_eq(input, state, pos, "\\");
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, { '\\' })
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
 fn parse_literal<'input, F: ParserFactory>(input: &'input str,
                                            state: &mut ParseState<'input>,
                                            pos: usize)
@@ -1060,6 +2684,99 @@ fn parse_literal<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
+fn parse_group_literal_chars<'input, F: ParserFactory>(input: &'input str,
+                                                       state: &mut ParseState<'input>,
+                                                       pos: usize)
+                                                       -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = {
+                let mut repeat_pos = pos;
+                let mut repeat_value = vec![];
+                loop {
+                    let pos = repeat_pos;
+                    let step_res = {
+                        let choice_res = {
+                            let seq_res = slice_eq(input, state, pos, "\\");
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    if input.len() > pos {
+                                        let (ch, next) = char_range_at(input, pos);
+                                        match ch {
+                                            '|' | ')' => Matched(next, ()),
+                                            _ => state.mark_failure(pos, "[|)]"),
+                                        }
+                                    } else {
+                                        state.mark_failure(pos, "[|)]")
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        };
+                        match choice_res {
+                            Matched(pos, value) => Matched(pos, value),
+                            Failed => {
+                                let seq_res = {
+                                    let assert_res = parse_PARSER_BEGIN::<F>(input, state, pos);
+                                    match assert_res {
+                                        Failed => Matched(pos, ()),
+                                        Matched(..) => Failed,
+                                    }
+                                };
+                                match seq_res {
+                                    Matched(pos, _) => {
+                                        let seq_res = {
+                                            let assert_res = if input.len() > pos {
+                                                let (ch, next) = char_range_at(input, pos);
+                                                match ch {
+                                                    '|' | ')' => Matched(next, ()),
+                                                    _ => state.mark_failure(pos, "[|)]"),
+                                                }
+                                            } else {
+                                                state.mark_failure(pos, "[|)]")
+                                            };
+                                            match assert_res {
+                                                Failed => Matched(pos, ()),
+                                                Matched(..) => Failed,
+                                            }
+                                        };
+                                        match seq_res {
+                                            Matched(pos, _) => any_char(input, state, pos),
+                                            Failed => Failed,
+                                        }
+                                    }
+                                    Failed => Failed,
+                                }
+                            }
+                        }
+                    };
+                    match step_res {
+                        Matched(newpos, value) => {
+                            repeat_pos = newpos;
+                            repeat_value.push(value);
+                        }
+                        Failed => {
+                            break;
+                        }
+                    }
+                }
+                if repeat_value.len() >= 1usize {
+                    Matched(repeat_pos, ())
+                } else {
+                    Failed
+                }
+            };
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
 fn parse_all_chars_until_quotation_mark<'input, F: ParserFactory>(input: &'input str,
                                                                   state: &mut ParseState<'input>,
                                                                   pos: usize)
@@ -1146,6 +2863,42 @@ fn parse_comma<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
+fn parse_bool<'input, F: ParserFactory>(input: &'input str,
+                                        state: &mut ParseState<'input>,
+                                        pos: usize)
+                                        -> RuleResult<bool> {
+    {
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = slice_eq(input, state, pos, "true");
+                match seq_res {
+                    Matched(pos, _) => {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, { true })
+                    }
+                    Failed => Failed,
+                }
+            }
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let start_pos = pos;
+                {
+                    let seq_res = slice_eq(input, state, pos, "false");
+                    match seq_res {
+                        Matched(pos, _) => {
+                            let match_str = &input[start_pos..pos];
+                            Matched(pos, { false })
+                        }
+                        Failed => Failed,
+                    }
+                }
+            }
+        }
+    }
+}
 fn parse_int<'input, F: ParserFactory>(input: &'input str,
                                        state: &mut ParseState<'input>,
                                        pos: usize)
@@ -1197,6 +2950,187 @@ fn parse_int<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
+fn parse_float<'input, F: ParserFactory>(input: &'input str,
+                                         state: &mut ParseState<'input>,
+                                         pos: usize)
+                                         -> RuleResult<f64> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = match if input.len() > pos {
+                let (ch, next) = char_range_at(input, pos);
+                match ch {
+                    '+' | '-' => Matched(next, ()),
+                    _ => state.mark_failure(pos, "[+-]"),
+                }
+            } else {
+                state.mark_failure(pos, "[+-]")
+            } {
+                Matched(newpos, value) => Matched(newpos, Some(value)),
+                Failed => Matched(pos, None),
+            };
+            match seq_res {
+                Matched(pos, _) => {
+                    let seq_res = {
+                        let mut repeat_pos = pos;
+                        let mut repeat_value = vec![];
+                        loop {
+                            let pos = repeat_pos;
+                            let step_res = if input.len() > pos {
+                                let (ch, next) = char_range_at(input, pos);
+                                match ch {
+                                    '0'...'9' => Matched(next, ()),
+                                    _ => state.mark_failure(pos, "[0-9]"),
+                                }
+                            } else {
+                                state.mark_failure(pos, "[0-9]")
+                            };
+                            match step_res {
+                                Matched(newpos, value) => {
+                                    repeat_pos = newpos;
+                                    repeat_value.push(value);
+                                }
+                                Failed => {
+                                    break;
+                                }
+                            }
+                        }
+                        if repeat_value.len() >= 1usize {
+                            Matched(repeat_pos, ())
+                        } else {
+                            Failed
+                        }
+                    };
+                    match seq_res {
+                        Matched(pos, _) => {
+                            let seq_res = match {
+                                let seq_res = slice_eq(input, state, pos, ".");
+                                match seq_res {
+                                    Matched(pos, _) => {
+                                        let mut repeat_pos = pos;
+                                        let mut repeat_value = vec![];
+                                        loop {
+                                            let pos = repeat_pos;
+                                            let step_res = if input.len() > pos {
+                                                let (ch, next) = char_range_at(input, pos);
+                                                match ch {
+                                                    '0'...'9' => Matched(next, ()),
+                                                    _ => state.mark_failure(pos, "[0-9]"),
+                                                }
+                                            } else {
+                                                state.mark_failure(pos, "[0-9]")
+                                            };
+                                            match step_res {
+                                                Matched(newpos, value) => {
+                                                    repeat_pos = newpos;
+                                                    repeat_value.push(value);
+                                                }
+                                                Failed => {
+                                                    break;
+                                                }
+                                            }
+                                        }
+                                        if repeat_value.len() >= 1usize {
+                                            Matched(repeat_pos, ())
+                                        } else {
+                                            Failed
+                                        }
+                                    }
+                                    Failed => Failed,
+                                }
+                            } {
+                                Matched(newpos, value) => Matched(newpos, Some(value)),
+                                Failed => Matched(pos, None),
+                            };
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    let seq_res = match {
+                                        let seq_res = if input.len() > pos {
+                                            let (ch, next) = char_range_at(input, pos);
+                                            match ch {
+                                                'e' | 'E' => Matched(next, ()),
+                                                _ => state.mark_failure(pos, "[eE]"),
+                                            }
+                                        } else {
+                                            state.mark_failure(pos, "[eE]")
+                                        };
+                                        match seq_res {
+                                            Matched(pos, _) => {
+                                                let seq_res = match if input.len() > pos {
+                                                    let (ch, next) = char_range_at(input, pos);
+                                                    match ch {
+                                                        '+' | '-' => Matched(next, ()),
+                                                        _ => state.mark_failure(pos, "[+-]"),
+                                                    }
+                                                } else {
+                                                    state.mark_failure(pos, "[+-]")
+                                                } {
+                                                    Matched(newpos, value) => {
+                                                        Matched(newpos, Some(value))
+                                                    }
+                                                    Failed => Matched(pos, None),
+                                                };
+                                                match seq_res {
+                                                    Matched(pos, _) => {
+                                                        let mut repeat_pos = pos;
+                                                        let mut repeat_value = vec![];
+                                                        loop {
+                                                            let pos = repeat_pos;
+                                                            let step_res = if input.len() > pos {
+                                                                let (ch, next) =
+                                                                    char_range_at(input, pos);
+                                                                match ch {
+                                                                    '0'...'9' => Matched(next, ()),
+                                                                    _ => state
+                                                                        .mark_failure(pos, "[0-9]"),
+                                                                }
+                                                            } else {
+                                                                state.mark_failure(pos, "[0-9]")
+                                                            };
+                                                            match step_res {
+                                                                Matched(newpos, value) => {
+                                                                    repeat_pos = newpos;
+                                                                    repeat_value.push(value);
+                                                                }
+                                                                Failed => {
+                                                                    break;
+                                                                }
+                                                            }
+                                                        }
+                                                        if repeat_value.len() >= 1usize {
+                                                            Matched(repeat_pos, ())
+                                                        } else {
+                                                            Failed
+                                                        }
+                                                    }
+                                                    Failed => Failed,
+                                                }
+                                            }
+                                            Failed => Failed,
+                                        }
+                                    } {
+                                        Matched(newpos, value) => Matched(newpos, Some(value)),
+                                        Failed => Matched(pos, None),
+                                    };
+                                    match seq_res {
+                                        Matched(pos, _) => {
+                                            let match_str = &input[start_pos..pos];
+                                            Matched(pos, { f64::from_str(match_str).ok().unwrap() })
+                                        }
+                                        Failed => Failed,
+                                    }
+                                }
+                                Failed => Failed,
+                            }
+                        }
+                        Failed => Failed,
+                    }
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
 pub fn pattern<F: ParserFactory>(input: &str) -> ParseResult<CompiledPattern> {
     let mut state = ParseState::new();
     if let Matched(pos, value) = parse_pattern::<F>(input, &mut state, 0) {
@@ -1,5 +1,7 @@
-use matcher::compiled_pattern::TokenType;
-use parsers::{SetParser, Parser, IntParser, GreedyParser, HasLengthConstraint};
+use matcher::compiled_pattern::{self, TokenType};
+use parsers::{SetParser, Parser, IntParser, GreedyParser, HasLengthConstraint, Ipv4Parser, Ipv6Parser,
+              IpAddrParser, NumberParser, HasValueConstraint, QStringParser,
+              TimestampParser, RegexParser, KvParser};
 
 fn assert_parser_name_equals(item: Option<&TokenType>, expected_name: Option<&str>) {
     if let Some(&TokenType::Parser(ref parser)) = item {
@@ -210,3 +212,217 @@ fn test_given_valid_pattern_when_it_does_not_have_a_name_then_we_can_parse_the_p
     println!("{:?}", &vec);
     assert_parser_name_equals(vec.get(0), None);
 }
+
+#[test]
+fn test_given_ip_address_parsers_when_we_parse_them_then_we_get_the_right_parsers() {
+    let pattern_as_string = "from %{IPV4:v4} %{IPV6:v6} %{IPADDR:ip} %{IPV4}";
+    let vec: Vec<TokenType> = ::grammar::parser::pattern(pattern_as_string).ok().unwrap();
+
+    assert_eq!(vec.len(), 8);
+    assert_literal_equals(vec.get(0), "from ");
+    assert_parser_equals(vec.get(1), &Ipv4Parser::with_name("v4"));
+    assert_parser_equals(vec.get(3), &Ipv6Parser::with_name("v6"));
+    assert_parser_equals(vec.get(5), &IpAddrParser::with_name("ip"));
+    assert_parser_name_equals(vec.get(7), None);
+}
+
+#[test]
+fn test_given_number_parser_with_value_constraints_when_we_parse_it_then_we_get_the_right_parser() {
+    let mut expected_parser = NumberParser::with_name("latency");
+    expected_parser.set_min_value(Some(-0.5));
+    expected_parser.set_max_value(Some(1.5e3));
+
+    let vec = ::grammar::parser::pattern("%{NUMBER(min=-0.5, max=1.5e3):latency}").ok().unwrap();
+    assert_eq!(vec.len(), 1);
+    assert_parser_equals(vec.get(0), &expected_parser);
+}
+
+#[test]
+fn test_given_float_parser_when_we_parse_it_then_we_get_a_number_parser() {
+    let vec = ::grammar::parser::pattern("%{FLOAT:value} %{NUMBER}").ok().unwrap();
+    assert_eq!(vec.len(), 3);
+    assert_parser_equals(vec.get(0), &NumberParser::with_name("value"));
+    assert_parser_equals(vec.get(2), &NumberParser::new());
+}
+
+#[test]
+fn test_given_number_parser_when_its_value_constraint_is_not_a_number_then_we_get_an_error() {
+    ::grammar::parser::pattern("%{NUMBER(min=abc)}").err().unwrap();
+    ::grammar::parser::pattern("%{NUMBER(min_len=2)}").err().unwrap();
+}
+
+#[test]
+fn test_given_qstring_parser_with_quotes_and_escape_character_when_we_parse_it_then_we_get_the_right_parser
+    () {
+    let mut expected_parser = QStringParser::with_name("user", "\"\"[]");
+    expected_parser.set_escape_character(Some('\\'));
+    expected_parser.set_unescape(false);
+
+    let vec = ::grammar::parser::pattern(r#"user=%{QSTRING("\"\"[]", escape="\\", unescape=false):user}"#)
+                  .ok()
+                  .unwrap();
+    assert_eq!(vec.len(), 2);
+    assert_literal_equals(vec.get(0), "user=");
+    assert_parser_equals(vec.get(1), &expected_parser);
+}
+
+#[test]
+fn test_given_qstring_parser_when_its_escape_is_not_a_single_character_then_we_get_an_error() {
+    let error = ::grammar::parser::pattern(r#"%{QSTRING("''", escape="ab"):q}"#).err().unwrap();
+    assert_eq!(error.expected.contains("single escape character"), true);
+    assert!(::grammar::parser::pattern(r#"%{QSTRING("''", escape=""):q}"#).is_err());
+}
+
+#[test]
+fn test_given_qstring_parser_without_parameters_when_we_parse_it_then_it_uses_double_quotes() {
+    let vec = ::grammar::parser::pattern("%{QSTRING:user}").ok().unwrap();
+    assert_eq!(vec.len(), 1);
+    assert_parser_equals(vec.get(0), &QStringParser::with_name("user", "\"\""));
+}
+
+#[test]
+fn test_given_timestamp_parser_with_format_when_we_parse_it_then_we_get_the_right_parser() {
+    let mut expected_parser = TimestampParser::with_name("date", "%d/%b/%Y:%H:%M:%S %z");
+    expected_parser.set_normalize(true);
+
+    let vec = ::grammar::parser::pattern(r#"[%{TIMESTAMP(format="%d/%b/%Y:%H:%M:%S %z", normalize=true):date}]"#)
+                  .ok()
+                  .unwrap();
+    assert_eq!(vec.len(), 3);
+    assert_parser_equals(vec.get(1), &expected_parser);
+}
+
+#[test]
+fn test_given_timestamp_parser_when_its_format_is_invalid_then_we_get_an_error() {
+    let error = ::grammar::parser::pattern(r#"%{TIMESTAMP(format="%Y-%Q"):date}"#).err().unwrap();
+    assert_eq!(error.expected.contains("valid timestamp format"), true);
+    assert!(::grammar::parser::pattern(r#"%{TIMESTAMP(format=""):date}"#).is_err());
+    assert!(::grammar::parser::pattern(r#"%{TIMESTAMP(format="epoch_millis"):date}"#).is_ok());
+}
+
+#[test]
+fn test_given_timestamp_parser_without_parameters_when_we_parse_it_then_it_uses_the_iso8601_preset() {
+    let vec = ::grammar::parser::pattern("%{TIMESTAMP:date}").ok().unwrap();
+    assert_eq!(vec.len(), 1);
+    assert_parser_equals(vec.get(0), &TimestampParser::with_name("date", "iso8601"));
+}
+
+#[test]
+fn test_given_regex_parser_when_we_parse_it_then_we_get_the_right_parser() {
+    let expected_parser = RegexParser::with_name("session", r#"[a-f0-9]+-(?P<quoted>"\w+")\d"#).unwrap();
+
+    let vec = ::grammar::parser::pattern(r#"session %{REGEX("[a-f0-9]+-(?P<quoted>\"\\w+\")\d"):session}"#)
+                  .ok()
+                  .unwrap();
+    assert_eq!(vec.len(), 2);
+    assert_parser_equals(vec.get(1), &expected_parser);
+}
+
+#[test]
+fn test_given_regex_parser_when_its_regular_expression_is_invalid_then_we_get_an_error() {
+    let error = ::grammar::parser::pattern(r#"session %{REGEX("(foo"):session}"#).err().unwrap();
+    assert_eq!(error.expected.contains("valid regular expression"), true);
+}
+
+#[test]
+fn test_given_kv_parser_with_separators_when_we_parse_it_then_we_get_the_right_parser() {
+    let mut expected_parser = KvParser::with_name("fw");
+    expected_parser.set_pair_separator(", ");
+    expected_parser.set_value_separator(":");
+
+    let vec = ::grammar::parser::pattern(r#"fw: %{KV(pair_separator=", ", value_separator=":"):fw}"#)
+                  .ok()
+                  .unwrap();
+    assert_eq!(vec.len(), 2);
+    assert_parser_equals(vec.get(1), &expected_parser);
+    let vec = ::grammar::parser::pattern("%{KV}").ok().unwrap();
+    assert_parser_equals(vec.get(0), &KvParser::new());
+}
+
+#[test]
+fn test_given_kv_parser_when_a_separator_is_empty_then_we_get_an_error() {
+    let error = ::grammar::parser::pattern(r#"%{KV(pair_separator=""):kv}"#).err().unwrap();
+    assert_eq!(error.expected.contains("non-empty separator"), true);
+    assert!(::grammar::parser::pattern(r#"%{KV(value_separator=""):kv}"#).is_err());
+}
+
+#[test]
+fn test_given_pattern_when_its_groups_have_too_many_variants_then_we_get_an_error() {
+    let pattern = (0..11).map(|i| format!("%{{(a{}|b)}}", i)).collect::<String>();
+    let error = ::grammar::parser::pattern(&pattern).err().unwrap();
+    assert_eq!(error.expected.contains("pattern with at most 1024 variants"), true);
+    let pattern = (0..10).map(|i| format!("%{{(a{}|b)}}", i)).collect::<String>();
+    assert!(::grammar::parser::pattern(&pattern).is_ok());
+}
+
+#[test]
+fn test_given_compiled_pattern_when_it_has_too_many_variants_then_they_are_not_expanded() {
+    let group = ::grammar::parser::pattern("%{(a|b)}").unwrap();
+    let pattern = (0..11).flat_map(|_| group.clone()).collect::<Vec<_>>();
+    assert!(compiled_pattern::expand_alternatives(&pattern).is_err());
+    assert_eq!(compiled_pattern::expand_alternatives(&pattern[1..].to_vec()).unwrap().len(), 1024);
+}
+
+#[test]
+fn test_given_pattern_with_alternation_and_optional_group_when_we_parse_it_then_we_get_the_groups() {
+    let pattern = "Accepted %{(publickey|password)} for %{INT:user}%{( [preauth])?}";
+    let vec = ::grammar::parser::pattern(pattern).ok().unwrap();
+    assert_eq!(vec.len(), 5);
+    assert_literal_equals(vec.get(0), "Accepted ");
+    if let Some(&TokenType::Alternation(ref alternatives)) = vec.get(1) {
+        assert_eq!(alternatives.len(), 2);
+        assert_literal_equals(alternatives[0].get(0), "publickey");
+        assert_literal_equals(alternatives[1].get(0), "password");
+    } else {
+        unreachable!();
+    }
+    assert_literal_equals(vec.get(2), " for ");
+    assert_parser_name_equals(vec.get(3), Some("user"));
+    if let Some(&TokenType::Optional(ref optional)) = vec.get(4) {
+        assert_eq!(optional.len(), 1);
+        assert_literal_equals(optional.get(0), " [preauth]");
+    } else {
+        unreachable!();
+    }
+}
+
+#[test]
+fn test_given_group_with_parsers_and_escaped_characters_when_we_parse_it_then_we_get_the_alternatives
+    () {
+    let vec = ::grammar::parser::pattern(r"%{(pid=%{INT:pid}|a\|b\)|)?}").ok().unwrap();
+    assert_eq!(vec.len(), 1);
+    if let Some(&TokenType::Optional(ref optional)) = vec.get(0) {
+        if let Some(&TokenType::Alternation(ref alternatives)) = optional.get(0) {
+            assert_eq!(alternatives.len(), 3);
+            assert_literal_equals(alternatives[0].get(0), "pid=");
+            assert_parser_name_equals(alternatives[0].get(1), Some("pid"));
+            assert_literal_equals(alternatives[1].get(0), "a|b)");
+            assert_eq!(alternatives[2].is_empty(), true);
+            return;
+        }
+    }
+    unreachable!();
+}
+
+fn assert_pattern_is_formatted_back(pattern: &str) {
+    let vec = ::grammar::parser::pattern(pattern).ok().unwrap();
+    assert_eq!(::grammar::format_pattern(&vec), pattern);
+}
+
+#[test]
+fn test_given_pattern_when_it_is_formatted_then_we_get_back_the_original_pattern() {
+    assert_pattern_is_formatted_back("Accepted %{GREEDY:method} for %{GREEDY:user} from \
+                                      %{IPV4:ip} port %{INT(min_len=1, max_len=5):port}");
+    assert_pattern_is_formatted_back(r#"%{SET("abc", min_len=2):set} %{NUMBER(min=-1.5, max=10)}"#);
+    assert_pattern_is_formatted_back(r#"%{QSTRING("''", escape="\\", unescape=true):q}"#);
+    assert_pattern_is_formatted_back(r#"%{REGEX("[a-z]+\"x"):r}"#);
+    assert_pattern_is_formatted_back(r#"%{KV(pair_separator=" ", value_separator="="):kv}"#);
+    assert_pattern_is_formatted_back(r#"%{TIMESTAMP(format="%Y", normalize=false):ts} %{IPV6}"#);
+}
+
+#[test]
+fn test_given_pattern_with_groups_and_escaped_characters_when_it_is_formatted_then_we_get_back_the_original_pattern
+    () {
+    assert_pattern_is_formatted_back(r"literal \%\{ %{(pid=%{INT:pid}|a\|b\)|)?}");
+    assert_pattern_is_formatted_back(r"%{(Accepted|Failed)} password%{( for invalid user)?}");
+}
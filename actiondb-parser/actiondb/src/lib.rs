@@ -8,6 +8,9 @@ extern crate uuid;
 extern crate serde;
 extern crate serde_json;
 extern crate serde_yaml;
+extern crate chrono;
+extern crate regex;
+extern crate xml;
 
 pub mod parsers;
 pub mod utils;
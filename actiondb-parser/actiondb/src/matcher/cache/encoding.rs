@@ -0,0 +1,202 @@
+use super::CacheError;
+use uuid::Uuid;
+
+// Writes the values in a compact binary form. The integers are little
+// endian, the strings and sequences are prefixed with their lengths.
+#[derive(Default)]
+pub struct Encoder {
+    buffer: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new() -> Encoder {
+        Encoder::default()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buffer
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.buffer.push(value);
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8);
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        for i in 0..4 {
+            self.write_u8((value >> (i * 8)) as u8);
+        }
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        for i in 0..8 {
+            self.write_u8((value >> (i * 8)) as u8);
+        }
+    }
+
+    pub fn write_i32(&mut self, value: i32) {
+        self.write_u32(value as u32);
+    }
+
+    pub fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    pub fn write_f64(&mut self, value: f64) {
+        self.write_u64(value.to_bits());
+    }
+
+    pub fn write_str(&mut self, value: &str) {
+        self.write_usize(value.len());
+        self.write_bytes(value.as_bytes());
+    }
+
+    pub fn write_uuid(&mut self, value: &Uuid) {
+        self.write_bytes(value.as_bytes());
+    }
+
+    pub fn write_option<T, F>(&mut self, value: Option<T>, write: F)
+        where F: FnOnce(&mut Encoder, T)
+    {
+        match value {
+            Some(value) => {
+                self.write_bool(true);
+                write(self, value);
+            }
+            None => self.write_bool(false),
+        }
+    }
+}
+
+// Reads the values written by an Encoder. Every read fails with
+// CacheError::Corrupted if the buffer ends too early or the value is invalid.
+pub struct Decoder<'a> {
+    buffer: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(buffer: &'a [u8]) -> Decoder<'a> {
+        Decoder { buffer: buffer }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    pub fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], CacheError> {
+        if length > self.buffer.len() {
+            return Err(CacheError::Corrupted);
+        }
+        let (bytes, rest) = self.buffer.split_at(length);
+        self.buffer = rest;
+        Ok(bytes)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, CacheError> {
+        self.read_bytes(1).map(|bytes| bytes[0])
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, CacheError> {
+        match try!(self.read_u8()) {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(CacheError::Corrupted),
+        }
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, CacheError> {
+        let bytes = try!(self.read_bytes(4));
+        Ok(bytes.iter().rev().fold(0, |value, byte| (value << 8) | *byte as u32))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, CacheError> {
+        let bytes = try!(self.read_bytes(8));
+        Ok(bytes.iter().rev().fold(0, |value, byte| (value << 8) | *byte as u64))
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32, CacheError> {
+        self.read_u32().map(|value| value as i32)
+    }
+
+    pub fn read_usize(&mut self) -> Result<usize, CacheError> {
+        let value = try!(self.read_u64());
+        if value > usize::max_value() as u64 {
+            Err(CacheError::Corrupted)
+        } else {
+            Ok(value as usize)
+        }
+    }
+
+    pub fn read_f64(&mut self) -> Result<f64, CacheError> {
+        self.read_u64().map(f64::from_bits)
+    }
+
+    pub fn read_string(&mut self) -> Result<String, CacheError> {
+        let length = try!(self.read_usize());
+        let bytes = try!(self.read_bytes(length));
+        String::from_utf8(bytes.to_vec()).map_err(|_| CacheError::Corrupted)
+    }
+
+    pub fn read_uuid(&mut self) -> Result<Uuid, CacheError> {
+        let bytes = try!(self.read_bytes(16));
+        Uuid::from_bytes(bytes).map_err(|_| CacheError::Corrupted)
+    }
+
+    pub fn read_option<T, F>(&mut self, read: F) -> Result<Option<T>, CacheError>
+        where F: FnOnce(&mut Decoder<'a>) -> Result<T, CacheError>
+    {
+        if try!(self.read_bool()) {
+            read(self).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Encoder, Decoder};
+    use matcher::cache::CacheError;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_given_encoder_when_values_are_written_then_the_decoder_reads_them_back() {
+        let uuid = Uuid::parse_str("9a49c47d-29e9-4072-be84-3b76c6814743").unwrap();
+        let mut encoder = Encoder::new();
+        encoder.write_u32(0xdeadbeef);
+        encoder.write_i32(-42);
+        encoder.write_f64(-1.5);
+        encoder.write_str("árvíztűrő");
+        encoder.write_uuid(&uuid);
+        encoder.write_option(Some(7), |encoder, value| encoder.write_usize(value));
+        encoder.write_option(None, |encoder, value| encoder.write_usize(value));
+        let bytes = encoder.into_bytes();
+
+        let mut decoder = Decoder::new(&bytes);
+        assert_eq!(decoder.read_u32().unwrap(), 0xdeadbeef);
+        assert_eq!(decoder.read_i32().unwrap(), -42);
+        assert_eq!(decoder.read_f64().unwrap(), -1.5);
+        assert_eq!(decoder.read_string().unwrap(), "árvíztűrő");
+        assert_eq!(decoder.read_uuid().unwrap(), uuid);
+        assert_eq!(decoder.read_option(|decoder| decoder.read_usize()).unwrap(), Some(7));
+        assert_eq!(decoder.read_option(|decoder| decoder.read_usize()).unwrap(), None);
+        assert!(decoder.is_empty());
+    }
+
+    #[test]
+    fn test_given_decoder_when_the_buffer_is_truncated_then_it_fails() {
+        let mut encoder = Encoder::new();
+        encoder.write_str("truncated");
+        let bytes = encoder.into_bytes();
+
+        let mut decoder = Decoder::new(&bytes[..bytes.len() - 1]);
+        assert_eq!(decoder.read_string(), Err(CacheError::Corrupted));
+    }
+}
@@ -0,0 +1,67 @@
+use std::error;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CacheError {
+    // the file isn't a cache file or it's damaged
+    Corrupted,
+    UnsupportedVersion(u32),
+    // the cache was written by an other matcher suite (expected, found)
+    MatcherMismatch(String, String),
+    // the parser can't be created from its kind and parameters
+    InvalidParser(String),
+    // the cache was compiled from other pattern files
+    OtherSources,
+    // the pattern files were changed since the cache was written
+    Stale,
+    // the cache was written while the invalid patterns were skipped, but
+    // they aren't allowed now
+    ErrorsIgnored,
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            CacheError::Corrupted => formatter.write_str("The cache file is corrupted"),
+            CacheError::UnsupportedVersion(version) => {
+                write!(formatter, "The version of the cache file is not supported: version={}",
+                       version)
+            }
+            CacheError::MatcherMismatch(ref expected, ref found) => {
+                write!(formatter,
+                       "The cache file was written by an other matcher: expected={} found={}",
+                       expected,
+                       found)
+            }
+            CacheError::InvalidParser(ref parser) => {
+                write!(formatter, "The cache file contains an invalid parser: parser={}", parser)
+            }
+            CacheError::OtherSources => {
+                formatter.write_str("The cache file was compiled from other pattern files")
+            }
+            CacheError::Stale => {
+                formatter.write_str("The pattern files were changed since the cache was written")
+            }
+            CacheError::ErrorsIgnored => {
+                formatter.write_str("The cache file was written while the invalid patterns were \
+                                     ignored")
+            }
+        }
+    }
+}
+
+impl error::Error for CacheError {
+    fn description(&self) -> &str {
+        match *self {
+            CacheError::Corrupted => "The cache file is corrupted",
+            CacheError::UnsupportedVersion(_) => "The version of the cache file is not supported",
+            CacheError::MatcherMismatch(..) => "The cache file was written by an other matcher",
+            CacheError::InvalidParser(_) => "The cache file contains an invalid parser",
+            CacheError::OtherSources => "The cache file was compiled from other pattern files",
+            CacheError::Stale => "The pattern files were changed since the cache was written",
+            CacheError::ErrorsIgnored => {
+                "The cache file was written while the invalid patterns were ignored"
+            }
+        }
+    }
+}
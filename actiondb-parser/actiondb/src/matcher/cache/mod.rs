@@ -0,0 +1,299 @@
+// A compiled pattern cache contains a matcher in a binary form, so it can be
+// loaded without parsing the pattern files, checking the test messages and
+// inserting the patterns again. It's written by `adbtool compile` or by
+// PatternLoader::from_file_cached().
+//
+// The file starts with MAGIC, FORMAT_VERSION and the name of the matcher,
+// then the Sources and the matcher follow.
+
+mod encoding;
+mod error;
+mod sources;
+
+pub use self::encoding::{Encoder, Decoder};
+pub use self::error::CacheError;
+pub use self::sources::Sources;
+
+use matcher::Pattern;
+use matcher::pattern::Location;
+use parsers::{Parser, ParserFactory, OptionalParameter};
+
+use std::collections::BTreeMap;
+
+pub const MAGIC: &'static [u8] = b"ADBCACHE";
+pub const FORMAT_VERSION: u32 = 3;
+
+// The names of the parser parameters which can be stored in a cache.
+const PARAMETER_NAMES: &'static [&'static str] = &["set",
+                                                   "quotes",
+                                                   "regex",
+                                                   "end_string",
+                                                   "min_len",
+                                                   "max_len",
+                                                   "min",
+                                                   "max",
+                                                   "escape",
+                                                   "unescape",
+                                                   "format",
+                                                   "normalize",
+                                                   "pair_separator",
+                                                   "value_separator"];
+
+// Matchers which can be written to and read from a cache.
+pub trait Cacheable: Sized {
+    // It identifies the type of the matcher in the cache.
+    fn cache_name() -> &'static str;
+    fn encode(&self, encoder: &mut Encoder);
+    fn decode<F: ParserFactory>(decoder: &mut Decoder) -> Result<Self, CacheError>;
+}
+
+pub fn write<M: Cacheable>(matcher: &M, sources: &Sources) -> Vec<u8> {
+    let mut encoder = Encoder::new();
+    encoder.write_bytes(MAGIC);
+    encoder.write_u32(FORMAT_VERSION);
+    encoder.write_str(M::cache_name());
+    sources.encode(&mut encoder);
+    matcher.encode(&mut encoder);
+    encoder.into_bytes()
+}
+
+// Reads the header and the sources of a cache. The returned decoder can be
+// passed to read_matcher() if the sources are up to date.
+pub fn read_sources<M: Cacheable>(bytes: &[u8]) -> Result<(Sources, Decoder), CacheError> {
+    let mut decoder = Decoder::new(bytes);
+
+    if try!(decoder.read_bytes(MAGIC.len())) != MAGIC {
+        return Err(CacheError::Corrupted);
+    }
+    let version = try!(decoder.read_u32());
+    if version != FORMAT_VERSION {
+        return Err(CacheError::UnsupportedVersion(version));
+    }
+    let name = try!(decoder.read_string());
+    if name != M::cache_name() {
+        return Err(CacheError::MatcherMismatch(M::cache_name().to_owned(), name));
+    }
+
+    let sources = try!(Sources::decode(&mut decoder));
+    Ok((sources, decoder))
+}
+
+pub fn read_matcher<M, F>(mut decoder: Decoder) -> Result<M, CacheError>
+    where M: Cacheable,
+          F: ParserFactory
+{
+    let matcher = try!(M::decode::<F>(&mut decoder));
+    if decoder.is_empty() {
+        Ok(matcher)
+    } else {
+        Err(CacheError::Corrupted)
+    }
+}
+
+// The test messages are not stored, they are checked before a cache is
+// written.
+pub fn encode_pattern(encoder: &mut Encoder, pattern: &Pattern) {
+    encoder.write_option(pattern.name(), |encoder, name| encoder.write_str(name));
+    encoder.write_uuid(pattern.uuid());
+    encoder.write_option(pattern.values(), |encoder, values| {
+        encoder.write_usize(values.len());
+        for (key, value) in values {
+            encoder.write_str(key);
+            encoder.write_str(value);
+        }
+    });
+    encoder.write_option(pattern.tags(), |encoder, tags| {
+        encoder.write_usize(tags.len());
+        for tag in tags {
+            encoder.write_str(tag);
+        }
+    });
+    encoder.write_i32(pattern.priority());
+    encoder.write_option(pattern.location(), |encoder, location| {
+        encoder.write_str(location.file());
+        encoder.write_usize(location.index());
+    });
+    encoder.write_usize(pattern.test_message_count());
+}
+
+pub fn decode_pattern(decoder: &mut Decoder) -> Result<Pattern, CacheError> {
+    let name = try!(decoder.read_option(|decoder| decoder.read_string()));
+    let uuid = try!(decoder.read_uuid());
+    let values = try!(decoder.read_option(|decoder| {
+        let mut values = BTreeMap::new();
+        for _ in 0..try!(decoder.read_usize()) {
+            let key = try!(decoder.read_string());
+            values.insert(key, try!(decoder.read_string()));
+        }
+        Ok(values)
+    }));
+    let tags = try!(decoder.read_option(|decoder| {
+        let mut tags = Vec::new();
+        for _ in 0..try!(decoder.read_usize()) {
+            tags.push(try!(decoder.read_string()));
+        }
+        Ok(tags)
+    }));
+    let priority = try!(decoder.read_i32());
+    let location = try!(decoder.read_option(|decoder| {
+        let file = try!(decoder.read_string());
+        Ok(Location::new(file, try!(decoder.read_usize())))
+    }));
+    let test_message_count = try!(decoder.read_usize());
+
+    let mut pattern = Pattern::new(name, uuid, Vec::new(), None, values, tags);
+    pattern.set_priority(priority);
+    pattern.set_location(location);
+    pattern.set_test_message_count(test_message_count);
+    Ok(pattern)
+}
+
+// A parser is stored with its kind, name and parameters, it's created again
+// by the ParserFactory.
+pub fn encode_parser(encoder: &mut Encoder, parser: &Parser) {
+    encoder.write_str(parser.kind());
+    encoder.write_option(parser.name(), |encoder, name| encoder.write_str(name));
+
+    let parameters = parser.parameters();
+    encoder.write_usize(parameters.len());
+    for parameter in parameters {
+        match parameter {
+            OptionalParameter::Int(name, value) => {
+                encoder.write_u8(0);
+                encoder.write_str(name);
+                encoder.write_usize(value);
+            }
+            OptionalParameter::Float(name, value) => {
+                encoder.write_u8(1);
+                encoder.write_str(name);
+                encoder.write_f64(value);
+            }
+            OptionalParameter::Str(name, value) => {
+                encoder.write_u8(2);
+                encoder.write_str(name);
+                encoder.write_str(&value);
+            }
+            OptionalParameter::Bool(name, value) => {
+                encoder.write_u8(3);
+                encoder.write_str(name);
+                encoder.write_bool(value);
+            }
+        }
+    }
+}
+
+pub fn decode_parser<F: ParserFactory>(decoder: &mut Decoder) -> Result<Box<Parser>, CacheError> {
+    let kind = try!(decoder.read_string());
+    let name = try!(decoder.read_option(|decoder| decoder.read_string()));
+
+    let mut parameters = Vec::new();
+    for _ in 0..try!(decoder.read_usize()) {
+        let tag = try!(decoder.read_u8());
+        let parameter_name = try!(decoder.read_string());
+        let parameter_name = try!(PARAMETER_NAMES.iter()
+                                                 .find(|name| **name == parameter_name)
+                                                 .ok_or(CacheError::Corrupted));
+        let parameter = match tag {
+            0 => OptionalParameter::Int(parameter_name, try!(decoder.read_usize())),
+            1 => OptionalParameter::Float(parameter_name, try!(decoder.read_f64())),
+            2 => OptionalParameter::Str(parameter_name, try!(decoder.read_string())),
+            3 => OptionalParameter::Bool(parameter_name, try!(decoder.read_bool())),
+            _ => return Err(CacheError::Corrupted),
+        };
+        parameters.push(parameter);
+    }
+
+    create_parser::<F>(&kind, name.as_ref().map(|name| &name[..]), parameters)
+}
+
+// Removes a required string argument from the parameters.
+fn take_argument(parameters: &mut Vec<OptionalParameter<'static>>, name: &str) -> Option<String> {
+    let position = parameters.iter().position(|parameter| {
+        match *parameter {
+            OptionalParameter::Str(key, _) => key == name,
+            _ => false,
+        }
+    });
+    match position.map(|position| parameters.remove(position)) {
+        Some(OptionalParameter::Str(_, value)) => Some(value),
+        _ => None,
+    }
+}
+
+fn create_parser<F: ParserFactory>(kind: &str,
+                                   name: Option<&str>,
+                                   mut parameters: Vec<OptionalParameter<'static>>)
+                                   -> Result<Box<Parser>, CacheError> {
+    let invalid = || CacheError::InvalidParser(kind.to_owned());
+
+    let parser = match kind {
+        "SET" => {
+            let set = try!(take_argument(&mut parameters, "set").ok_or_else(&invalid));
+            F::new_set(&set, name, Some(parameters))
+        }
+        "INT" => F::new_int(name, Some(parameters)),
+        "GREEDY" => {
+            let end_string = take_argument(&mut parameters, "end_string");
+            F::new_greedy(name, end_string.as_ref().map(|end_string| &end_string[..]))
+        }
+        "NUMBER" => F::new_number(name, Some(parameters)),
+        "QSTRING" => {
+            let quotes = try!(take_argument(&mut parameters, "quotes").ok_or_else(&invalid));
+            F::new_qstring(&quotes, name, Some(parameters))
+        }
+        "TIMESTAMP" => F::new_timestamp(name, Some(parameters)),
+        "REGEX" => {
+            let regex = try!(take_argument(&mut parameters, "regex").ok_or_else(&invalid));
+            try!(F::new_regex(&regex, name).map_err(|_| invalid()))
+        }
+        "KV" => F::new_kv(name, Some(parameters)),
+        "IPV4" => F::new_ipv4(name),
+        "IPV6" => F::new_ipv6(name),
+        "IPADDR" => F::new_ipaddr(name),
+        _ => return Err(invalid()),
+    };
+    Ok(parser)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Encoder, Decoder, encode_parser, decode_parser};
+    use matcher::compiled_pattern::TokenType;
+    use matcher::trie::parser_factory::TrieParserFactory;
+
+    fn assert_parser_is_decoded_as_it_was(pattern: &str) {
+        let tokens = ::grammar::parser::pattern(pattern).expect("Failed to parse the pattern");
+        let parser = match tokens[0] {
+            TokenType::Parser(ref parser) => parser,
+            _ => panic!("The first token is not a parser: pattern={}", pattern),
+        };
+
+        let mut encoder = Encoder::new();
+        encode_parser(&mut encoder, &**parser);
+        let bytes = encoder.into_bytes();
+        let decoded = decode_parser::<TrieParserFactory>(&mut Decoder::new(&bytes))
+                          .expect("Failed to decode the parser");
+
+        assert_eq!(decoded.hash_os(), parser.hash_os(), "pattern={}", pattern);
+    }
+
+    #[test]
+    fn test_given_parser_when_it_is_encoded_then_the_same_parser_is_decoded() {
+        let patterns = ["%{SET(\"a-z[:digit:]\", min_len=2, max_len=5):set}",
+                        "%{INT(min_len=1):int}",
+                        "%{GREEDY:greedy} end",
+                        "%{GREEDY}",
+                        "%{NUMBER(min=-1.5, max=1e3):number}",
+                        "%{QSTRING(\"<>'\", escape=\"\\\\\", unescape=false):qstring}",
+                        "%{TIMESTAMP(format=\"bsd\", normalize=true):timestamp}",
+                        "%{REGEX(\"(?P<user>[a-z]+)@\"):regex}",
+                        "%{KV(pair_separator=\";\", value_separator=\":\"):kv}",
+                        "%{IPV4:ipv4}",
+                        "%{IPV6:ipv6}",
+                        "%{IPADDR}"];
+
+        for pattern in &patterns {
+            assert_parser_is_decoded_as_it_was(pattern);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use super::{CacheError, Encoder, Decoder};
+
+use std::fs::{self, File};
+use std::hash::{SipHasher, Hash, Hasher};
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+// The files and directories the patterns were loaded from. Their content is
+// hashed, so a cache can detect if they were changed. The paths are
+// canonical.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Sources {
+    // the paths given to the loader
+    roots: Vec<PathBuf>,
+    files: Vec<PathBuf>,
+    directories: Vec<PathBuf>,
+    hash: u64,
+    // the invalid patterns were skipped while they were loaded
+    errors_ignored: bool,
+}
+
+impl Sources {
+    pub fn new(roots: Vec<PathBuf>) -> Sources {
+        Sources { roots: roots, ..Sources::default() }
+    }
+
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    pub fn push_file(&mut self, path: PathBuf) {
+        self.files.push(path);
+    }
+
+    pub fn push_directory(&mut self, path: PathBuf) {
+        self.directories.push(path);
+    }
+
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub fn errors_ignored(&self) -> bool {
+        self.errors_ignored
+    }
+
+    pub fn set_errors_ignored(&mut self, errors_ignored: bool) {
+        self.errors_ignored = errors_ignored;
+    }
+
+    // Hashes the current content of the files and the entries of the
+    // directories (so a new file in a directory changes it too).
+    pub fn calculate_hash(&self) -> Result<u64, io::Error> {
+        let mut hasher = SipHasher::new();
+
+        for path in &self.files {
+            let mut content = Vec::new();
+            try!(try!(File::open(path)).read_to_end(&mut content));
+            path.hash(&mut hasher);
+            content.hash(&mut hasher);
+        }
+
+        for path in &self.directories {
+            let mut entries = Vec::new();
+            for entry in try!(fs::read_dir(path)) {
+                entries.push(try!(entry).file_name());
+            }
+            entries.sort();
+            path.hash(&mut hasher);
+            entries.hash(&mut hasher);
+        }
+
+        Ok(hasher.finish())
+    }
+
+    pub fn update_hash(&mut self) -> Result<(), io::Error> {
+        self.hash = try!(self.calculate_hash());
+        Ok(())
+    }
+
+    // If a file can't be read, it's changed too.
+    pub fn is_up_to_date(&self) -> bool {
+        self.calculate_hash().ok() == Some(self.hash)
+    }
+
+    // The modification times of the files and directories (a directory is
+    // modified when an entry is added or removed). It's cheaper than
+    // hashing, so it's used to watch the sources. If a file can't be
+    // accessed, it has no modification time.
+    pub fn modification_times(&self) -> Vec<Option<SystemTime>> {
+        self.files
+            .iter()
+            .chain(self.directories.iter())
+            .map(|path| fs::metadata(path).and_then(|metadata| metadata.modified()).ok())
+            .collect()
+    }
+
+    pub fn encode(&self, encoder: &mut Encoder) {
+        for paths in &[&self.roots, &self.files, &self.directories] {
+            encoder.write_usize(paths.len());
+            for path in paths.iter() {
+                encoder.write_str(&path.to_string_lossy());
+            }
+        }
+        encoder.write_u64(self.hash);
+        encoder.write_bool(self.errors_ignored);
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<Sources, CacheError> {
+        let roots = try!(Sources::decode_paths(decoder));
+        let files = try!(Sources::decode_paths(decoder));
+        let directories = try!(Sources::decode_paths(decoder));
+        Ok(Sources {
+            roots: roots,
+            files: files,
+            directories: directories,
+            hash: try!(decoder.read_u64()),
+            errors_ignored: try!(decoder.read_bool()),
+        })
+    }
+
+    fn decode_paths(decoder: &mut Decoder) -> Result<Vec<PathBuf>, CacheError> {
+        let mut paths = Vec::new();
+        for _ in 0..try!(decoder.read_usize()) {
+            let path = try!(decoder.read_string());
+            paths.push(PathBuf::from(path));
+        }
+        Ok(paths)
+    }
+}
@@ -1,4 +1,15 @@
-use parsers::Parser;
+use parsers::{GreedyParser, Parser};
+
+// The literal is needed by concat!() in the grammar's error message.
+macro_rules! max_variant_count {
+    () => (1024)
+}
+
+// The grammar rejects the patterns whose groups have more variants.
+pub const MAX_VARIANT_COUNT: usize = max_variant_count!();
+pub const MAX_VARIANT_COUNT_EXPECTED: &'static str = concat!("pattern with at most ",
+                                                             max_variant_count!(),
+                                                             " variants");
 
 pub type CompiledPattern = Vec<TokenType>;
 
@@ -6,6 +17,17 @@ pub type CompiledPattern = Vec<TokenType>;
 pub enum TokenType {
     Parser(Box<Parser>),
     Literal(String),
+    Alternation(Vec<CompiledPattern>),
+    Optional(CompiledPattern),
+}
+
+impl TokenType {
+    pub fn is_group(&self) -> bool {
+        match *self {
+            TokenType::Alternation(_) | TokenType::Optional(_) => true,
+            _ => false,
+        }
+    }
 }
 
 impl Clone for TokenType {
@@ -17,10 +39,116 @@ impl Clone for TokenType {
             TokenType::Literal(ref literal) => {
                 TokenType::Literal(literal.clone())
             }
+            TokenType::Alternation(ref alternatives) => {
+                TokenType::Alternation(alternatives.clone())
+            }
+            TokenType::Optional(ref pattern) => {
+                TokenType::Optional(pattern.clone())
+            }
         }
     }
 }
 
+// Returns every variant of `pattern` which can be built by choosing one of
+// the alternatives of its groups. The variants don't contain groups and
+// their neighbouring literals are merged, so they can be inserted into the
+// matchers like any other patterns. The end strings of the GREEDY parsers
+// are the literals following them in the variants. An error is returned if
+// there are more than MAX_VARIANT_COUNT variants.
+pub fn expand_alternatives(pattern: &CompiledPattern) -> Result<Vec<CompiledPattern>, String> {
+    let mut variants = try!(expand_groups(pattern));
+    for variant in &mut variants {
+        set_greedy_end_strings(variant);
+    }
+    Ok(variants)
+}
+
+// Returns the number of variants of `pattern`, it saturates instead of
+// overflowing.
+pub fn count_variants(pattern: &CompiledPattern) -> usize {
+    pattern.iter().fold(1, |count: usize, token| {
+        let token_count = match *token {
+            TokenType::Alternation(ref alternatives) => {
+                alternatives.iter().fold(0, |sum: usize, alternative| {
+                    sum.saturating_add(count_variants(alternative))
+                })
+            }
+            TokenType::Optional(ref optional) => count_variants(optional).saturating_add(1),
+            _ => 1,
+        };
+        count.saturating_mul(token_count)
+    })
+}
+
+fn expand_groups(pattern: &CompiledPattern) -> Result<Vec<CompiledPattern>, String> {
+    let mut variants = vec![CompiledPattern::new()];
+
+    for token in pattern {
+        let token_variants = match *token {
+            TokenType::Alternation(ref alternatives) => {
+                let mut token_variants = Vec::new();
+                for alternative in alternatives {
+                    token_variants.extend(try!(expand_groups(alternative)));
+                }
+                token_variants
+            }
+            TokenType::Optional(ref optional) => {
+                let mut token_variants = try!(expand_groups(optional));
+                token_variants.push(CompiledPattern::new());
+                token_variants
+            }
+            _ => vec![vec![token.clone()]],
+        };
+
+        let capacity = variants.len().saturating_mul(token_variants.len());
+        if capacity > MAX_VARIANT_COUNT {
+            return Err(format!("the pattern has more than {} variants", MAX_VARIANT_COUNT));
+        }
+        let mut new_variants = Vec::with_capacity(capacity);
+        for variant in &variants {
+            for token_variant in &token_variants {
+                let mut new_variant = variant.clone();
+                for token in token_variant {
+                    push_token_merging_literals(&mut new_variant, token.clone());
+                }
+                new_variants.push(new_variant);
+            }
+        }
+        variants = new_variants;
+    }
+
+    Ok(variants)
+}
+
+// A GREEDY right before a group is parsed without an end string, it gets
+// the literal which follows it in the variant.
+fn set_greedy_end_strings(variant: &mut CompiledPattern) {
+    for index in 0..variant.len() {
+        let greedy = match variant[index] {
+            TokenType::Parser(ref parser) if parser.kind() == "GREEDY" => {
+                let mut greedy = GreedyParser::new();
+                greedy.set_name(parser.name().map(|name| name.to_owned()));
+                if let Some(&TokenType::Literal(ref literal)) = variant.get(index + 1) {
+                    greedy.set_end_string(Some(literal.clone()));
+                }
+                greedy
+            }
+            _ => continue,
+        };
+        variant[index] = TokenType::Parser(Box::new(greedy));
+    }
+}
+
+fn push_token_merging_literals(pattern: &mut CompiledPattern, token: TokenType) {
+    if let TokenType::Literal(ref literal) = token {
+        if let Some(&mut TokenType::Literal(ref mut last)) = pattern.last_mut() {
+            last.push_str(literal);
+            return;
+        }
+    }
+    pattern.push(token);
+}
+
 pub struct CompiledPatternBuilder {
     pattern: CompiledPattern,
 }
@@ -42,6 +170,18 @@ impl CompiledPatternBuilder {
         self
     }
 
+    pub fn alternation(&mut self,
+                       alternatives: Vec<CompiledPattern>)
+                       -> &mut CompiledPatternBuilder {
+        self.pattern.push(TokenType::Alternation(alternatives));
+        self
+    }
+
+    pub fn optional(&mut self, pattern: CompiledPattern) -> &mut CompiledPatternBuilder {
+        self.pattern.push(TokenType::Optional(pattern));
+        self
+    }
+
     pub fn build(&self) -> CompiledPattern {
         self.pattern.clone()
     }
@@ -0,0 +1,270 @@
+use matcher::Pattern;
+use matcher::lint::PatternInfo;
+use parsers::Parser;
+
+use std::fmt;
+
+// The maximum number of candidate patterns kept for the deepest point.
+pub const MAX_CANDIDATES: usize = 8;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Step {
+    // `literal` was compared with the text at `offset`, their first
+    // `matched` bytes are the same.
+    Literal {
+        offset: usize,
+        literal: String,
+        matched: usize,
+    },
+    // `parser` was tried at `offset`. If it's tried more than once at the
+    // same offset, it's backtracking to an alternative match.
+    Parser {
+        offset: usize,
+        parser: String,
+        consumed: Option<usize>,
+    },
+}
+
+// The path walked through a matcher while a message is parsed. The
+// matchers report the remaining part of the message, the offsets are
+// calculated from it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Explanation {
+    text: String,
+    steps: Vec<Step>,
+    matched: Option<PatternInfo>,
+    deepest_offset: usize,
+    candidates: Vec<PatternInfo>,
+}
+
+impl Explanation {
+    pub fn new(text: &str) -> Explanation {
+        Explanation {
+            text: text.to_owned(),
+            steps: Vec::new(),
+            matched: None,
+            deepest_offset: 0,
+            candidates: Vec::new(),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    pub fn matched(&self) -> Option<&PatternInfo> {
+        self.matched.as_ref()
+    }
+
+    pub fn deepest_offset(&self) -> usize {
+        self.deepest_offset
+    }
+
+    pub fn candidates(&self) -> &[PatternInfo] {
+        &self.candidates
+    }
+
+    fn offset(&self, remaining: &str) -> usize {
+        self.text.len() - remaining.len()
+    }
+
+    pub fn push_literal(&mut self, remaining: &str, literal: &str, matched: usize) {
+        let offset = self.offset(remaining);
+        self.steps.push(Step::Literal {
+            offset: offset,
+            literal: literal.to_owned(),
+            matched: matched,
+        });
+    }
+
+    pub fn push_parser(&mut self, remaining: &str, parser: &Parser, consumed: Option<usize>) {
+        let offset = self.offset(remaining);
+        let parser = match parser.name() {
+            Some(name) => format!("%{{{}:{}}}", parser.kind(), name),
+            None => format!("%{{{}}}", parser.kind()),
+        };
+        self.steps.push(Step::Parser {
+            offset: offset,
+            parser: parser,
+            consumed: consumed,
+        });
+    }
+
+    // Called when the matcher gets to `remaining`. If it's deeper than any
+    // other point reached before, the patterns collected by `collect` become
+    // the candidates.
+    pub fn reach<'a, F>(&mut self, remaining: &str, collect: F)
+        where F: FnOnce(&mut Vec<&'a Pattern>)
+    {
+        let offset = self.offset(remaining);
+
+        if offset > self.deepest_offset || self.candidates.is_empty() {
+            let mut patterns = Vec::new();
+            collect(&mut patterns);
+            self.deepest_offset = offset;
+            self.candidates.clear();
+
+            for pattern in patterns {
+                if self.candidates.len() == MAX_CANDIDATES {
+                    break;
+                }
+                if self.candidates.iter().all(|candidate| candidate.uuid() != pattern.uuid()) {
+                    self.candidates.push(PatternInfo::new(pattern));
+                }
+            }
+        }
+    }
+
+    pub fn set_matched(&mut self, pattern: Option<&Pattern>) {
+        self.matched = pattern.map(PatternInfo::new);
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        try!(writeln!(fmt, "Message: '{}'", self.text));
+
+        for step in &self.steps {
+            match *step {
+                Step::Literal{offset, ref literal, matched} => {
+                    try!(writeln!(fmt,
+                                  "  at {}: literal '{}' matches {} of {} bytes",
+                                  offset,
+                                  literal,
+                                  matched,
+                                  literal.len()))
+                }
+                Step::Parser{offset, ref parser, consumed: Some(consumed)} => {
+                    try!(writeln!(fmt,
+                                  "  at {}: {} consumes {} bytes: '{}'",
+                                  offset,
+                                  parser,
+                                  consumed,
+                                  &self.text[offset..offset + consumed]))
+                }
+                Step::Parser{offset, ref parser, consumed: None} => {
+                    try!(writeln!(fmt, "  at {}: {} doesn't match", offset, parser))
+                }
+            }
+        }
+
+        match self.matched {
+            Some(ref pattern) => write!(fmt, "Matched pattern: {}", pattern),
+            None => {
+                try!(write!(fmt,
+                            "No match, the deepest point reached is at {}: '{}'",
+                            self.deepest_offset,
+                            &self.text[self.deepest_offset..]));
+                if !self.candidates.is_empty() {
+                    try!(write!(fmt, "\nClosest candidate patterns:"));
+                }
+                for candidate in &self.candidates {
+                    try!(write!(fmt, "\n  {}", candidate));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use matcher::{Matcher, MatcherFactory, Pattern};
+    use matcher::trie::factory::TrieMatcherFactory;
+    use matcher::suffix_array::SuffixArrayMatcherFactory;
+    use super::Step;
+
+    fn create_matcher<F: MatcherFactory>(patterns: &[(&str, &str)]) -> F::Matcher {
+        let mut matcher = F::new_matcher();
+        for &(uuid, pattern) in patterns {
+            matcher.add_pattern(Pattern::new(None,
+                                             Uuid::parse_str(uuid).unwrap(),
+                                             ::grammar::parser::pattern(pattern).unwrap(),
+                                             None,
+                                             None,
+                                             None));
+        }
+        matcher.sort_parsers();
+        matcher
+    }
+
+    fn assert_explanation_contains_the_matched_pattern<F: MatcherFactory>() {
+        let matcher = create_matcher::<F>(&[("11111111-1111-1111-1111-111111111111",
+                                             "user %{INT:uid} logged in"),
+                                            ("22222222-2222-2222-2222-222222222222",
+                                             "user %{INT:uid} logged out")]);
+        let message = "user 42 logged out";
+        let explanation = matcher.explain(message);
+        let result = matcher.parse(message).expect("Failed to parse a message");
+
+        assert_eq!(explanation.matched().map(|pattern| pattern.uuid()),
+                   Some(result.pattern().uuid()));
+        assert!(explanation.steps().contains(&Step::Parser {
+            offset: 5,
+            parser: "%{INT:uid}".to_owned(),
+            consumed: Some(2),
+        }));
+    }
+
+    #[test]
+    fn test_given_matcher_when_a_matching_message_is_explained_then_the_matched_pattern_is_returned
+        () {
+        assert_explanation_contains_the_matched_pattern::<TrieMatcherFactory>();
+        assert_explanation_contains_the_matched_pattern::<SuffixArrayMatcherFactory>();
+    }
+
+    fn assert_the_deepest_point_is_reported<F: MatcherFactory>() {
+        let matcher = create_matcher::<F>(&[("11111111-1111-1111-1111-111111111111",
+                                             "user %{INT:uid} logged in"),
+                                            ("22222222-2222-2222-2222-222222222222",
+                                             "user %{INT:uid} logged out"),
+                                            ("33333333-3333-3333-3333-333333333333",
+                                             "host %{GREEDY:host} is down")]);
+        let explanation = matcher.explain("user 42 logged off");
+
+        assert_eq!(explanation.matched(), None);
+        assert_eq!(explanation.deepest_offset(), 16);
+        let candidates = explanation.candidates()
+                                    .iter()
+                                    .map(|pattern| pattern.uuid().hyphenated().to_string())
+                                    .collect::<Vec<String>>();
+        assert_eq!(candidates, vec!["22222222-2222-2222-2222-222222222222".to_owned()]);
+
+        let explanation = matcher.explain("user root logged in");
+        assert_eq!(explanation.deepest_offset(), 5);
+        assert_eq!(explanation.candidates().len(), 2);
+    }
+
+    #[test]
+    fn test_given_matcher_when_a_not_matching_message_is_explained_then_the_closest_patterns_are_returned
+        () {
+        assert_the_deepest_point_is_reported::<TrieMatcherFactory>();
+        assert_the_deepest_point_is_reported::<SuffixArrayMatcherFactory>();
+    }
+
+    #[test]
+    fn test_given_suffix_array_matcher_when_it_backtracks_then_every_tried_match_is_in_the_explanation
+        () {
+        let matcher = create_matcher::<SuffixArrayMatcherFactory>(
+            &[("11111111-1111-1111-1111-111111111111", "%{GREEDY:a} - %{INT:n} - end")]);
+        let explanation = matcher.explain("x - y - 5 - end");
+        let tries = explanation.steps()
+                               .iter()
+                               .filter(|step| {
+                                   match **step {
+                                       Step::Parser{offset: 0, ..} => true,
+                                       _ => false,
+                                   }
+                               })
+                               .count();
+
+        assert!(explanation.matched().is_some());
+        assert_eq!(tries, 2);
+    }
+}
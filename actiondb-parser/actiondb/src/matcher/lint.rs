@@ -0,0 +1,586 @@
+use uuid::Uuid;
+
+use matcher::{Matcher, MatcherFactory, Pattern};
+use matcher::compiled_pattern::{self, TokenType};
+use parsers::Parser;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+// Implemented by the matchers whose patterns can be analyzed.
+pub trait PatternTree {
+    // Returns the UUIDs of the patterns which can be reached from this
+    // node.
+    fn collect_reachable(&self) -> BTreeSet<Uuid>;
+    // Collects the overlapping branches of this node and of the nodes below
+    // it (see find_overlaps()).
+    fn collect_overlaps(&self, overlaps: &mut Vec<Overlap>);
+}
+
+// Two patterns which can match the same input, because their paths overlap
+// at a node of the matcher. `first` is the one which is tried first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Overlap {
+    first: Uuid,
+    second: Uuid,
+    reason: String,
+}
+
+// Compares the branches which start at the same node: a parser which can
+// parse a sibling literal, GREEDY next to an other parser and two parsers
+// which differ only in their names overlap. Every branch has the UUIDs of
+// the patterns reachable through it. The literals are tried before the
+// parsers, the parsers are in the order they are tried.
+pub fn find_overlaps(literals: &[(&str, BTreeSet<Uuid>)],
+                     parsers: &[(&Parser, BTreeSet<Uuid>)],
+                     overlaps: &mut Vec<Overlap>) {
+    for (index, &(parser, ref parser_uuids)) in parsers.iter().enumerate() {
+        for &(literal, ref literal_uuids) in literals {
+            if can_parse_literal(parser, literal) {
+                let reason = format!("{} can parse the literal '{}'", parser.kind(), literal);
+                push_overlaps(literal_uuids, parser_uuids, &reason, overlaps);
+            }
+        }
+        for &(other, ref other_uuids) in &parsers[index + 1..] {
+            if let Some(reason) = compare_parsers(parser, other) {
+                push_overlaps(parser_uuids, other_uuids, &reason, overlaps);
+            }
+        }
+    }
+}
+
+// GREEDY (the only parser with 0 specificity) can parse anything.
+fn can_parse_literal(parser: &Parser, literal: &str) -> bool {
+    parser.specificity() == 0 ||
+    parser.parse(literal).map_or(false, |result| result.match_length() > 0)
+}
+
+fn compare_parsers(first: &Parser, second: &Parser) -> Option<String> {
+    if first.kind() == second.kind() && first.parameters() == second.parameters() {
+        Some(format!("{} parsers with the same parameters", first.kind()))
+    } else if first.specificity() == 0 || second.specificity() == 0 {
+        Some(format!("{} and {} parse the same text", first.kind(), second.kind()))
+    } else {
+        None
+    }
+}
+
+fn push_overlaps(first: &BTreeSet<Uuid>,
+                 second: &BTreeSet<Uuid>,
+                 reason: &str,
+                 overlaps: &mut Vec<Overlap>) {
+    for first_uuid in first {
+        for second_uuid in second.iter().filter(|uuid| *uuid != first_uuid) {
+            overlaps.push(Overlap {
+                first: first_uuid.clone(),
+                second: second_uuid.clone(),
+                reason: reason.to_owned(),
+            });
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatternInfo {
+    uuid: Uuid,
+    name: Option<String>,
+}
+
+impl PatternInfo {
+    pub fn new(pattern: &Pattern) -> PatternInfo {
+        PatternInfo {
+            uuid: pattern.uuid().clone(),
+            name: pattern.name().map(|name| name.to_owned()),
+        }
+    }
+
+    pub fn uuid(&self) -> &Uuid {
+        &self.uuid
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(|name| &name[..])
+    }
+}
+
+impl fmt::Display for PatternInfo {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self.name {
+            Some(ref name) => write!(fmt, "{} ({})", self.uuid.hyphenated(), name),
+            None => write!(fmt, "{}", self.uuid.hyphenated()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Issue {
+    // Both patterns match `message`, the matcher returns only one of them.
+    Ambiguous {
+        first: PatternInfo,
+        second: PatternInfo,
+        message: String,
+    },
+    // The paths of the patterns overlap in the matcher, so they can match
+    // the same message, but none of their test messages shows it.
+    Overlapping {
+        first: PatternInfo,
+        second: PatternInfo,
+        reason: String,
+    },
+    // `pattern` can't be returned by the matcher, because `shadowed_by` is
+    // chosen instead. If `message` is None, the patterns are identical.
+    Unreachable {
+        pattern: PatternInfo,
+        shadowed_by: PatternInfo,
+        message: Option<String>,
+    },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            Issue::Ambiguous{ref first, ref second, ref message} => {
+                write!(fmt,
+                       "Patterns match the same message: first={} second={} message='{}'",
+                       first,
+                       second,
+                       message)
+            }
+            Issue::Overlapping{ref first, ref second, ref reason} => {
+                write!(fmt,
+                       "Patterns can match the same message, {}: first={} second={}",
+                       reason,
+                       first,
+                       second)
+            }
+            Issue::Unreachable{ref pattern, ref shadowed_by, message: Some(ref message)} => {
+                write!(fmt,
+                       "Pattern is never reached, its test messages match to an other \
+                        pattern: pattern={} shadowed_by={} message='{}'",
+                       pattern,
+                       shadowed_by,
+                       message)
+            }
+            Issue::Unreachable{ref pattern, ref shadowed_by, message: None} => {
+                write!(fmt,
+                       "Pattern is never reached, it's identical to an other pattern: \
+                        pattern={} shadowed_by={}",
+                       pattern,
+                       shadowed_by)
+            }
+        }
+    }
+}
+
+struct LintedPattern {
+    info: PatternInfo,
+    messages: Vec<String>,
+    signatures: BTreeSet<Vec<String>>,
+}
+
+// Finds the patterns which can match the same input and the ones which are
+// never returned by the matcher. The overlapping branches of the matcher
+// are reported even if the patterns don't have test messages. The test
+// messages are matched against all of the patterns too, the overlaps shown
+// by them are reported as ambiguous.
+pub fn lint<F>(patterns: Vec<Pattern>) -> Vec<Issue>
+    where F: MatcherFactory,
+          F::Matcher: PatternTree
+{
+    let mut matcher = F::new_matcher();
+    let mut linted_patterns = Vec::new();
+
+    for mut pattern in patterns {
+        let mut messages = Vec::new();
+        while let Some(test_message) = pattern.pop_test_message() {
+            messages.push(test_message.message().to_owned());
+        }
+        messages.reverse();
+
+        linted_patterns.push(LintedPattern {
+            info: PatternInfo::new(&pattern),
+            messages: messages,
+            signatures: create_signatures(&pattern),
+        });
+        matcher.add_pattern(pattern);
+    }
+    matcher.sort_parsers();
+
+    let reachable = matcher.collect_reachable();
+    let by_uuid: BTreeMap<&Uuid, &LintedPattern> = linted_patterns.iter()
+                                                                  .map(|p| (p.info.uuid(), p))
+                                                                  .collect();
+    let mut issues = Vec::new();
+
+    for pattern in &linted_patterns {
+        if !reachable.contains(pattern.info.uuid()) {
+            if let Some(shadowing) = find_identical_pattern(pattern, &linted_patterns, &reachable) {
+                issues.push(Issue::Unreachable {
+                    pattern: pattern.info.clone(),
+                    shadowed_by: shadowing.info.clone(),
+                    message: None,
+                });
+            }
+        } else if let Some(issue) = check_shadowing(pattern, &matcher, &by_uuid) {
+            issues.push(issue);
+        }
+    }
+
+    let ambiguous_pairs = collect_ambiguous_pairs(&linted_patterns, &matcher);
+    for (_, &(ref first, ref second, message)) in &ambiguous_pairs {
+        if let (Some(first), Some(second)) = (by_uuid.get(first), by_uuid.get(second)) {
+            issues.push(Issue::Ambiguous {
+                first: first.info.clone(),
+                second: second.info.clone(),
+                message: message.to_owned(),
+            });
+        }
+    }
+
+    for (key, overlap) in collect_overlapping_pairs(&matcher, &by_uuid) {
+        if ambiguous_pairs.contains_key(&key) {
+            continue;
+        }
+        if let (Some(first), Some(second)) = (by_uuid.get(&overlap.first),
+                                              by_uuid.get(&overlap.second)) {
+            issues.push(Issue::Overlapping {
+                first: first.info.clone(),
+                second: second.info.clone(),
+                reason: overlap.reason,
+            });
+        }
+    }
+
+    issues
+}
+
+// Every variant of the pattern is converted to a list of tokens which can
+// be compared to the tokens of other patterns.
+fn create_signatures(pattern: &Pattern) -> BTreeSet<Vec<String>> {
+    // the grammar rejects the patterns with too many variants
+    compiled_pattern::expand_alternatives(pattern.pattern())
+        .unwrap_or_default()
+        .iter()
+        .map(|variant| {
+            variant.iter()
+                   .map(|token| {
+                       match *token {
+                           TokenType::Literal(ref literal) => format!("literal:{}", literal),
+                           TokenType::Parser(ref parser) => format!("parser:{}", parser.hash_os()),
+                           _ => unreachable!("groups are expanded before comparison"),
+                       }
+                   })
+                   .collect()
+        })
+        .collect()
+}
+
+fn find_identical_pattern<'a>(pattern: &LintedPattern,
+                              patterns: &'a [LintedPattern],
+                              reachable: &BTreeSet<Uuid>)
+                              -> Option<&'a LintedPattern> {
+    patterns.iter().find(|other| {
+        reachable.contains(other.info.uuid()) &&
+        !other.signatures.is_disjoint(&pattern.signatures)
+    })
+}
+
+// A pattern is shadowed if all of its test messages match to other patterns.
+fn check_shadowing<M: Matcher>(pattern: &LintedPattern,
+                               matcher: &M,
+                               by_uuid: &BTreeMap<&Uuid, &LintedPattern>)
+                               -> Option<Issue> {
+    let mut shadowing = None;
+
+    for message in &pattern.messages {
+        match matcher.parse(message) {
+            Some(ref result) if result.pattern().uuid() != pattern.info.uuid() => {
+                if shadowing.is_none() {
+                    shadowing = Some((result.pattern().uuid().clone(), message));
+                }
+            }
+            _ => return None,
+        }
+    }
+
+    shadowing.and_then(|(uuid, message)| {
+        by_uuid.get(&uuid).map(|shadowing| {
+            Issue::Unreachable {
+                pattern: pattern.info.clone(),
+                shadowed_by: shadowing.info.clone(),
+                message: Some(message.to_owned()),
+            }
+        })
+    })
+}
+
+// Returns the overlapping pairs of patterns, keyed like the ambiguous pairs.
+// A pair is skipped if the patterns end with different literals.
+fn collect_overlapping_pairs<M: PatternTree>(matcher: &M,
+                                             by_uuid: &BTreeMap<&Uuid, &LintedPattern>)
+                                             -> BTreeMap<(Uuid, Uuid), Overlap> {
+    let mut overlaps = Vec::new();
+    matcher.collect_overlaps(&mut overlaps);
+    let mut pairs = BTreeMap::new();
+
+    for overlap in overlaps {
+        let can_end_alike = match (by_uuid.get(&overlap.first), by_uuid.get(&overlap.second)) {
+            (Some(first), Some(second)) => can_end_alike(first, second),
+            _ => false,
+        };
+        if !can_end_alike {
+            continue;
+        }
+        let key = if overlap.first < overlap.second {
+            (overlap.first.clone(), overlap.second.clone())
+        } else {
+            (overlap.second.clone(), overlap.first.clone())
+        };
+        pairs.entry(key).or_insert(overlap);
+    }
+
+    pairs
+}
+
+fn can_end_alike(first: &LintedPattern, second: &LintedPattern) -> bool {
+    first.signatures.iter().any(|first| {
+        second.signatures.iter().any(|second| {
+            match (last_literal(first), last_literal(second)) {
+                (Some(first), Some(second)) => first.ends_with(second) || second.ends_with(first),
+                _ => true,
+            }
+        })
+    })
+}
+
+fn last_literal(signature: &[String]) -> Option<&str> {
+    signature.last().and_then(|token| {
+        if token.starts_with("literal:") {
+            Some(&token["literal:".len()..])
+        } else {
+            None
+        }
+    })
+}
+
+// Returns the pairs of patterns which match a test message of one of them.
+// The first pattern of a pair is the one which the matcher tries first. The
+// pairs are keyed by their UUIDs in ascending order, so every pair is
+// reported once, with its first message.
+fn collect_ambiguous_pairs<'a, M: Matcher>(patterns: &'a [LintedPattern],
+                                           matcher: &M)
+                                           -> BTreeMap<(Uuid, Uuid), (Uuid, Uuid, &'a str)> {
+    let mut pairs = BTreeMap::new();
+
+    for pattern in patterns {
+        for message in &pattern.messages {
+            let uuids = matcher.parse_all(message)
+                               .iter()
+                               .map(|result| result.pattern().uuid().clone())
+                               .collect::<Vec<_>>();
+            let position = match uuids.iter().position(|uuid| uuid == pattern.info.uuid()) {
+                Some(position) => position,
+                None => continue,
+            };
+            for (other_position, other) in uuids.iter().enumerate() {
+                if other == pattern.info.uuid() {
+                    continue;
+                }
+                let (first, second) = if other_position < position {
+                    (other.clone(), pattern.info.uuid().clone())
+                } else {
+                    (pattern.info.uuid().clone(), other.clone())
+                };
+                let key = if first < second {
+                    (first.clone(), second.clone())
+                } else {
+                    (second.clone(), first.clone())
+                };
+                pairs.entry(key).or_insert((first, second, &message[..]));
+            }
+        }
+    }
+
+    pairs
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use matcher::{MatcherFactory, Pattern};
+    use matcher::pattern::testmessage::TestMessage;
+    use matcher::trie::factory::TrieMatcherFactory;
+    use matcher::suffix_array::SuffixArrayMatcherFactory;
+    use super::{lint, Issue, PatternTree};
+
+    use std::collections::BTreeMap;
+
+    fn create_pattern(uuid: &str, pattern: &str, messages: &[&str]) -> Pattern {
+        let test_messages = messages.iter()
+                                    .map(|message| {
+                                        TestMessage::new(message.to_string(), BTreeMap::new(), None)
+                                    })
+                                    .collect();
+        Pattern::new(None,
+                     Uuid::parse_str(uuid).unwrap(),
+                     ::grammar::parser::pattern(pattern).unwrap(),
+                     Some(test_messages),
+                     None,
+                     None)
+    }
+
+    fn assert_overlapping_patterns_are_reported<F>()
+        where F: MatcherFactory,
+              F::Matcher: PatternTree
+    {
+        let patterns = vec![create_pattern("11111111-1111-1111-1111-111111111111",
+                                           "user %{GREEDY:user} logged in",
+                                           &["user joe logged in"]),
+                            create_pattern("22222222-2222-2222-2222-222222222222",
+                                           "user %{INT:uid} logged in",
+                                           &["user 42 logged in"]),
+                            create_pattern("33333333-3333-3333-3333-333333333333",
+                                           "user %{INT:uid} logged out",
+                                           &["user 42 logged out"])];
+        // the INT parser is more specific, so it's tried before GREEDY
+        let issues = lint::<F>(patterns);
+        assert_eq!(issues.len(), 1);
+        match issues[0] {
+            Issue::Ambiguous{ref first, ref second, ref message} => {
+                assert_eq!(first.uuid().hyphenated().to_string(),
+                           "22222222-2222-2222-2222-222222222222");
+                assert_eq!(second.uuid().hyphenated().to_string(),
+                           "11111111-1111-1111-1111-111111111111");
+                assert_eq!(message, "user 42 logged in");
+            }
+            _ => panic!("Overlapping patterns should be reported as ambiguous"),
+        }
+    }
+
+    fn assert_identical_patterns_are_reported<F>()
+        where F: MatcherFactory,
+              F::Matcher: PatternTree
+    {
+        let patterns = vec![create_pattern("11111111-1111-1111-1111-111111111111",
+                                           "user %{INT:uid} logged in",
+                                           &[]),
+                            create_pattern("22222222-2222-2222-2222-222222222222",
+                                           "user %{INT:uid} logged in",
+                                           &[]),
+                            create_pattern("33333333-3333-3333-3333-333333333333",
+                                           "user %{INT:uid} logged out",
+                                           &[])];
+        let issues = lint::<F>(patterns);
+        assert_eq!(issues.len(), 1);
+        match issues[0] {
+            Issue::Unreachable{ref pattern, ref shadowed_by, ref message} => {
+                assert_eq!(pattern.uuid().hyphenated().to_string(),
+                           "11111111-1111-1111-1111-111111111111");
+                assert_eq!(shadowed_by.uuid().hyphenated().to_string(),
+                           "22222222-2222-2222-2222-222222222222");
+                assert_eq!(message, &None);
+            }
+            _ => panic!("Identical patterns should be reported as unreachable"),
+        }
+    }
+
+    fn assert_overlaps_without_test_messages_are_reported<F>()
+        where F: MatcherFactory,
+              F::Matcher: PatternTree
+    {
+        let patterns = vec![create_pattern("11111111-1111-1111-1111-111111111111",
+                                           "user %{GREEDY:user} logged in",
+                                           &[]),
+                            create_pattern("22222222-2222-2222-2222-222222222222",
+                                           "user %{INT:uid} logged in",
+                                           &[]),
+                            create_pattern("33333333-3333-3333-3333-333333333333",
+                                           "user %{INT:uid} logged out",
+                                           &[]),
+                            create_pattern("44444444-4444-4444-4444-444444444444",
+                                           "port %{SET(\"a-z\"):service}",
+                                           &[]),
+                            create_pattern("55555555-5555-5555-5555-555555555555",
+                                           "port ssh",
+                                           &[])];
+        // the patterns which end with different literals don't overlap
+        let issues = lint::<F>(patterns);
+        assert_eq!(issues.len(), 2);
+        match issues[0] {
+            Issue::Overlapping{ref first, ref second, ..} => {
+                assert_eq!(first.uuid().hyphenated().to_string(),
+                           "22222222-2222-2222-2222-222222222222");
+                assert_eq!(second.uuid().hyphenated().to_string(),
+                           "11111111-1111-1111-1111-111111111111");
+            }
+            _ => panic!("GREEDY and INT at the same position should be reported as overlapping"),
+        }
+        match issues[1] {
+            Issue::Overlapping{ref first, ref second, ..} => {
+                assert_eq!(first.uuid().hyphenated().to_string(),
+                           "55555555-5555-5555-5555-555555555555");
+                assert_eq!(second.uuid().hyphenated().to_string(),
+                           "44444444-4444-4444-4444-444444444444");
+            }
+            _ => panic!("SET parsing a sibling literal should be reported as overlapping"),
+        }
+    }
+
+    fn assert_parsers_differing_only_in_their_names_are_reported<F>()
+        where F: MatcherFactory,
+              F::Matcher: PatternTree
+    {
+        let patterns = vec![create_pattern("11111111-1111-1111-1111-111111111111",
+                                           "user %{INT:uid} logged in",
+                                           &[]),
+                            create_pattern("22222222-2222-2222-2222-222222222222",
+                                           "user %{INT:user_id} logged in",
+                                           &[])];
+        let issues = lint::<F>(patterns);
+        assert_eq!(issues.len(), 1);
+        match issues[0] {
+            Issue::Overlapping{ref reason, ..} => {
+                assert_eq!(reason, "INT parsers with the same parameters");
+            }
+            _ => panic!("Parsers differing only in their names should be reported as overlapping"),
+        }
+    }
+
+    #[test]
+    fn test_given_patterns_without_test_messages_when_they_overlap_then_they_are_reported() {
+        assert_overlaps_without_test_messages_are_reported::<TrieMatcherFactory>();
+        assert_overlaps_without_test_messages_are_reported::<SuffixArrayMatcherFactory>();
+    }
+
+    #[test]
+    fn test_given_parsers_differing_only_in_names_when_they_are_linted_then_they_are_reported() {
+        assert_parsers_differing_only_in_their_names_are_reported::<TrieMatcherFactory>();
+        assert_parsers_differing_only_in_their_names_are_reported::<SuffixArrayMatcherFactory>();
+    }
+
+    #[test]
+    fn test_given_overlapping_patterns_when_they_are_linted_then_they_are_reported_as_ambiguous() {
+        assert_overlapping_patterns_are_reported::<TrieMatcherFactory>();
+        assert_overlapping_patterns_are_reported::<SuffixArrayMatcherFactory>();
+    }
+
+    #[test]
+    fn test_given_identical_patterns_when_they_are_linted_then_the_overwritten_one_is_unreachable
+        () {
+        assert_identical_patterns_are_reported::<TrieMatcherFactory>();
+        assert_identical_patterns_are_reported::<SuffixArrayMatcherFactory>();
+    }
+
+    #[test]
+    fn test_given_disjoint_patterns_when_they_are_linted_then_no_issues_are_reported() {
+        let patterns = vec![create_pattern("11111111-1111-1111-1111-111111111111",
+                                           "port %{INT:port}",
+                                           &["port 22"]),
+                            create_pattern("22222222-2222-2222-2222-222222222222",
+                                           "port %{SET(\"a-z\"):service}",
+                                           &["port ssh"])];
+        assert_eq!(lint::<TrieMatcherFactory>(patterns.clone()), vec![]);
+        assert_eq!(lint::<SuffixArrayMatcherFactory>(patterns), vec![]);
+    }
+}
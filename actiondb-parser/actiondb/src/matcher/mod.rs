@@ -7,18 +7,41 @@ pub mod pattern_loader;
 pub mod suite;
 pub mod compiled_pattern;
 pub mod suffix_array;
+pub mod lint;
+pub mod explain;
+pub mod suggest;
+pub mod cache;
 
 pub use self::pattern::Pattern;
 pub use self::pattern_loader::PatternLoader;
 pub use self::factory::MatcherFactory;
-pub use self::suite::MatcherSuite;
+pub use self::suite::{MatcherSuite, MatcherKind};
 pub use self::pattern_source::{FromPatternSource, BuildError};
 
 use matcher::result::MatchResult;
+use matcher::explain::Explanation;
 use std::fmt;
 
+// The number of alternative parser matches which are tried during the
+// parsing of a message.
+pub const DEFAULT_BACKTRACKING_BUDGET: usize = 64;
+
 pub trait Matcher: fmt::Debug {
     fn parse<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>>;
+    // Returns the results of every pattern which matches `text`. The most
+    // specific one (with the most literal bytes matched) is the first. Every
+    // path through the matcher has its own backtracking budget, so a match
+    // found on one path doesn't shorten the search on the others.
+    fn parse_all<'a, 'b>(&'a self, text: &'b str) -> Vec<MatchResult<'a, 'b>>;
+    // Returns the path walked through the matcher while `text` is parsed.
+    fn explain(&self, text: &str) -> Explanation;
     fn add_pattern(&mut self, pattern: Pattern);
+    // Orders the parsers by the priority of their patterns and by their
+    // specificity. add_pattern() doesn't keep them ordered, so it's called
+    // once after the last pattern is added.
+    fn sort_parsers(&mut self);
     fn boxed_clone(&self) -> Box<Matcher>;
+    // Limits the number of alternative parser matches tried during the
+    // parsing of a message. Matchers which don't backtrack ignore it.
+    fn set_backtracking_budget(&mut self, _budget: usize) {}
 }
@@ -21,6 +21,7 @@ enum Field {
     VALUES,
     TAGS,
     TESTMESSAGES,
+    PRIORITY,
 }
 
 impl serde::Deserialize for Field {
@@ -42,6 +43,7 @@ impl serde::Deserialize for Field {
                     "values" => Ok(Field::VALUES),
                     "tags" => Ok(Field::TAGS),
                     "test_messages" => Ok(Field::TESTMESSAGES),
+                    "priority" => Ok(Field::PRIORITY),
                     _ => Err(serde::de::Error::custom(format!("Unexpected field: {}", value))),
                 }
             }
@@ -66,6 +68,7 @@ impl serde::de::Visitor for PatternVisitor {
         let mut values: Option<BTreeMap<String, String>> = None;
         let mut tags: Option<Vec<String>> = None;
         let mut test_messages: Option<Vec<TestMessage>> = None;
+        let mut priority: Option<i32> = None;
 
         while let Some(field) = try!(visitor.visit_key()) {
             match field {
@@ -75,6 +78,7 @@ impl serde::de::Visitor for PatternVisitor {
                 Field::VALUES => values = Some(try!(visitor.visit_value())),
                 Field::TAGS => tags = Some(try!(visitor.visit_value())),
                 Field::TESTMESSAGES => test_messages = Some(try!(visitor.visit_value())),
+                Field::PRIORITY => priority = Some(try!(visitor.visit_value())),
             }
         }
 
@@ -103,6 +107,8 @@ impl serde::de::Visitor for PatternVisitor {
 
         try!(visitor.end());
 
-        Ok(Pattern::new(name, uuid, pattern, test_messages, values, tags))
+        let mut pattern = Pattern::new(name, uuid, pattern, test_messages, values, tags);
+        pattern.set_priority(priority.unwrap_or_default());
+        Ok(pattern)
     }
 }
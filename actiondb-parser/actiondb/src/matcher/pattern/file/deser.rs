@@ -13,6 +13,7 @@ impl serde::de::Deserialize for PatternFile {
 
 enum Field {
     PATTERNS,
+    INCLUDE,
 }
 
 impl serde::Deserialize for Field {
@@ -29,6 +30,7 @@ impl serde::Deserialize for Field {
             {
                 match value {
                     "patterns" => Ok(Field::PATTERNS),
+                    "include" => Ok(Field::INCLUDE),
                     _ => Err(serde::de::Error::custom(format!("Unexpected field: {}", value))),
                 }
             }
@@ -47,20 +49,64 @@ impl serde::de::Visitor for FileVisitor {
         where V: serde::de::MapVisitor
     {
         let mut patterns: Option<Vec<Pattern>> = None;
+        let mut includes: Option<Includes> = None;
 
         while let Some (field) = try!(visitor.visit_key()) {
             match field {
-                Field::PATTERNS => patterns = Some(try!(visitor.visit_value()))
+                Field::PATTERNS => patterns = Some(try!(visitor.visit_value())),
+                Field::INCLUDE => includes = Some(try!(visitor.visit_value())),
             }
         }
 
-        let patterns_final = match patterns {
-            Some(patterns) => patterns,
-            None => try!(visitor.missing_field("patterns")),
+        // a file which only includes other files doesn't need patterns
+        let patterns_final = match (patterns, includes.is_some()) {
+            (Some(patterns), _) => patterns,
+            (None, true) => Vec::new(),
+            (None, false) => try!(visitor.missing_field("patterns")),
         };
 
         try!(visitor.end());
 
-        Ok(PatternFile { patterns: patterns_final })
+        Ok(PatternFile {
+            patterns: patterns_final,
+            includes: includes.map_or_else(Vec::new, |includes| includes.0),
+        })
+    }
+}
+
+// The value of `include` can be a path or an array of paths.
+struct Includes(Vec<String>);
+
+impl serde::Deserialize for Includes {
+    fn deserialize<D>(deserializer: &mut D) -> Result<Includes, D::Error>
+        where D: serde::de::Deserializer
+    {
+        deserializer.deserialize(IncludesVisitor)
+    }
+}
+
+struct IncludesVisitor;
+
+impl serde::de::Visitor for IncludesVisitor {
+    type Value = Includes;
+
+    fn visit_str<E>(&mut self, value: &str) -> Result<Includes, E>
+        where E: serde::de::Error
+    {
+        Ok(Includes(vec![value.to_owned()]))
+    }
+
+    fn visit_seq<V>(&mut self, mut visitor: V) -> Result<Includes, V::Error>
+        where V: serde::de::SeqVisitor
+    {
+        let mut includes = Vec::new();
+
+        while let Some(include) = try!(visitor.visit()) {
+            includes.push(include);
+        }
+
+        try!(visitor.end());
+
+        Ok(Includes(includes))
     }
 }
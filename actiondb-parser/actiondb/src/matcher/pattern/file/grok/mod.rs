@@ -0,0 +1,307 @@
+// Conversion of the patterns into Grok expressions, like the ones in
+// Logstash's pattern files. Every pattern becomes a named expression which
+// has to match the whole message. The parsers are converted into the
+// standard Grok patterns if there is an equivalent one, the others (e.g.
+// SET) get their own pattern definitions before the expression.
+
+#[cfg(test)]
+mod test;
+
+use matcher::Pattern;
+use matcher::compiled_pattern::{CompiledPattern, TokenType};
+use matcher::pattern::file::Issue;
+use parsers::{Parser, OptionalParameter};
+
+// Grok's INT accepts a sign and its NUMBER doesn't have an exponent part.
+const INT_PATTERN: &'static str = "ACTIONDB_INT";
+const INT_REGEX: &'static str = "[0-9]+";
+const NUMBER_PATTERN: &'static str = "ACTIONDB_NUMBER";
+const NUMBER_REGEX: &'static str = r"[+-]?(?:[0-9]+(?:\.[0-9]+)?|\.[0-9]+)(?:[eE][+-]?[0-9]+)?";
+
+// Writes the patterns as a Grok pattern file. The expression of a pattern is
+// named after the pattern's name and UUID. The patterns which can't be
+// converted are left out, they and the left out constructs are reported as
+// issues.
+pub fn export(patterns: &[Pattern]) -> (String, Vec<Issue>) {
+    let mut issues = Vec::new();
+    let mut lines = vec![format!("{} {}", INT_PATTERN, INT_REGEX),
+                         format!("{} {}", NUMBER_PATTERN, NUMBER_REGEX)];
+
+    for pattern in patterns {
+        let source = format!("pattern={}", pattern.uuid().hyphenated());
+        let mut ignored = Vec::new();
+        match export_pattern(&expression_name(pattern), pattern.pattern(), &mut ignored) {
+            Ok(definitions) => {
+                lines.push(String::new());
+                let name = pattern.name().map(|name| format!(" {}", name)).unwrap_or_default();
+                lines.push(format!("# {}{}", pattern.uuid().hyphenated(), name));
+                lines.extend(definitions);
+            }
+            Err(reason) => {
+                issues.push(Issue::Skipped {
+                    source: source,
+                    reason: reason,
+                });
+                continue;
+            }
+        }
+
+        if pattern.values().is_some() {
+            ignored.push("values".to_owned());
+        }
+        if pattern.tags().is_some() {
+            ignored.push("tags".to_owned());
+        }
+        if pattern.priority() != 0 {
+            ignored.push("priority".to_owned());
+        }
+        ignored.sort();
+        ignored.dedup();
+        for construct in ignored {
+            issues.push(Issue::Ignored {
+                source: source.clone(),
+                construct: construct,
+            });
+        }
+    }
+    (lines.join("\n"), issues)
+}
+
+// Returns the lines of a Grok pattern file which define `name`: the custom
+// patterns used by the expression and the expression itself. The left out
+// constructs are pushed into `ignored`, the reason is returned if the
+// pattern can't be converted.
+pub fn export_pattern(name: &str,
+                      pattern: &CompiledPattern,
+                      ignored: &mut Vec<String>)
+                      -> Result<Vec<String>, String> {
+    let mut exporter = Exporter {
+        name: name,
+        definitions: Vec::new(),
+        ignored: ignored,
+    };
+    let expression = try!(exporter.export_tokens(pattern, true));
+    let mut lines = exporter.definitions;
+    lines.push(format!("{} ^{}$", name, expression));
+    Ok(lines)
+}
+
+struct Exporter<'a> {
+    name: &'a str,
+    // the lines of the custom patterns
+    definitions: Vec<String>,
+    ignored: &'a mut Vec<String>,
+}
+
+impl<'a> Exporter<'a> {
+    // `at_end` is true if nothing follows the tokens in the pattern.
+    fn export_tokens(&mut self, tokens: &CompiledPattern, at_end: bool) -> Result<String, String> {
+        let mut expression = String::new();
+        for (index, token) in tokens.iter().enumerate() {
+            let is_last = at_end && index + 1 == tokens.len();
+            match *token {
+                TokenType::Literal(ref literal) => {
+                    for c in literal.chars() {
+                        push_escaped(&mut expression, c, REGEX_SPECIAL_CHARS);
+                    }
+                }
+                TokenType::Parser(ref parser) => {
+                    expression.push_str(&try!(self.export_parser(&**parser, is_last)))
+                }
+                TokenType::Alternation(ref alternatives) => {
+                    let mut converted = Vec::new();
+                    for alternative in alternatives {
+                        converted.push(try!(self.export_tokens(alternative, false)));
+                    }
+                    expression.push_str(&format!("(?:{})", converted.join("|")));
+                }
+                TokenType::Optional(ref optional) => {
+                    let converted = try!(self.export_tokens(optional, false));
+                    expression.push_str(&format!("(?:{})?", converted));
+                }
+            }
+        }
+        Ok(expression)
+    }
+
+    fn export_parser(&mut self, parser: &Parser, is_last: bool) -> Result<String, String> {
+        let kind = parser.kind();
+        let mut argument = String::new();
+        let mut min_length = None;
+        let mut max_length = None;
+        let mut escape = None;
+        let mut unescape = false;
+        for parameter in parser.parameters() {
+            match parameter {
+                OptionalParameter::Int("min_len", length) => min_length = Some(length),
+                OptionalParameter::Int("max_len", length) => max_length = Some(length),
+                OptionalParameter::Str("escape", escape_character) => {
+                    escape = escape_character.chars().next()
+                }
+                OptionalParameter::Str(_, value) => argument = value,
+                OptionalParameter::Bool("unescape", value) => unescape = value,
+                OptionalParameter::Int(name, _) |
+                OptionalParameter::Float(name, _) |
+                OptionalParameter::Bool(name, true) => {
+                    self.ignored.push(format!("{} of {}", name, kind))
+                }
+                OptionalParameter::Bool(..) => {}
+            }
+        }
+        if escape.is_some() && unescape {
+            self.ignored.push(format!("unescape of {}", kind));
+        }
+
+        let pattern = match kind {
+            // the expression is anchored, so DATA extends to the next token
+            "GREEDY" if is_last => "GREEDYDATA".to_owned(),
+            "GREEDY" => "DATA".to_owned(),
+            "INT" if min_length.is_none() && max_length.is_none() => INT_PATTERN.to_owned(),
+            "INT" => self.define(format!("[0-9]{}", quantifier(min_length, max_length))),
+            "NUMBER" => NUMBER_PATTERN.to_owned(),
+            "IPV4" => "IPV4".to_owned(),
+            "IPV6" => "IPV6".to_owned(),
+            "IPADDR" => "IP".to_owned(),
+            "SET" => {
+                let set = convert_set(&argument);
+                self.define(format!("{}{}", set, quantifier(min_length, max_length)))
+            }
+            "QSTRING" => {
+                let quotes = argument.chars().collect::<Vec<_>>();
+                if quotes.len() != 2 {
+                    return Err(format!("unsupported quotes of QSTRING: '{}'", argument));
+                }
+                // the value is the content between the quotes
+                let mut excluded = String::new();
+                push_escaped(&mut excluded, quotes[1], CLASS_SPECIAL_CHARS);
+                let content = match escape {
+                    Some(escape) => {
+                        push_escaped(&mut excluded, escape, CLASS_SPECIAL_CHARS);
+                        let mut escaped = String::new();
+                        push_escaped(&mut escaped, escape, REGEX_SPECIAL_CHARS);
+                        format!("(?:[^{}]|{}.)*", excluded, escaped)
+                    }
+                    None => format!("[^{}]*", excluded),
+                };
+                let content = self.define(content);
+
+                let mut quoted = String::new();
+                push_escaped(&mut quoted, quotes[0], REGEX_SPECIAL_CHARS);
+                quoted.push_str(&reference(&content, parser.name()));
+                push_escaped(&mut quoted, quotes[1], REGEX_SPECIAL_CHARS);
+                return Ok(quoted);
+            }
+            "REGEX" => self.define(argument),
+            "TIMESTAMP" => {
+                match &argument[..] {
+                    "bsd" => "SYSLOGTIMESTAMP".to_owned(),
+                    "rfc3339" | "iso8601" => "TIMESTAMP_ISO8601".to_owned(),
+                    "clf" => "HTTPDATE".to_owned(),
+                    _ => return Err(format!("unsupported format of TIMESTAMP: {}", argument)),
+                }
+            }
+            _ => return Err(format!("the {} parser has no equivalent", kind)),
+        };
+        Ok(reference(&pattern, parser.name()))
+    }
+
+    // Adds a custom pattern and returns its name.
+    fn define(&mut self, regex: String) -> String {
+        let name = format!("{}_{}", self.name, self.definitions.len() + 1);
+        self.definitions.push(format!("{} {}", name, regex));
+        name
+    }
+}
+
+const REGEX_SPECIAL_CHARS: &'static str = r"\.+*?()|[]{}^$";
+const CLASS_SPECIAL_CHARS: &'static str = r"\[]^-";
+
+fn push_escaped(buffer: &mut String, c: char, special_chars: &str) {
+    match c {
+        '\t' => buffer.push_str(r"\t"),
+        '\n' => buffer.push_str(r"\n"),
+        '\r' => buffer.push_str(r"\r"),
+        _ => {
+            if special_chars.contains(c) {
+                buffer.push('\\');
+            }
+            buffer.push(c);
+        }
+    }
+}
+
+fn reference(pattern: &str, name: Option<&str>) -> String {
+    match name {
+        Some(name) => format!("%{{{}:{}}}", pattern, name),
+        None => format!("%{{{}}}", pattern),
+    }
+}
+
+fn quantifier(min_length: Option<usize>, max_length: Option<usize>) -> String {
+    match (min_length, max_length) {
+        (None, None) => "+".to_owned(),
+        (min_length, Some(max_length)) => format!("{{{},{}}}", min_length.unwrap_or(1), max_length),
+        (Some(min_length), None) => format!("{{{},}}", min_length),
+    }
+}
+
+// The character sets are written as regex character classes. Their syntax
+// is similar, but the escaped characters and the brackets which don't start
+// a named class are literals in the sets.
+fn convert_set(set: &str) -> String {
+    let mut class = "[".to_owned();
+    let mut rest = set;
+    if rest.starts_with('^') && rest.len() > 1 {
+        class.push('^');
+        rest = &rest[1..];
+    }
+
+    let mut chars = rest.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '[' => {
+                let tail = chars.as_str();
+                match tail.find(":]") {
+                    Some(end) if tail.starts_with(':') && end > 0 &&
+                                 tail[1..end].chars().all(|c| c >= 'a' && c <= 'z') => {
+                        class.push('[');
+                        class.push_str(&tail[..end + 2]);
+                        chars = tail[end + 2..].chars();
+                    }
+                    _ => class.push_str(r"\["),
+                }
+            }
+            '\\' => {
+                match chars.next() {
+                    Some('t') => class.push_str(r"\t"),
+                    Some('n') => class.push_str(r"\n"),
+                    Some('r') => class.push_str(r"\r"),
+                    Some(escaped) => push_escaped(&mut class, escaped, CLASS_SPECIAL_CHARS),
+                    None => class.push_str(r"\\"),
+                }
+            }
+            ']' => class.push_str(r"\]"),
+            // `&&` is the intersection of classes
+            '&' if class.ends_with('&') => class.push_str(r"\&"),
+            _ => class.push(c),
+        }
+    }
+    class.push(']');
+    class
+}
+
+fn expression_name(pattern: &Pattern) -> String {
+    let name = pattern.name()
+                      .unwrap_or("PATTERN")
+                      .chars()
+                      .map(|c| {
+                          if (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') ||
+                             (c >= '0' && c <= '9') {
+                              c
+                          } else {
+                              '_'
+                          }
+                      })
+                      .collect::<String>();
+    format!("{}_{}", name, pattern.uuid().simple()).to_uppercase()
+}
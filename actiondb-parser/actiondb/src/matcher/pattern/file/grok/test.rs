@@ -0,0 +1,79 @@
+use matcher::pattern::file::{Issue, PatternFile};
+use grammar;
+use super::{export, export_pattern};
+
+use serde_json;
+
+fn export_lines(pattern: &str) -> Result<Vec<String>, String> {
+    let pattern = grammar::parser::pattern(pattern).unwrap();
+    export_pattern("NAME", &pattern, &mut Vec::new())
+}
+
+#[test]
+fn test_given_pattern_when_it_is_exported_to_grok_then_the_parsers_become_standard_patterns() {
+    let lines = export_lines("Accepted %{(publickey|password)} for %{GREEDY:user} from \
+                              %{IPADDR:ip} port %{INT:port}%{( ssh2)?} [%{NUMBER}] %{GREEDY}")
+                    .unwrap();
+    assert_eq!(lines,
+               vec![concat!(r"NAME ^Accepted (?:publickey|password) for %{DATA:user} from ",
+                            r"%{IP:ip} port %{ACTIONDB_INT:port}(?: ssh2)? \[%{ACTIONDB_NUMBER}\] ",
+                            r"%{GREEDYDATA}$")]);
+}
+
+#[test]
+fn test_given_pattern_when_its_parsers_have_no_standard_pattern_then_they_get_custom_ones() {
+    let lines = export_lines(concat!(r#"%{SET("[:alpha:]_\-\]", min_len=2):s} "#,
+                                     r#"%{INT(max_len=3):i} %{QSTRING("<>"):q} "#,
+                                     r#"%{QSTRING("\"\"", escape="\\"):e} %{REGEX("a+b"):r}"#))
+                    .unwrap();
+    assert_eq!(lines,
+               vec![r"NAME_1 [[:alpha:]_\-\]]{2,}",
+                    r"NAME_2 [0-9]{1,3}",
+                    r"NAME_3 [^>]*",
+                    r#"NAME_4 (?:[^"\\]|\\.)*"#,
+                    r"NAME_5 a+b",
+                    r#"NAME ^%{NAME_1:s} %{NAME_2:i} <%{NAME_3:q}> "%{NAME_4:e}" %{NAME_5:r}$"#]);
+}
+
+#[test]
+fn test_given_pattern_when_its_parsers_have_no_grok_equivalent_then_it_is_not_exported() {
+    assert!(export_lines("%{KV}").is_err());
+    assert!(export_lines(r#"%{TIMESTAMP(format="%H:%M"):time}"#).is_err());
+    assert!(export_lines(r#"%{QSTRING("\"\"''")}"#).is_err());
+}
+
+#[test]
+fn test_given_pattern_file_when_it_is_exported_to_grok_then_the_lost_constructs_are_reported() {
+    let file = serde_json::from_str::<PatternFile>(r#"{
+      "patterns": [
+        {
+          "uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+          "name": "ssh.login",
+          "pattern": "login %{NUMBER(min=0):n}",
+          "values": {"kind": "login"}
+        },
+        {
+          "uuid": "fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2",
+          "pattern": "%{KV}"
+        }
+      ]
+    }"#)
+                   .unwrap();
+    let (grok, issues) = export(file.patterns());
+    assert!(grok.ends_with("\n\n# 9a49c47d-29e9-4072-be84-3b76c6814743 ssh.login\n\
+                            SSH_LOGIN_9A49C47D29E94072BE843B76C6814743 ^login \
+                            %{ACTIONDB_NUMBER:n}$"));
+    assert_eq!(issues,
+               vec![Issue::Ignored {
+                        source: "pattern=9a49c47d-29e9-4072-be84-3b76c6814743".to_owned(),
+                        construct: "min of NUMBER".to_owned(),
+                    },
+                    Issue::Ignored {
+                        source: "pattern=9a49c47d-29e9-4072-be84-3b76c6814743".to_owned(),
+                        construct: "values".to_owned(),
+                    },
+                    Issue::Skipped {
+                        source: "pattern=fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2".to_owned(),
+                        reason: "the KV parser has no equivalent".to_owned(),
+                    }]);
+}
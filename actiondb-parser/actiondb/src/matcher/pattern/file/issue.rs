@@ -0,0 +1,35 @@
+use std::fmt;
+
+// A construct which can't be converted exactly between the pattern files and
+// an other format (e.g. syslog-ng's patterndb).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Issue {
+    // The rule or pattern (or one of its parts) is not converted, because it
+    // uses constructs without an equivalent.
+    Skipped {
+        source: String,
+        reason: String,
+    },
+    // The rule or pattern is converted, but `construct` is left out or
+    // changed.
+    Ignored {
+        source: String,
+        construct: String,
+    },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            Issue::Skipped{ref source, ref reason} => {
+                write!(fmt, "Not converted: {} reason='{}'", source, reason)
+            }
+            Issue::Ignored{ref source, ref construct} => {
+                write!(fmt,
+                       "Converted without an equivalent of: {} construct='{}'",
+                       source,
+                       construct)
+            }
+        }
+    }
+}
@@ -3,25 +3,26 @@ use matcher::pattern::Pattern;
 use matcher::pattern::source::BuildResult;
 
 use std::iter;
+use std::vec;
 
 impl iter::IntoIterator for PatternFile {
     type Item = BuildResult;
     type IntoIter = IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
-        IntoIter { patterns: self.patterns }
+        IntoIter { patterns: self.patterns.into_iter() }
     }
 }
 
 pub struct IntoIter {
-    patterns: Vec<Pattern>,
+    patterns: vec::IntoIter<Pattern>,
 }
 
 impl Iterator for IntoIter {
     type Item = BuildResult;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.patterns.pop() {
+        match self.patterns.next() {
             Some(pattern) => Some(Ok(pattern)),
             None => None,
         }
@@ -1,14 +1,33 @@
+pub use self::issue::Issue;
+
+pub mod grok;
+pub mod patterndb;
 mod deser;
+mod issue;
 mod iter;
+mod ser;
 
 use matcher::pattern::Pattern;
 
 pub struct PatternFile {
     pub patterns: Vec<Pattern>,
+    // the paths of the included pattern files, relative to this one
+    pub includes: Vec<String>,
 }
 
 impl PatternFile {
+    pub fn new(patterns: Vec<Pattern>) -> PatternFile {
+        PatternFile {
+            patterns: patterns,
+            includes: Vec::new(),
+        }
+    }
+
     pub fn patterns(&self) -> &Vec<Pattern> {
         &self.patterns
     }
+
+    pub fn includes(&self) -> &[String] {
+        &self.includes
+    }
 }
@@ -0,0 +1,135 @@
+use grammar::{escape_literal, escape_string};
+use uuid::Uuid;
+
+use std::hash::{Hasher, SipHasher};
+
+const PARSER_DELIMITER: char = '@';
+const PARAMETER_SEPARATOR: char = ':';
+
+// Converts a patterndb pattern (e.g. `port @NUMBER:port@`) into the pattern
+// grammar. The changed constructs are pushed into `ignored`, the reason is
+// returned if the pattern uses a parser which has no equivalent.
+pub fn convert_pattern(pattern: &str, ignored: &mut Vec<String>) -> Result<String, String> {
+    let mut converted = String::new();
+    let mut literal = String::new();
+    let mut rest = pattern;
+
+    while let Some(start) = rest.find(PARSER_DELIMITER) {
+        literal.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+        // `@@` is a literal `@`
+        if rest.starts_with(PARSER_DELIMITER) {
+            literal.push(PARSER_DELIMITER);
+            rest = &rest[1..];
+            continue;
+        }
+        let end = try!(rest.find(PARSER_DELIMITER)
+                           .ok_or_else(|| format!("unterminated parser: @{}", rest)));
+        converted.push_str(&escape_literal(&literal));
+        literal.clear();
+        let (parser, end_string) = try!(convert_parser(&rest[..end], ignored));
+        converted.push_str(&parser);
+        literal.push_str(&end_string);
+        rest = &rest[end + 1..];
+    }
+
+    literal.push_str(rest);
+    converted.push_str(&escape_literal(&literal));
+    Ok(converted)
+}
+
+// Returns the converted parser and the literal which has to follow it.
+fn convert_parser(parser: &str, ignored: &mut Vec<String>) -> Result<(String, String), String> {
+    let mut parts = parser.splitn(3, PARAMETER_SEPARATOR);
+    let kind = parts.next().unwrap_or("");
+    let name = try!(convert_name(parts.next().unwrap_or("")));
+    let parameter = parts.next().unwrap_or("");
+
+    let converted = match kind {
+        // the end string is consumed by ESTRING, but it's not in the value
+        "ESTRING" if parameter.is_empty() => {
+            return Err("ESTRING doesn't have an end string".to_owned())
+        }
+        "ESTRING" => return Ok((format!("%{{GREEDY{}}}", name), parameter.to_owned())),
+        "ANYSTRING" => format!("%{{GREEDY{}}}", name),
+        // INT doesn't parse the signed and the hexadecimal numbers
+        "NUMBER" => {
+            ignored.push("sign and hexadecimal form of NUMBER, it's imported as INT".to_owned());
+            format!("%{{INT{}}}", name)
+        }
+        "DOUBLE" | "FLOAT" => format!("%{{NUMBER{}}}", name),
+        "IPv4" => format!("%{{IPV4{}}}", name),
+        "IPv6" => format!("%{{IPV6{}}}", name),
+        "IPvANY" => format!("%{{IPADDR{}}}", name),
+        "STRING" => {
+            let set = format!("[:alnum:]{}", escape_set(parameter));
+            format!("%{{SET({}){}}}", try!(quote_set(&set)), name)
+        }
+        "SET" => format!("%{{SET({}){}}}", try!(quote_set(&escape_set(parameter))), name),
+        "QSTRING" => {
+            let quotes = match parameter.chars().count() {
+                1 => format!("{}{}", parameter, parameter),
+                2 => parameter.to_owned(),
+                _ => return Err(format!("unsupported quotes of QSTRING: '{}'", parameter)),
+            };
+            format!("%{{QSTRING({}){}}}", escape_string(&quotes), name)
+        }
+        "PCRE" => format!("%{{REGEX({}){}}}", escape_string(parameter), name),
+        _ => return Err(format!("the {} parser has no equivalent", kind)),
+    };
+    Ok((converted, String::new()))
+}
+
+fn convert_name(name: &str) -> Result<String, String> {
+    if name.is_empty() {
+        return Ok(String::new());
+    }
+    let is_valid = name.chars().enumerate().all(|(index, c)| {
+        (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || c == '_' || c == '.' ||
+        (index > 0 && c >= '0' && c <= '9')
+    });
+    if is_valid {
+        Ok(format!(":{}", name))
+    } else {
+        Err(format!("unsupported parser name: {}", name))
+    }
+}
+
+// The characters of patterndb's sets don't have special meanings.
+fn escape_set(characters: &str) -> String {
+    let mut escaped = String::new();
+    for c in characters.chars() {
+        if c == '\\' || c == '-' || c == '[' || c == '^' {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// The set parameter of SET can't be escaped, so it can't contain quotes.
+fn quote_set(set: &str) -> Result<String, String> {
+    if set.contains('"') {
+        Err(format!("unsupported quotation mark in a character set: {}", set))
+    } else {
+        Ok(format!("\"{}\"", set))
+    }
+}
+
+// Derives a UUID from the name, so converting the same file again gives the
+// same UUIDs. The name is hashed with two SipHash keys and the version of
+// the UUID is 8 (custom), because uuid can't create name based UUIDs.
+pub fn derive_uuid(name: &str) -> Uuid {
+    let mut bytes = [0u8; 16];
+    for (key, chunk) in bytes.chunks_mut(8).enumerate() {
+        let mut hasher = SipHasher::new_with_keys(0, key as u64);
+        hasher.write(name.as_bytes());
+        let hash = hasher.finish();
+        for (index, byte) in chunk.iter_mut().enumerate() {
+            *byte = (hash >> (index * 8)) as u8;
+        }
+    }
+    bytes[6] = bytes[6] & 0x0f | 0x80;
+    bytes[8] = bytes[8] & 0x3f | 0x80;
+    Uuid::from_bytes(&bytes).expect("16 bytes are always a valid UUID")
+}
@@ -0,0 +1,342 @@
+use matcher::Pattern;
+use matcher::compiled_pattern::{expand_alternatives, CompiledPattern, TokenType};
+use matcher::pattern::file::Issue;
+use parsers::{Parser, OptionalParameter};
+use super::convert::derive_uuid;
+
+const PARSER_DELIMITER: char = '@';
+const PROVIDER: &'static str = "actiondb";
+
+// Writes the patterns as the rules of a patterndb XML ruleset. Its UUID is
+// derived from its name and it doesn't have a publication date, so exporting
+// the same patterns gives the same XML. The ruleset doesn't have program
+// patterns, so syslog-ng uses it as a fallback for the messages of every
+// program. The patterns which can't be converted are left out, they and the
+// left out constructs are reported as issues.
+pub fn export(patterns: &[Pattern], ruleset: &str) -> (String, Vec<Issue>) {
+    let mut issues = Vec::new();
+    let mut lines = vec!["<?xml version='1.0' encoding='UTF-8'?>".to_owned(),
+                         "<patterndb version='4'>".to_owned(),
+                         format!("  <ruleset name='{}' id='{}'>",
+                                 escape_xml(ruleset),
+                                 derive_uuid(&format!("ruleset={}", ruleset)).hyphenated()),
+                         "    <rules>".to_owned()];
+
+    for pattern in patterns {
+        let source = format!("pattern={}", pattern.uuid().hyphenated());
+        let mut ignored = Vec::new();
+        match export_pattern(pattern.pattern(), &mut ignored) {
+            Ok(rule_patterns) => write_rule(&mut lines, pattern, &rule_patterns, &mut ignored),
+            Err(reason) => {
+                issues.push(Issue::Skipped {
+                    source: source,
+                    reason: reason,
+                });
+                continue;
+            }
+        }
+        ignored.sort();
+        ignored.dedup();
+        for construct in ignored {
+            issues.push(Issue::Ignored {
+                source: source.clone(),
+                construct: construct,
+            });
+        }
+    }
+
+    lines.push("    </rules>".to_owned());
+    lines.push("  </ruleset>".to_owned());
+    lines.push("</patterndb>".to_owned());
+    (lines.join("\n"), issues)
+}
+
+// Converts a pattern into patterndb patterns, one for each variant of its
+// groups. The left out constructs are pushed into `ignored`, the reason is
+// returned if the pattern can't be converted.
+pub fn export_pattern(pattern: &CompiledPattern,
+                      ignored: &mut Vec<String>)
+                      -> Result<Vec<String>, String> {
+    let mut patterns = Vec::new();
+    for variant in try!(expand_alternatives(pattern)) {
+        let mut converted = String::new();
+        // the beginning of a literal can be consumed by the parser before it
+        let mut consumed = 0;
+        for (index, token) in variant.iter().enumerate() {
+            match *token {
+                TokenType::Literal(ref literal) => {
+                    converted.push_str(&escape_literal(&literal[consumed..]));
+                    consumed = 0;
+                }
+                TokenType::Parser(ref parser) => {
+                    let next_literal = match variant.get(index + 1) {
+                        Some(&TokenType::Literal(ref literal)) => Some(&literal[..]),
+                        Some(_) => Some(""),
+                        None => None,
+                    };
+                    let (parser, end_length) = try!(export_parser(&**parser,
+                                                                  next_literal,
+                                                                  ignored));
+                    converted.push_str(&parser);
+                    consumed = end_length;
+                }
+                _ => unreachable!("the variants don't contain groups"),
+            }
+        }
+        patterns.push(converted);
+    }
+    Ok(patterns)
+}
+
+// Returns the converted parser and the length of the beginning of the next
+// literal which it consumes. `next_literal` is empty if the parser is
+// followed by an other parser and it's None at the end of the pattern.
+fn export_parser(parser: &Parser,
+                 next_literal: Option<&str>,
+                 ignored: &mut Vec<String>)
+                 -> Result<(String, usize), String> {
+    let name = parser.name();
+    let kind = parser.kind();
+    let mut ignore_constraints = || {
+        for parameter in parser.parameters() {
+            match parameter {
+                OptionalParameter::Int(parameter, _) |
+                OptionalParameter::Float(parameter, _) => {
+                    ignored.push(format!("{} of {}", parameter, kind))
+                }
+                OptionalParameter::Str("escape", _) => {
+                    ignored.push(format!("escape of {}", kind))
+                }
+                _ => {}
+            }
+        }
+    };
+
+    let converted = match kind {
+        // ESTRING stops at the first occurrence of its end string, which
+        // can't contain the delimiter
+        "GREEDY" => {
+            match next_literal {
+                Some("") => return Err("GREEDY is followed by a parser".to_owned()),
+                Some(literal) => {
+                    let end = literal.split(PARSER_DELIMITER).next().unwrap_or("");
+                    if end.is_empty() {
+                        return Err(format!("the end string of GREEDY starts with {}",
+                                           PARSER_DELIMITER));
+                    }
+                    return Ok((format_parser("ESTRING", name, Some(end)), end.len()));
+                }
+                None => format_parser("ANYSTRING", name, None),
+            }
+        }
+        "INT" => {
+            ignore_constraints();
+            format_parser("NUMBER", name, None)
+        }
+        "NUMBER" => {
+            ignore_constraints();
+            format_parser("FLOAT", name, None)
+        }
+        "IPV4" => format_parser("IPv4", name, None),
+        "IPV6" => format_parser("IPv6", name, None),
+        "IPADDR" => format_parser("IPvANY", name, None),
+        "SET" => {
+            ignore_constraints();
+            let set = string_parameter(parser, "set");
+            match convert_set(&set) {
+                Some((true, characters)) => format_parser("STRING", name, Some(&characters)),
+                Some((false, characters)) => format_parser("SET", name, Some(&characters)),
+                None => return Err(format!("unsupported character set: {}", set)),
+            }
+        }
+        "QSTRING" => {
+            ignore_constraints();
+            let quotes = string_parameter(parser, "quotes");
+            let chars = quotes.chars().collect::<Vec<_>>();
+            if chars.len() != 2 || quotes.contains(PARSER_DELIMITER) {
+                return Err(format!("unsupported quotes of QSTRING: '{}'", quotes));
+            }
+            // a single character is both the opening and the closing quote
+            let quotes = if chars[0] == chars[1] { chars[0].to_string() } else { quotes };
+            format_parser("QSTRING", name, Some(&quotes))
+        }
+        "REGEX" => {
+            let regex = string_parameter(parser, "regex");
+            if regex.contains(PARSER_DELIMITER) {
+                return Err(format!("unsupported {} in a regex: {}", PARSER_DELIMITER, regex));
+            }
+            format_parser("PCRE", name, Some(&regex))
+        }
+        _ => return Err(format!("the {} parser has no equivalent", kind)),
+    };
+    Ok((converted, 0))
+}
+
+fn string_parameter(parser: &Parser, name: &str) -> String {
+    for parameter in parser.parameters() {
+        match parameter {
+            OptionalParameter::Str(parameter, value) if parameter == name => return value,
+            _ => {}
+        }
+    }
+    String::new()
+}
+
+fn format_parser(kind: &str, name: Option<&str>, parameter: Option<&str>) -> String {
+    match parameter {
+        Some(parameter) => format!("@{}:{}:{}@", kind, name.unwrap_or(""), parameter),
+        None => {
+            match name {
+                Some(name) => format!("@{}:{}@", kind, name),
+                None => format!("@{}@", kind),
+            }
+        }
+    }
+}
+
+// The characters of patterndb's sets don't have special meanings, so only
+// the sets without negation and character classes are converted. The ASCII
+// ranges are expanded and a leading `[:alnum:]` is converted to STRING.
+// Returns whether the set is a STRING and its characters.
+fn convert_set(set: &str) -> Option<(bool, String)> {
+    let (alnum, set) = if set.starts_with("[:alnum:]") {
+        (true, &set["[:alnum:]".len()..])
+    } else {
+        (false, set)
+    };
+    if !alnum && set.starts_with('^') && set.len() > 1 {
+        return None;
+    }
+
+    // the characters and whether they are escaped
+    let mut chars = Vec::new();
+    let mut rest = set.chars();
+    while let Some(c) = rest.next() {
+        if c == '[' && rest.as_str().starts_with(':') {
+            return None;
+        }
+        if c != '\\' {
+            chars.push((c, false));
+            continue;
+        }
+        match rest.next() {
+            Some('t') => chars.push(('\t', true)),
+            Some('n') => chars.push(('\n', true)),
+            Some('r') => chars.push(('\r', true)),
+            Some(escaped) => chars.push((escaped, true)),
+            None => chars.push(('\\', false)),
+        }
+    }
+
+    let mut characters = String::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let first = chars[index].0;
+        match (chars.get(index + 1), chars.get(index + 2)) {
+            (Some(&('-', false)), Some(&(last, _))) if first <= last => {
+                if last > '\x7f' {
+                    return None;
+                }
+                for code in first as u8..last as u8 + 1 {
+                    characters.push(code as char);
+                }
+                index += 3;
+            }
+            _ => {
+                characters.push(first);
+                index += 1;
+            }
+        }
+    }
+
+    if characters.contains(PARSER_DELIMITER) {
+        None
+    } else {
+        Some((alnum, characters))
+    }
+}
+
+fn write_rule(lines: &mut Vec<String>,
+              pattern: &Pattern,
+              rule_patterns: &[String],
+              ignored: &mut Vec<String>) {
+    let class = pattern.name()
+                       .map(|name| format!(" class='{}'", escape_xml(name)))
+                       .unwrap_or_default();
+    lines.push(format!("      <rule provider='{}' id='{}'{}>",
+                       PROVIDER,
+                       pattern.uuid().hyphenated(),
+                       class));
+    if pattern.priority() != 0 {
+        ignored.push("priority".to_owned());
+    }
+
+    lines.push("        <patterns>".to_owned());
+    for rule_pattern in rule_patterns {
+        lines.push(format!("          <pattern>{}</pattern>", escape_xml(rule_pattern)));
+    }
+    lines.push("        </patterns>".to_owned());
+
+    let test_messages = pattern.test_messages().unwrap_or(&[]);
+    if !test_messages.is_empty() {
+        lines.push("        <examples>".to_owned());
+        for test_message in test_messages {
+            lines.push("          <example>".to_owned());
+            lines.push(format!("            <test_message>{}</test_message>",
+                               escape_xml(test_message.message())));
+            if !test_message.values().is_empty() {
+                lines.push("            <test_values>".to_owned());
+                for (key, value) in test_message.values() {
+                    lines.push(format!("              <test_value name='{}'>{}</test_value>",
+                                       escape_xml(key),
+                                       escape_xml(value)));
+                }
+                lines.push("            </test_values>".to_owned());
+            }
+            lines.push("          </example>".to_owned());
+            if test_message.tags().is_some() {
+                ignored.push("tags of the test messages".to_owned());
+            }
+        }
+        lines.push("        </examples>".to_owned());
+    }
+
+    if let Some(tags) = pattern.tags() {
+        lines.push("        <tags>".to_owned());
+        for tag in tags {
+            lines.push(format!("          <tag>{}</tag>", escape_xml(tag)));
+        }
+        lines.push("        </tags>".to_owned());
+    }
+
+    if let Some(values) = pattern.values() {
+        lines.push("        <values>".to_owned());
+        for (key, value) in values {
+            // the values are templates, `$$` is a literal `$`
+            lines.push(format!("          <value name='{}'>{}</value>",
+                               escape_xml(key),
+                               escape_xml(&value.replace('$', "$$"))));
+        }
+        lines.push("        </values>".to_owned());
+    }
+    lines.push("      </rule>".to_owned());
+}
+
+fn escape_literal(literal: &str) -> String {
+    literal.replace('@', "@@")
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '\'' => escaped.push_str("&apos;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
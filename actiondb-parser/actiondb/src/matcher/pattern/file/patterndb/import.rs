@@ -0,0 +1,286 @@
+use uuid::Uuid;
+use xml::attribute::OwnedAttribute;
+use xml::reader::{EventReader, XmlEvent, Error};
+
+use grammar;
+use matcher::{Matcher, MatcherFactory, Pattern};
+use matcher::compiled_pattern::CompiledPattern;
+use matcher::pattern::file::{Issue, PatternFile};
+use matcher::pattern::testmessage::TestMessage;
+use matcher::trie::factory::TrieMatcherFactory;
+use super::convert::{convert_pattern, derive_uuid};
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::mem;
+
+// The attributes of the rules which have no equivalent.
+const CONTEXT_ATTRIBUTES: &'static [&'static str] = &["context-id",
+                                                      "context-scope",
+                                                      "context-timeout"];
+
+// Reads a patterndb XML file and converts its rules into patterns. The
+// constructs which have no actiondb equivalent are reported as issues. The
+// examples which don't match their converted patterns are left out, so
+// the patterns can be loaded.
+pub fn import<R: Read>(reader: R) -> Result<(PatternFile, Vec<Issue>), Error> {
+    let mut importer = Importer::default();
+    for event in EventReader::new(reader) {
+        match try!(event) {
+            XmlEvent::StartElement { name, attributes, .. } => {
+                importer.start_element(&name.local_name, &attributes)
+            }
+            XmlEvent::EndElement { name } => importer.end_element(&name.local_name),
+            XmlEvent::Characters(text) |
+            XmlEvent::CData(text) |
+            XmlEvent::Whitespace(text) => importer.text.push_str(&text),
+            _ => {}
+        }
+    }
+    Ok((PatternFile::new(importer.patterns), importer.issues))
+}
+
+#[derive(Default)]
+struct Ruleset {
+    name: String,
+    // the programs whose messages are matched against the rules
+    programs: Vec<String>,
+}
+
+#[derive(Default)]
+struct Rule {
+    id: String,
+    class: Option<String>,
+    patterns: Vec<String>,
+    examples: Vec<Example>,
+    tags: Vec<String>,
+    values: BTreeMap<String, String>,
+    ignored: Vec<String>,
+}
+
+#[derive(Default)]
+struct Example {
+    message: String,
+    values: BTreeMap<String, String>,
+}
+
+#[derive(Default)]
+struct Importer {
+    // the names of the open elements
+    elements: Vec<String>,
+    text: String,
+    // the name attribute of the open value or test_value element
+    key: String,
+    ruleset: Ruleset,
+    rule: Rule,
+    example: Example,
+    patterns: Vec<Pattern>,
+    issues: Vec<Issue>,
+}
+
+impl Importer {
+    fn start_element(&mut self, element: &str, attributes: &[OwnedAttribute]) {
+        let attribute = |name: &str| {
+            attributes.iter()
+                      .find(|attribute| attribute.name.local_name == name)
+                      .map(|attribute| attribute.value.clone())
+        };
+
+        match element {
+            "ruleset" => {
+                self.ruleset = Ruleset::default();
+                self.ruleset.name = attribute("name").unwrap_or_default();
+            }
+            "rule" => {
+                self.rule = Rule::default();
+                self.rule.id = attribute("id").unwrap_or_default();
+                self.rule.class = attribute("class");
+                for name in CONTEXT_ATTRIBUTES {
+                    if attribute(name).is_some() {
+                        self.rule.ignored.push(format!("{} attribute", name));
+                    }
+                }
+            }
+            "example" => self.example = Example::default(),
+            "value" | "test_value" => self.key = attribute("name").unwrap_or_default(),
+            "actions" => self.rule.ignored.push("actions".to_owned()),
+            _ => {}
+        }
+        self.elements.push(element.to_owned());
+        self.text.clear();
+    }
+
+    fn end_element(&mut self, element: &str) {
+        self.elements.pop();
+        let text = mem::replace(&mut self.text, String::new());
+        // the actions are not imported, but they can contain values too
+        if self.elements.iter().any(|element| element == "actions") {
+            return;
+        }
+        let parent = self.elements.last().map_or("", |parent| &parent[..]);
+
+        match (parent, element) {
+            ("ruleset", "pattern") => self.ruleset.programs.push(text),
+            ("patterns", "pattern") => self.rule.patterns.push(text),
+            ("tags", "tag") => self.rule.tags.push(text),
+            ("values", "value") => {
+                self.rule.values.insert(mem::replace(&mut self.key, String::new()), text);
+            }
+            ("example", "test_message") => self.example.message = text,
+            ("test_values", "test_value") => {
+                self.example.values.insert(mem::replace(&mut self.key, String::new()), text);
+            }
+            (_, "example") => {
+                let example = mem::replace(&mut self.example, Example::default());
+                self.rule.examples.push(example);
+            }
+            (_, "rule") => {
+                let rule = mem::replace(&mut self.rule, Rule::default());
+                self.import_rule(rule);
+            }
+            (_, "ruleset") if !self.ruleset.programs.is_empty() => {
+                self.issues.push(Issue::Ignored {
+                    source: format!("ruleset={}", self.ruleset.name),
+                    construct: format!("program patterns: {}", self.ruleset.programs.join(", ")),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn import_rule(&mut self, rule: Rule) {
+        let source = format!("rule={}", rule.id);
+        for construct in &rule.ignored {
+            self.ignore(&source, construct.clone());
+        }
+        let templates = rule.values.iter().filter(|&(_, value)| value.contains('$'));
+        for key in templates.map(|(key, _)| key) {
+            self.ignore(&source,
+                        format!("template in the value of {}, it's imported as a literal", key));
+        }
+
+        let mut compiled_patterns = Vec::new();
+        for pattern in &rule.patterns {
+            let pattern_source = format!("{} pattern='{}'", source, pattern);
+            let mut ignored = Vec::new();
+            match convert_pattern(pattern, &mut ignored)
+                      .and_then(|converted| parse_pattern(&converted)) {
+                Ok(compiled_pattern) => compiled_patterns.push(compiled_pattern),
+                Err(reason) => {
+                    self.skip(pattern_source, reason);
+                    continue;
+                }
+            }
+            ignored.sort();
+            ignored.dedup();
+            for construct in ignored {
+                self.ignore(&pattern_source, construct);
+            }
+        }
+        if rule.patterns.is_empty() {
+            self.skip(source.clone(), "the rule doesn't have any patterns".to_owned());
+        }
+
+        // only the first pattern can have the id of the rule, the UUIDs of the
+        // others are derived from it, so they don't change between imports
+        let rule_uuid = Uuid::parse_str(&rule.id).ok();
+        let mut uuids = Vec::new();
+        for index in 0..compiled_patterns.len() {
+            let uuid = match rule_uuid {
+                Some(uuid) if index == 0 => uuid,
+                Some(_) => {
+                    let uuid = derive_uuid(&format!("{}#{}", rule.id, index + 1));
+                    self.ignore(&source,
+                                format!("id of the rule's pattern #{}, it's imported with the \
+                                         UUID {}",
+                                        index + 1,
+                                        uuid.hyphenated()));
+                    uuid
+                }
+                None => {
+                    let uuid = derive_uuid(&format!("{}#{}", rule.id, index + 1));
+                    self.ignore(&source,
+                                format!("id which is not a UUID, the rule's pattern #{} is \
+                                         imported with the UUID {}",
+                                        index + 1,
+                                        uuid.hyphenated()));
+                    uuid
+                }
+            };
+            uuids.push(uuid);
+        }
+
+        let values = if rule.values.is_empty() { None } else { Some(rule.values.clone()) };
+        let tags = if rule.tags.is_empty() { None } else { Some(rule.tags.clone()) };
+        let patterns = compiled_patterns.into_iter()
+                                        .zip(uuids)
+                                        .map(|(compiled_pattern, uuid)| {
+                                            Pattern::new(rule.class.clone(),
+                                                         uuid,
+                                                         compiled_pattern,
+                                                         None,
+                                                         values.clone(),
+                                                         tags.clone())
+                                        })
+                                        .collect::<Vec<_>>();
+
+        let mut test_messages = patterns.iter().map(|_| Vec::new()).collect::<Vec<_>>();
+        for example in rule.examples {
+            let test_message = TestMessage::new(example.message, example.values, None);
+            match find_matching_pattern(&patterns, &test_message) {
+                Ok(index) => test_messages[index].push(test_message),
+                Err(reason) => {
+                    self.skip(format!("{} example='{}'", source, test_message.message()), reason)
+                }
+            }
+        }
+
+        for (pattern, test_messages) in patterns.into_iter().zip(test_messages) {
+            self.patterns.push(Pattern::new(pattern.name().map(|name| name.to_owned()),
+                                            pattern.uuid().clone(),
+                                            pattern.pattern().clone(),
+                                            Some(test_messages),
+                                            values.clone(),
+                                            tags.clone()));
+        }
+    }
+
+    fn skip(&mut self, source: String, reason: String) {
+        self.issues.push(Issue::Skipped {
+            source: source,
+            reason: reason,
+        });
+    }
+
+    fn ignore(&mut self, source: &str, construct: String) {
+        self.issues.push(Issue::Ignored {
+            source: source.to_owned(),
+            construct: construct,
+        });
+    }
+}
+
+fn parse_pattern(pattern: &str) -> Result<CompiledPattern, String> {
+    grammar::parser::pattern(pattern)
+        .map_err(|error| format!("the converted pattern is invalid: {} error={}", pattern, error))
+}
+
+// Returns the index of the first pattern which matches the test message
+// with the expected values.
+fn find_matching_pattern(patterns: &[Pattern],
+                         test_message: &TestMessage)
+                         -> Result<usize, String> {
+    let mut reason = "the example doesn't match the pattern".to_owned();
+    for (index, pattern) in patterns.iter().enumerate() {
+        let mut matcher = TrieMatcherFactory::new_matcher();
+        matcher.add_pattern(pattern.clone());
+        matcher.sort_parsers();
+        if let Some(result) = matcher.parse(test_message.message()) {
+            match test_message.test_result(&result) {
+                Ok(()) => return Ok(index),
+                Err(error) => reason = error.to_string(),
+            }
+        }
+    }
+    Err(reason)
+}
@@ -0,0 +1,13 @@
+// Conversion between the patterns and syslog-ng's patterndb XML rulesets.
+// A rule corresponds to a pattern: the rule's id is the UUID, its class is
+// the name, its tags and values are kept and its examples are the test
+// messages.
+
+pub use self::export::{export, export_pattern};
+pub use self::import::import;
+
+#[cfg(test)]
+mod test;
+mod convert;
+mod export;
+mod import;
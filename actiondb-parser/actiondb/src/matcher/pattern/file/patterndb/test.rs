@@ -0,0 +1,265 @@
+use matcher::{FromPatternSource, MatcherFactory};
+use matcher::pattern::file::{Issue, PatternFile};
+use matcher::suffix_array::SuffixArrayMatcherFactory;
+use matcher::trie::factory::TrieMatcherFactory;
+use grammar;
+use super::{export, export_pattern, import};
+use super::convert::convert_pattern;
+
+use serde_json;
+
+const PATTERNDB: &'static str = r#"<?xml version='1.0' encoding='UTF-8'?>
+<patterndb version='4' pub_date='2016-05-20'>
+  <ruleset name='ssh' id='4a5d5c1c-5f3c-4a6e-a1b2-7b1a2a6f0c11'>
+    <pattern>sshd</pattern>
+    <rules>
+      <rule provider='test' id='6d2cba0c-e241-464a-89c3-8035cac8f73e' class='system'>
+        <patterns>
+          <pattern>Accepted @ESTRING:usracct.authmethod: @for @ESTRING:usracct.username: @from @IPv4:usracct.device@ port @NUMBER:usracct.port@ ssh2</pattern>
+        </patterns>
+        <examples>
+          <example>
+            <test_message program='sshd'>Accepted password for bazsi from 127.0.0.1 port 48650 ssh2</test_message>
+            <test_values>
+              <test_value name='usracct.username'>bazsi</test_value>
+              <test_value name='usracct.port'>48650</test_value>
+            </test_values>
+          </example>
+        </examples>
+        <tags>
+          <tag>usracct</tag>
+        </tags>
+        <values>
+          <value name='usracct.type'>login</value>
+          <value name='usracct.application'>$PROGRAM</value>
+        </values>
+      </rule>
+      <rule provider='test' id='not-a-uuid' class='system' context-scope='process'>
+        <patterns>
+          <pattern>Disconnected from @IPv4:ip@@@@STRING:host:.-@</pattern>
+          <pattern>Connection closed by @IPv4:ip@</pattern>
+        </patterns>
+        <examples>
+          <example>
+            <test_message program='sshd'>Connection closed by 1.2.3.4</test_message>
+          </example>
+          <example>
+            <test_message program='sshd'>Disconnected from 1.2.3.4@my-host.com</test_message>
+            <test_values>
+              <test_value name='host'>my-host.com</test_value>
+            </test_values>
+          </example>
+        </examples>
+        <actions>
+          <action><message><values><value name='MESSAGE'>closed</value></values></message></action>
+        </actions>
+      </rule>
+      <rule provider='test' id='9a49c47d-29e9-4072-be84-3b76c6814743' class='mail'>
+        <patterns>
+          <pattern>mail to @EMAIL:rcpt@</pattern>
+        </patterns>
+      </rule>
+    </rules>
+  </ruleset>
+</patterndb>
+"#;
+
+fn assert_test_messages_match<F: MatcherFactory>(json: &str) {
+    let file = serde_json::from_str::<PatternFile>(json).unwrap();
+    assert!(F::Matcher::from_source::<F>(&mut file.into_iter()).is_ok());
+}
+
+fn convert(pattern: &str) -> Result<String, String> {
+    convert_pattern(pattern, &mut Vec::new())
+}
+
+#[test]
+fn test_given_patterndb_parsers_when_they_are_converted_then_we_get_the_equivalent_parsers() {
+    assert_eq!(convert("port @NUMBER:port@ @ESTRING:user: @x @@ 100%{").unwrap(),
+               r"port %{INT:port} %{GREEDY:user} x @ 100\%\{");
+    assert_eq!(convert("@STRING::-@ @SET:s: \t@ @QSTRING:q:<>@ @QSTRING::'@").unwrap(),
+               "%{SET(\"[:alnum:]\\-\")} %{SET(\" \t\"):s} %{QSTRING(\"<>\"):q} \
+                %{QSTRING(\"''\")}");
+    assert_eq!(convert(r#"@PCRE:p:a"b@ @DOUBLE:d@ @IPvANY@ @ANYSTRING:rest@"#).unwrap(),
+               r#"%{REGEX("a\"b"):p} %{NUMBER:d} %{IPADDR} %{GREEDY:rest}"#);
+}
+
+#[test]
+fn test_given_patterndb_pattern_when_it_has_unsupported_constructs_then_the_conversion_fails() {
+    assert!(convert("mail to @EMAIL:rcpt@").is_err());
+    assert!(convert("port @NUMBER:port").is_err());
+    assert!(convert("port @NUMBER:invalid-name@").is_err());
+    assert!(convert("user @ESTRING:user:@").is_err());
+}
+
+#[test]
+fn test_given_patterndb_number_parser_when_it_is_converted_then_the_narrower_int_is_reported() {
+    let mut ignored = Vec::new();
+    assert_eq!(convert_pattern("port @NUMBER:port@", &mut ignored).unwrap(),
+               "port %{INT:port}");
+    assert_eq!(ignored,
+               vec!["sign and hexadecimal form of NUMBER, it's imported as INT".to_owned()]);
+}
+
+#[test]
+fn test_given_patterndb_file_when_it_is_imported_then_the_rules_become_patterns() {
+    let (file, _) = import(PATTERNDB.as_bytes()).unwrap();
+    let patterns = file.patterns();
+    assert_eq!(patterns.len(), 3);
+
+    assert_eq!(patterns[0].uuid().hyphenated().to_string(),
+               "6d2cba0c-e241-464a-89c3-8035cac8f73e");
+    assert_eq!(patterns[0].name(), Some("system"));
+    assert_eq!(grammar::format_pattern(patterns[0].pattern()),
+               "Accepted %{GREEDY:usracct.authmethod} for %{GREEDY:usracct.username} from \
+                %{IPV4:usracct.device} port %{INT:usracct.port} ssh2");
+    assert_eq!(patterns[0].tags(), Some(&["usracct".to_owned()][..]));
+    assert_eq!(patterns[0].values().unwrap().get("usracct.type"),
+               Some(&"login".to_owned()));
+    assert_eq!(patterns[0].test_message_count(), 1);
+
+    assert_eq!(grammar::format_pattern(patterns[1].pattern()),
+               r#"Disconnected from %{IPV4:ip}@%{SET("[:alnum:].\-"):host}"#);
+    assert_eq!(patterns[1].values(), None);
+    assert_eq!(patterns[1].test_message_count(), 1);
+    assert_eq!(patterns[2].test_message_count(), 1);
+}
+
+#[test]
+fn test_given_patterndb_file_when_it_is_imported_then_the_constructs_without_equivalent_are_reported
+    () {
+    let (_, issues) = import(PATTERNDB.as_bytes()).unwrap();
+    let sources = issues.iter()
+                        .map(|issue| {
+                            match *issue {
+                                Issue::Skipped{ref source, ..} |
+                                Issue::Ignored{ref source, ..} => &source[..],
+                            }
+                        })
+                        .collect::<Vec<_>>();
+    assert_eq!(sources,
+               vec!["rule=6d2cba0c-e241-464a-89c3-8035cac8f73e",
+                    "rule=6d2cba0c-e241-464a-89c3-8035cac8f73e pattern='Accepted \
+                     @ESTRING:usracct.authmethod: @for @ESTRING:usracct.username: @from \
+                     @IPv4:usracct.device@ port @NUMBER:usracct.port@ ssh2'",
+                    "rule=not-a-uuid",
+                    "rule=not-a-uuid",
+                    "rule=not-a-uuid",
+                    "rule=not-a-uuid",
+                    "rule=9a49c47d-29e9-4072-be84-3b76c6814743 pattern='mail to @EMAIL:rcpt@'",
+                    "ruleset=ssh"]);
+    assert_eq!(issues[6],
+               Issue::Skipped {
+                   source: "rule=9a49c47d-29e9-4072-be84-3b76c6814743 pattern='mail to \
+                            @EMAIL:rcpt@'"
+                               .to_owned(),
+                   reason: "the EMAIL parser has no equivalent".to_owned(),
+               });
+}
+
+#[test]
+fn test_given_patterndb_file_when_it_is_imported_again_then_the_derived_uuids_are_the_same() {
+    let (first, _) = import(PATTERNDB.as_bytes()).unwrap();
+    let (second, _) = import(PATTERNDB.as_bytes()).unwrap();
+    let uuids = |file: &PatternFile| {
+        file.patterns().iter().map(|pattern| pattern.uuid().clone()).collect::<Vec<_>>()
+    };
+    assert_eq!(uuids(&first), uuids(&second));
+    assert!(first.patterns()[1].uuid() != first.patterns()[2].uuid());
+}
+
+#[test]
+fn test_given_imported_patterndb_file_when_it_is_loaded_then_the_examples_are_valid_test_messages
+    () {
+    let (file, _) = import(PATTERNDB.as_bytes()).unwrap();
+    let json = file.to_json().unwrap();
+    assert_test_messages_match::<TrieMatcherFactory>(&json);
+    assert_test_messages_match::<SuffixArrayMatcherFactory>(&json);
+}
+
+const PATTERNS: &'static str = r#"{
+  "patterns": [
+    {
+      "uuid": "3c5e0c9d-1f6e-4d39-9a3f-0c6a5c0f8e21",
+      "name": "ssh",
+      "pattern": "Accepted %{(publickey|password)} for %{GREEDY:user} from %{IPADDR:ip}@%{SET(\"0-3a_\"):host}",
+      "values": {"program": "$sshd"},
+      "tags": ["login"],
+      "test_messages": [
+        {
+          "message": "Accepted password for <joe> from ::1@a12",
+          "values": {"user": "<joe>", "host": "a12"}
+        }
+      ]
+    },
+    {
+      "uuid": "fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2",
+      "pattern": "%{GREEDY:a}%{INT:b}"
+    }
+  ]
+}"#;
+
+fn export_patterns(pattern: &str) -> Result<Vec<String>, String> {
+    let pattern = grammar::parser::pattern(pattern).unwrap();
+    export_pattern(&pattern, &mut Vec::new())
+}
+
+#[test]
+fn test_given_pattern_when_it_is_exported_to_patterndb_then_we_get_the_equivalent_parsers() {
+    assert_eq!(export_patterns("Accepted %{GREEDY:method} for %{INT:port}@x %{GREEDY:rest}")
+                   .unwrap(),
+               vec!["Accepted @ESTRING:method: for @@NUMBER:port@@@x @ANYSTRING:rest@"]);
+    assert_eq!(export_patterns(concat!(r#"%{SET("[:alnum:]0-3\t"):s} %{SET("x-"):t} "#,
+                                       r#"%{QSTRING("<>")} %{QSTRING("''"):q} "#,
+                                       r#"%{NUMBER:n} %{IPV6}"#))
+                   .unwrap(),
+               vec!["@STRING:s:0123\t@ @SET:t:x-@ @QSTRING::<>@ @QSTRING:q:'@ @FLOAT:n@ @IPv6@"]);
+    assert_eq!(export_patterns("%{(a|b)} %{GREEDY:x}%{( y)?}").unwrap(),
+               vec!["a @ESTRING:x: y@", "a @ANYSTRING:x@", "b @ESTRING:x: y@", "b @ANYSTRING:x@"]);
+}
+
+#[test]
+fn test_given_pattern_when_it_has_no_patterndb_equivalent_then_the_export_fails() {
+    assert!(export_patterns("%{GREEDY:a}%{INT:b}").is_err());
+    assert!(export_patterns("%{GREEDY:a}@b").is_err());
+    assert!(export_patterns(r#"%{SET("^a"):s}"#).is_err());
+    assert!(export_patterns("%{KV}").is_err());
+}
+
+#[test]
+fn test_given_patterns_when_they_are_exported_again_then_the_ruleset_is_the_same() {
+    let file = serde_json::from_str::<PatternFile>(PATTERNS).unwrap();
+    let (first, _) = export(file.patterns(), "ssh");
+    let (second, _) = export(file.patterns(), "ssh");
+    assert_eq!(first, second);
+    let (other, _) = export(file.patterns(), "sshd");
+    assert!(first.lines().nth(2) != other.lines().nth(2));
+}
+
+#[test]
+fn test_given_patterns_when_they_are_exported_then_the_rules_can_be_imported_back() {
+    let file = serde_json::from_str::<PatternFile>(PATTERNS).unwrap();
+    let (xml, issues) = export(file.patterns(), "ssh");
+    assert!(xml.contains("<test_message>Accepted password for &lt;joe&gt; from \
+                          ::1@a12</test_message>"));
+    assert!(xml.contains("<value name='program'>$$sshd</value>"));
+    assert_eq!(issues,
+               vec![Issue::Skipped {
+                        source: "pattern=fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2".to_owned(),
+                        reason: "GREEDY is followed by a parser".to_owned(),
+                    }]);
+
+    let (imported, _) = import(xml.as_bytes()).unwrap();
+    let patterns = imported.patterns();
+    assert_eq!(patterns.len(), 2);
+    assert_eq!(patterns[0].uuid(), file.patterns()[0].uuid());
+    assert_eq!(patterns[0].name(), Some("ssh"));
+    assert_eq!(patterns[0].tags(), Some(&["login".to_owned()][..]));
+    assert_eq!(grammar::format_pattern(patterns[1].pattern()),
+               r#"Accepted password for %{GREEDY:user} from %{IPADDR:ip}@%{SET("0123a_"):host}"#);
+    assert_eq!(patterns[1].test_message_count(), 1);
+
+    let json = imported.to_json().unwrap();
+    assert_test_messages_match::<TrieMatcherFactory>(&json);
+    assert_test_messages_match::<SuffixArrayMatcherFactory>(&json);
+}
@@ -0,0 +1,94 @@
+use serde_json::{self, Value};
+use serde_yaml;
+
+use std::collections::BTreeMap;
+
+use super::PatternFile;
+use grammar;
+use matcher::pattern::Pattern;
+use matcher::pattern::testmessage::TestMessage;
+
+// The documents are built as JSON values, so the same structure is written
+// in both formats. The optional fields are left out if they are not set.
+impl PatternFile {
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.to_value())
+    }
+
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&self.to_value())
+    }
+
+    fn to_value(&self) -> Value {
+        let mut file = BTreeMap::new();
+        if !self.includes.is_empty() {
+            let includes = self.includes.iter().map(|include| string(include)).collect();
+            file.insert("include".to_owned(), Value::Array(includes));
+        }
+        let patterns = self.patterns.iter().map(pattern_to_value).collect();
+        file.insert("patterns".to_owned(), Value::Array(patterns));
+        Value::Object(file)
+    }
+}
+
+fn pattern_to_value(pattern: &Pattern) -> Value {
+    let mut object = BTreeMap::new();
+    if let Some(name) = pattern.name() {
+        object.insert("name".to_owned(), string(name));
+    }
+    object.insert("uuid".to_owned(),
+                  string(&pattern.uuid().hyphenated().to_string()));
+    object.insert("pattern".to_owned(),
+                  string(&grammar::format_pattern(pattern.pattern())));
+    if let Some(values) = pattern.values() {
+        object.insert("values".to_owned(), string_map(values));
+    }
+    if let Some(tags) = pattern.tags() {
+        object.insert("tags".to_owned(), string_array(tags));
+    }
+    if pattern.priority() != 0 {
+        object.insert("priority".to_owned(), Value::I64(pattern.priority() as i64));
+    }
+    if let Some(test_messages) = pattern.test_messages() {
+        let test_messages = test_messages.iter().map(test_message_to_value).collect();
+        object.insert("test_messages".to_owned(), Value::Array(test_messages));
+    }
+    Value::Object(object)
+}
+
+fn test_message_to_value(test_message: &TestMessage) -> Value {
+    let mut object = BTreeMap::new();
+    object.insert("message".to_owned(), string(test_message.message()));
+    if !test_message.values().is_empty() {
+        let mut values = BTreeMap::new();
+        for (key, value) in test_message.values() {
+            let value = match test_message.value_types().get(key) {
+                Some(value_type) => {
+                    let mut typed_value = BTreeMap::new();
+                    typed_value.insert("value".to_owned(), string(value));
+                    typed_value.insert("type".to_owned(), string(value_type.as_str()));
+                    Value::Object(typed_value)
+                }
+                None => string(value),
+            };
+            values.insert(key.clone(), value);
+        }
+        object.insert("values".to_owned(), Value::Object(values));
+    }
+    if let Some(tags) = test_message.tags() {
+        object.insert("tags".to_owned(), string_array(tags));
+    }
+    Value::Object(object)
+}
+
+fn string(value: &str) -> Value {
+    Value::String(value.to_owned())
+}
+
+fn string_array(values: &[String]) -> Value {
+    Value::Array(values.iter().map(|value| string(value)).collect())
+}
+
+fn string_map(values: &BTreeMap<String, String>) -> Value {
+    Value::Object(values.iter().map(|(key, value)| (key.clone(), string(value))).collect())
+}
@@ -0,0 +1,31 @@
+use std::fmt;
+
+// The file a pattern is loaded from and its index in the file's patterns.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Location {
+    file: String,
+    index: usize,
+}
+
+impl Location {
+    pub fn new<S: Into<String>>(file: S, index: usize) -> Location {
+        Location {
+            file: file.into(),
+            index: index,
+        }
+    }
+
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "file={} index={}", self.file, self.index)
+    }
+}
@@ -1,4 +1,5 @@
 pub use self::source::PatternSource;
+pub use self::location::Location;
 
 #[cfg(test)]
 mod test;
@@ -6,11 +7,12 @@ mod deser;
 pub mod source;
 pub mod file;
 pub mod testmessage;
+pub mod location;
 
 use uuid::Uuid;
 use serde_json;
 
-use matcher::compiled_pattern::{CompiledPattern, TokenType};
+use matcher::compiled_pattern::{self, CompiledPattern, TokenType};
 use self::testmessage::TestMessage;
 
 use std::borrow::Borrow;
@@ -24,6 +26,11 @@ pub struct Pattern {
     values: Option<BTreeMap<String, String>>,
     tags: Option<Vec<String>>,
     test_messages: Option<Vec<TestMessage>>,
+    priority: i32,
+    location: Option<Location>,
+    // the test messages are removed when they are checked, so their number
+    // is stored separately
+    test_message_count: usize,
 }
 
 impl Pattern {
@@ -38,6 +45,7 @@ impl Pattern {
                values: Option<BTreeMap<String, String>>,
                tags: Option<Vec<String>>)
                -> Pattern {
+        let test_message_count = test_messages.as_ref().map_or(0, |messages| messages.len());
         Pattern {
             uuid: uuid,
             name: name,
@@ -45,6 +53,9 @@ impl Pattern {
             values: values,
             tags: tags,
             test_messages: test_messages,
+            priority: 0,
+            location: None,
+            test_message_count: test_message_count,
         }
     }
 
@@ -72,6 +83,39 @@ impl Pattern {
         self.tags.as_ref().map(|tags| tags.borrow())
     }
 
+    // The test messages which weren't checked (popped) yet.
+    pub fn test_messages(&self) -> Option<&[TestMessage]> {
+        self.test_messages.as_ref().map(|messages| messages.borrow())
+    }
+
+    // If more patterns can match a message, the one with the highest
+    // priority wins. The default priority is 0.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    pub fn set_priority(&mut self, priority: i32) {
+        self.priority = priority;
+    }
+
+    // It's only known if the pattern is loaded by PatternLoader.
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+
+    pub fn set_location(&mut self, location: Option<Location>) {
+        self.location = location;
+    }
+
+    // The number of test messages the pattern was created with.
+    pub fn test_message_count(&self) -> usize {
+        self.test_message_count
+    }
+
+    pub fn set_test_message_count(&mut self, count: usize) {
+        self.test_message_count = count;
+    }
+
     pub fn from_json(doc: &str) -> Result<Pattern, serde_json::error::Error> {
         serde_json::from_str::<Pattern>(doc)
     }
@@ -80,6 +124,23 @@ impl Pattern {
         self.pattern = pattern;
     }
 
+    pub fn has_alternatives(&self) -> bool {
+        self.pattern.iter().any(|token| token.is_group())
+    }
+
+    // Returns a copy of this pattern for every variant of its compiled
+    // pattern, so all of them are matched with the same UUID.
+    pub fn expand_alternatives(&self) -> Result<Vec<Pattern>, String> {
+        let variants = try!(compiled_pattern::expand_alternatives(&self.pattern));
+        Ok(variants.into_iter()
+                   .map(|variant| {
+                       let mut pattern = self.clone();
+                       pattern.set_pattern(variant);
+                       pattern
+                   })
+                   .collect())
+    }
+
     pub fn pop_first_token(&mut self) -> Option<TokenType> {
         if self.pattern.is_empty() {
             None
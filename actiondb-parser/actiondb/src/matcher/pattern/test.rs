@@ -103,8 +103,10 @@ fn test_given_json_pattern_when_test_messages_are_specified_then_they_are_parsed
 }
 "#;
 
-    let _ = Pattern::from_json(buffer)
-                .expect("Failed to create a Pattern when test_messages are specified");
+    let mut pattern = Pattern::from_json(buffer)
+                          .expect("Failed to create a Pattern when test_messages are specified");
+    assert!(pattern.pop_test_message().is_some());
+    assert_eq!(pattern.test_message_count(), 1);
 }
 
 #[test]
@@ -163,3 +165,21 @@ fn test_invalid_uuid_wont_make_uuid_library_panic_during_deserialization() {
 
     let _ = Pattern::from_json(buffer).err().expect("We should not panic when 'uuid' field is invalid");
 }
+
+#[test]
+fn test_given_json_pattern_when_it_has_a_priority_then_it_is_deserialized() {
+    let buffer = r#"
+{
+  "uuid": "b3c778e3-f476-4df9-b5a1-aaf27a3b4e21",
+  "pattern": "Jun %{INT:day}",
+  "priority": 10
+}
+"#;
+
+    let pattern = Pattern::from_json(buffer).expect("Failed to deserialize a JSON Pattern");
+    assert_eq!(pattern.priority(), 10);
+    let pattern = Pattern::from_json(r#"{"uuid": "b3c778e3-f476-4df9-b5a1-aaf27a3b4e21",
+                                         "pattern": "Jun %{INT:day}"}"#)
+                      .expect("Failed to deserialize a JSON Pattern");
+    assert_eq!(pattern.priority(), 0);
+}
@@ -1,4 +1,7 @@
+use std::collections::BTreeMap;
+
 use super::TestMessage;
+use parsers::ValueType;
 use serde;
 
 impl serde::Deserialize for TestMessage {
@@ -66,10 +69,136 @@ impl serde::de::Visitor for TestMessageVisitor {
             None => try!(visitor.missing_field("message")),
         };
 
-        let values = values.unwrap_or_default();
+        let expected_values: BTreeMap<String, ExpectedValue> = values.unwrap_or_default();
+        let mut values = BTreeMap::new();
+        let mut value_types = BTreeMap::new();
+
+        for (key, expected) in expected_values {
+            if let Some(value_type) = expected.value_type {
+                value_types.insert(key.clone(), value_type);
+            }
+            values.insert(key, expected.value);
+        }
+
+        try!(visitor.end());
+
+        let mut test_message = TestMessage::new(message_final, values, tags);
+        test_message.set_value_types(value_types);
+        Ok(test_message)
+    }
+}
+
+// An expected value is either a string, a number (which is also checked to
+// be a number) or an object with `value` and `type` keys.
+struct ExpectedValue {
+    value: String,
+    value_type: Option<ValueType>,
+}
+
+impl ExpectedValue {
+    fn new(value: String, value_type: Option<ValueType>) -> ExpectedValue {
+        ExpectedValue {
+            value: value,
+            value_type: value_type,
+        }
+    }
+}
+
+impl serde::Deserialize for ExpectedValue {
+    fn deserialize<D>(deserializer: &mut D) -> Result<ExpectedValue, D::Error>
+        where D: serde::de::Deserializer
+    {
+        deserializer.deserialize(ExpectedValueVisitor)
+    }
+}
+
+enum ExpectedValueField {
+    VALUE,
+    TYPE,
+}
+
+impl serde::Deserialize for ExpectedValueField {
+    fn deserialize<D>(deserializer: &mut D) -> Result<ExpectedValueField, D::Error>
+        where D: serde::de::Deserializer
+    {
+        struct FieldVisitor;
+
+        impl serde::de::Visitor for FieldVisitor {
+            type Value = ExpectedValueField;
+
+            fn visit_str<E>(&mut self, value: &str) -> Result<ExpectedValueField, E>
+                where E: serde::de::Error
+            {
+                match value {
+                    "value" => Ok(ExpectedValueField::VALUE),
+                    "type" => Ok(ExpectedValueField::TYPE),
+                    _ => Err(serde::de::Error::custom(format!("Unexpected field: {}", value))),
+                }
+            }
+        }
+
+        deserializer.deserialize(FieldVisitor)
+    }
+}
+
+struct ExpectedValueVisitor;
+
+impl serde::de::Visitor for ExpectedValueVisitor {
+    type Value = ExpectedValue;
+
+    fn visit_str<E>(&mut self, value: &str) -> Result<ExpectedValue, E>
+        where E: serde::de::Error
+    {
+        Ok(ExpectedValue::new(value.to_owned(), None))
+    }
+
+    fn visit_i64<E>(&mut self, value: i64) -> Result<ExpectedValue, E>
+        where E: serde::de::Error
+    {
+        Ok(ExpectedValue::new(value.to_string(), Some(ValueType::Int)))
+    }
+
+    fn visit_u64<E>(&mut self, value: u64) -> Result<ExpectedValue, E>
+        where E: serde::de::Error
+    {
+        Ok(ExpectedValue::new(value.to_string(), Some(ValueType::Int)))
+    }
+
+    fn visit_f64<E>(&mut self, value: f64) -> Result<ExpectedValue, E>
+        where E: serde::de::Error
+    {
+        Ok(ExpectedValue::new(value.to_string(), Some(ValueType::Float)))
+    }
+
+    fn visit_map<V>(&mut self, mut visitor: V) -> Result<ExpectedValue, V::Error>
+        where V: serde::de::MapVisitor
+    {
+        let mut value = None;
+        let mut value_type = None;
+
+        while let Some(field) = try!(visitor.visit_key()) {
+            match field {
+                ExpectedValueField::VALUE => value = Some(try!(visitor.visit_value())),
+                ExpectedValueField::TYPE => {
+                    let name: String = try!(visitor.visit_value());
+                    match name.parse::<ValueType>() {
+                        Ok(parsed_type) => value_type = Some(parsed_type),
+                        Err(()) => {
+                            return Err(serde::de::Error::custom(format!("Unknown value type: {}",
+                                                                        name)))
+                        }
+                    }
+                }
+            }
+        }
+
+        let value = match value {
+            Some(value) => value,
+            None => try!(visitor.missing_field("value")),
+        };
 
         try!(visitor.end());
 
-        Ok(TestMessage::new(message_final, values, tags))
+        Ok(ExpectedValue::new(value, value_type))
     }
 }
@@ -3,6 +3,7 @@ use uuid::Uuid;
 use std::error;
 use std::fmt;
 
+use parsers::ValueType;
 use super::TestMessage;
 
 #[derive(Debug)]
@@ -13,6 +14,12 @@ pub enum Error {
         expected_value: String,
         got_value: String,
     },
+    ValueTypeNotMatch {
+        pattern_uuid: String,
+        key: String,
+        expected_type: ValueType,
+        got_type: ValueType,
+    },
     KeyNotFound {
         pattern_uuid: String,
         key: String,
@@ -47,6 +54,19 @@ impl Error {
         }
     }
 
+    pub fn value_type_not_match(pattern_uuid: &Uuid,
+                                key: &str,
+                                expected_type: ValueType,
+                                got_type: ValueType)
+                                -> Error {
+        Error::ValueTypeNotMatch {
+            pattern_uuid: pattern_uuid.hyphenated().to_string(),
+            key: key.to_owned(),
+            expected_type: expected_type,
+            got_type: got_type,
+        }
+    }
+
     pub fn key_not_found(pattern_uuid: &Uuid, key: &str) -> Error {
         Error::KeyNotFound {
             pattern_uuid: pattern_uuid.hyphenated().to_string(),
@@ -95,6 +115,17 @@ impl fmt::Display for Error {
                                            expected_value,
                                            got_value))
             }
+            Error::ValueTypeNotMatch{ref pattern_uuid,
+                                     ref key,
+                                     ref expected_type,
+                                     ref got_type} => {
+                fmt.write_fmt(format_args!("The type of a parsed value does not equal to its \
+                                            expected type: uuid={} key={} expected={} got={}",
+                                           pattern_uuid,
+                                           key,
+                                           expected_type,
+                                           got_type))
+            }
             Error::KeyNotFound{ref pattern_uuid, ref key} => {
                 fmt.write_fmt(format_args!("A parsed key in not found among the expected ones: \
                                             uuid={} key={}",
@@ -132,6 +163,9 @@ impl error::Error for Error {
             Error::ValueNotMatch{..} => {
                 "A parsed value does not equal to its expected value"
             }
+            Error::ValueTypeNotMatch{..} => {
+                "The type of a parsed value does not equal to its expected type"
+            }
             Error::KeyNotFound{..} => {
                 "A parsed key in not found among the expected ones"
             }
@@ -2,12 +2,14 @@ use std::collections::BTreeMap;
 use std::borrow::Borrow;
 
 use matcher::result::MatchResult;
+use parsers::ValueType;
 use super::Error;
 
 #[derive(Clone, Debug)]
 pub struct TestMessage {
     message: String,
     values: BTreeMap<String, String>,
+    value_types: BTreeMap<String, ValueType>,
     tags: Option<Vec<String>>,
 }
 
@@ -19,6 +21,7 @@ impl TestMessage {
         TestMessage {
             message: message,
             values: values,
+            value_types: BTreeMap::new(),
             tags: tags,
         }
     }
@@ -31,6 +34,16 @@ impl TestMessage {
         &self.values
     }
 
+    // The expected types of the values. Values without an expected type
+    // are not checked against their type.
+    pub fn value_types(&self) -> &BTreeMap<String, ValueType> {
+        &self.value_types
+    }
+
+    pub fn set_value_types(&mut self, value_types: BTreeMap<String, ValueType>) {
+        self.value_types = value_types;
+    }
+
     pub fn tags(&self) -> Option<&[String]> {
         self.tags.as_ref().map(|x| x.borrow())
     }
@@ -44,28 +57,60 @@ impl TestMessage {
         let merged_values = TestMessage::merge_values(result);
 
         for (key, value) in self.values() {
-            try!(TestMessage::test_value(key, value, &merged_values, result));
+            let value_type = self.value_types.get(key).cloned();
+            try!(TestMessage::test_value(key, value, value_type, &merged_values, result));
         }
         Ok(())
     }
 
     fn test_value(key: &str,
                   value: &str,
+                  value_type: Option<ValueType>,
                   values: &BTreeMap<&str, &str>,
                   result: &MatchResult)
                   -> Result<(), Error> {
         if let Some(got_value) = values.get(key) {
             let got_value: &str = got_value;
-            if value == got_value {
-                Ok(())
-            } else {
-                Err(Error::value_not_match(result.pattern().uuid(), key, value, got_value))
+            if !TestMessage::values_are_equal(value, got_value, value_type) {
+                return Err(Error::value_not_match(result.pattern().uuid(), key, value, got_value));
+            }
+            match value_type {
+                Some(value_type) if !TestMessage::value_types_match(value_type,
+                                                                     result.value_type(key)) => {
+                    Err(Error::value_type_not_match(result.pattern().uuid(),
+                                                    key,
+                                                    value_type,
+                                                    result.value_type(key)))
+                }
+                _ => Ok(()),
             }
         } else {
             Err(Error::key_not_found(result.pattern().uuid(), key))
         }
     }
 
+    // The integer literals are also valid values of the NUMBER parsers.
+    fn value_types_match(expected: ValueType, got: ValueType) -> bool {
+        expected == got || (expected == ValueType::Int && got == ValueType::Float)
+    }
+
+    // Numbers are compared by their values, so `22` equals to `0022` and
+    // `1.5` equals to `1.50`.
+    fn values_are_equal(expected: &str, got: &str, value_type: Option<ValueType>) -> bool {
+        if expected == got {
+            return true;
+        }
+        match value_type {
+            Some(value_type) if value_type.is_numeric() => {
+                match (expected.parse::<f64>(), got.parse::<f64>()) {
+                    (Ok(expected), Ok(got)) => expected == got,
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
     fn merge_values<'a>(result: &'a MatchResult) -> BTreeMap<&'a str, &'a str> {
         let mut merged_values: BTreeMap<&str, &str> = BTreeMap::new();
 
@@ -75,7 +120,7 @@ impl TestMessage {
             }
         }
         for (key, value) in result.values() {
-            merged_values.insert(key, value);
+            merged_values.insert(key.borrow(), value.borrow());
         }
 
         merged_values
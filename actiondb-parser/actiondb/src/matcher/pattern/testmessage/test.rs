@@ -1,5 +1,6 @@
 use super::TestMessage;
 use serde_json;
+use parsers::ValueType;
 use std::borrow::Borrow;
 
 #[test]
@@ -80,3 +81,43 @@ fn test_given_json_test_message_when_it_contains_not_just_the_valid_fields_then_
     let _ = result.err().expect("Failed to return an error when a serialized TestMessage \
                                  contains non-valid fields");
 }
+
+#[test]
+fn test_given_json_test_message_when_it_contains_typed_values_then_their_types_are_deserialized
+    () {
+    let buffer = r#"
+{
+"message": "Accepted publickey for joe from 1.2.3.4 port 22 ssh2",
+"values": {
+"user": "joe",
+"port": 22,
+"latency": 0.5,
+"ipaddr": {"value": "1.2.3.4", "type": "ip"}
+}
+}
+"#;
+    let result = serde_json::from_str::<TestMessage>(buffer);
+    println!("{:?}", result);
+    let msg = result.expect("Failed to deserialize a TestMessage with typed values");
+    assert_eq!(Some("22"), msg.values().get("port").map(|x| x.borrow()));
+    assert_eq!(Some("1.2.3.4"), msg.values().get("ipaddr").map(|x| x.borrow()));
+    assert_eq!(Some(&ValueType::Int), msg.value_types().get("port"));
+    assert_eq!(Some(&ValueType::Float), msg.value_types().get("latency"));
+    assert_eq!(Some(&ValueType::Ip), msg.value_types().get("ipaddr"));
+    assert_eq!(None, msg.value_types().get("user"));
+}
+
+#[test]
+fn test_given_json_test_message_when_a_value_has_an_unknown_type_then_we_return_an_error() {
+    let buffer = r#"
+{
+"message": "port 22",
+"values": {
+"port": {"value": "22", "type": "integer"}
+}
+}
+"#;
+    let result = serde_json::from_str::<TestMessage>(buffer);
+    println!("{:?}", result);
+    let _ = result.err().expect("Failed to return an error when a value has an unknown type");
+}
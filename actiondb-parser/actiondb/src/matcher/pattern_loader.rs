@@ -1,15 +1,26 @@
 use super::pattern::file::PatternFile;
+use super::pattern::Location;
+use super::pattern::source::BuildResult;
 use matcher::BuildError;
 use matcher::MatcherFactory;
+use matcher::MatcherSuite;
 use matcher::FromPatternSource;
+use matcher::cache::{self, Cacheable, CacheError, Sources};
+use uuid::Uuid;
 
-use std::path;
-use std::fs::File;
-use std::io::Read;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{self, Path, PathBuf};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
 
 use serde_json;
 use serde_yaml;
 
+// It makes the temporary cache files of the threads of a process unique.
+static TEMPORARY_FILE_COUNTER: AtomicUsize = ATOMIC_USIZE_INIT;
+
 pub struct PatternLoader;
 
 impl PatternLoader {
@@ -21,20 +32,210 @@ impl PatternLoader {
         Ok(buffer)
     }
 
+    // The path can be a pattern file or a directory of pattern files.
     pub fn from_file_ignore_errors<F>(pattern_file_path: &str) -> Result<F::Matcher, BuildError>
         where F: MatcherFactory
     {
-        let file = try!(PatternLoader::load_file(pattern_file_path));
-        Ok(F::Matcher::from_source_ignore_errors::<F>(&mut file.into_iter()))
+        PatternLoader::from_paths_ignore_errors::<F, _>(&[pattern_file_path])
     }
 
+    // The path can be a pattern file or a directory of pattern files.
     pub fn from_file<F>(pattern_file_path: &str) -> Result<F::Matcher, BuildError>
         where F: MatcherFactory
     {
-        let file = try!(PatternLoader::load_file(pattern_file_path));
-        F::Matcher::from_source::<F>(&mut file.into_iter())
+        PatternLoader::from_paths::<F, _>(&[pattern_file_path])
+    }
+
+    pub fn from_paths_ignore_errors<F, P>(paths: &[P]) -> Result<F::Matcher, BuildError>
+        where F: MatcherFactory,
+              P: AsRef<Path>
+    {
+        let patterns = try!(PatternLoader::load_paths(paths));
+        Ok(F::Matcher::from_source_ignore_errors::<F>(&mut patterns.into_iter()))
+    }
+
+    pub fn from_paths<F, P>(paths: &[P]) -> Result<F::Matcher, BuildError>
+        where F: MatcherFactory,
+              P: AsRef<Path>
+    {
+        let patterns = try!(PatternLoader::load_paths(paths));
+        F::Matcher::from_source::<F>(&mut patterns.into_iter())
+    }
+
+    // Loads the patterns from pattern files and directories. The
+    // directories are loaded recursively and the included files are loaded
+    // too. If the file can't be loaded, an error is returned, the errors of
+    // the patterns (e.g. duplicate UUIDs) are returned among the patterns.
+    pub fn load_paths<P: AsRef<Path>>(paths: &[P]) -> Result<Vec<BuildResult>, BuildError> {
+        PatternLoader::load_sources(paths).map(|(results, _)| results)
+    }
+
+    // Like load_paths(), but the files and directories which were loaded are
+    // returned too.
+    pub fn load_sources<P: AsRef<Path>>(paths: &[P])
+                                        -> Result<(Vec<BuildResult>, Sources), BuildError> {
+        let mut roots = Vec::new();
+        for path in paths {
+            roots.push(try!(canonicalize(path.as_ref())));
+        }
+
+        let mut loader = Loader::default();
+        loader.sources = Sources::new(roots);
+        for path in paths {
+            try!(loader.load_path(path.as_ref()));
+        }
+        try!(loader.sources.update_hash());
+        Ok((loader.results, loader.sources))
+    }
+
+    // Loads the patterns like from_paths() and writes the matcher to a cache
+    // file.
+    pub fn compile<MS, P>(paths: &[P], cache_path: &str) -> Result<MS::Matcher, BuildError>
+        where MS: MatcherSuite,
+              MS::Matcher: Cacheable,
+              P: AsRef<Path>
+    {
+        let (matcher, sources) =
+            try!(PatternLoader::from_sources::<MS::MatcherFactory, _>(paths, false));
+        try!(PatternLoader::write_cache(&matcher, &sources, cache_path));
+        Ok(matcher)
+    }
+
+    // Loads a matcher from a cache file. If the pattern files were changed
+    // since the cache was written, CacheError::Stale is returned.
+    pub fn from_cache<MS>(cache_path: &str) -> Result<MS::Matcher, BuildError>
+        where MS: MatcherSuite,
+              MS::Matcher: Cacheable
+    {
+        PatternLoader::read_cache::<MS>(cache_path).map(|(_, matcher)| matcher)
+    }
+
+    // Loads the matcher from the cache file if it was compiled from the
+    // pattern file and it's up to date, otherwise the pattern file is loaded
+    // and the cache is written again.
+    pub fn from_file_cached<MS>(pattern_file_path: &str,
+                                cache_path: &str)
+                                -> Result<MS::Matcher, BuildError>
+        where MS: MatcherSuite,
+              MS::Matcher: Cacheable
+    {
+        PatternLoader::from_file_cached_with_sources::<MS>(pattern_file_path, cache_path, false)
+            .map(|(matcher, _)| matcher)
+    }
+
+    // Like from_file() (or from_file_ignore_errors() if `ignore_errors` is
+    // set), but the files and directories which were loaded are returned
+    // too, so they can be watched.
+    pub fn from_file_with_sources<F>(pattern_file_path: &str,
+                                     ignore_errors: bool)
+                                     -> Result<(F::Matcher, Sources), BuildError>
+        where F: MatcherFactory
+    {
+        PatternLoader::from_sources::<F, _>(&[pattern_file_path], ignore_errors)
+    }
+
+    // Like from_file_cached(), but the files and directories which were
+    // loaded are returned too. If `ignore_errors` is set, the invalid
+    // patterns are skipped when the pattern file is loaded.
+    pub fn from_file_cached_with_sources<MS>(pattern_file_path: &str,
+                                             cache_path: &str,
+                                             ignore_errors: bool)
+                                             -> Result<(MS::Matcher, Sources), BuildError>
+        where MS: MatcherSuite,
+              MS::Matcher: Cacheable
+    {
+        let root = try!(canonicalize(Path::new(pattern_file_path)));
+        let cached = PatternLoader::read_cache::<MS>(cache_path).and_then(|(sources, matcher)| {
+            if sources.roots() != &[root][..] {
+                Err(BuildError::Cache(CacheError::OtherSources))
+            } else if sources.errors_ignored() && !ignore_errors {
+                Err(BuildError::Cache(CacheError::ErrorsIgnored))
+            } else {
+                Ok((matcher, sources))
+            }
+        });
+
+        match cached {
+            Ok(cached) => {
+                debug!("Loaded the patterns from the cache: cache_file={}", cache_path);
+                Ok(cached)
+            }
+            Err(error) => {
+                info!("Failed to load the patterns from the cache, loading the pattern file: \
+                       pattern_file={} cache_file={} error={}",
+                      pattern_file_path,
+                      cache_path,
+                      error);
+                let paths = [pattern_file_path];
+                let (matcher, sources) =
+                    try!(PatternLoader::from_sources::<MS::MatcherFactory, _>(&paths,
+                                                                              ignore_errors));
+                if let Err(error) = PatternLoader::write_cache(&matcher, &sources, cache_path) {
+                    warn!("Failed to write the cache file: cache_file={} error={}",
+                          cache_path,
+                          error);
+                }
+                Ok((matcher, sources))
+            }
+        }
+    }
+
+    fn from_sources<F, P>(paths: &[P],
+                          ignore_errors: bool)
+                          -> Result<(F::Matcher, Sources), BuildError>
+        where F: MatcherFactory,
+              P: AsRef<Path>
+    {
+        let (patterns, mut sources) = try!(PatternLoader::load_sources(paths));
+        sources.set_errors_ignored(ignore_errors);
+        let mut patterns = patterns.into_iter();
+        let matcher = if ignore_errors {
+            F::Matcher::from_source_ignore_errors::<F>(&mut patterns)
+        } else {
+            try!(F::Matcher::from_source::<F>(&mut patterns))
+        };
+        Ok((matcher, sources))
+    }
+
+    fn read_cache<MS>(cache_path: &str) -> Result<(Sources, MS::Matcher), BuildError>
+        where MS: MatcherSuite,
+              MS::Matcher: Cacheable
+    {
+        let mut bytes = Vec::new();
+        try!(try!(File::open(cache_path)).read_to_end(&mut bytes));
+
+        let (sources, decoder) = try!(cache::read_sources::<MS::Matcher>(&bytes));
+        if !sources.is_up_to_date() {
+            return Err(BuildError::Cache(CacheError::Stale));
+        }
+        let matcher = try!(cache::read_matcher::<MS::Matcher, MS::ParserFactory>(decoder));
+        Ok((sources, matcher))
+    }
+
+    // The cache is written to a temporary file first, so a running reader
+    // never sees a partially written cache. The name of the temporary file
+    // is unique, so concurrent writers don't overwrite each other's file.
+    fn write_cache<M: Cacheable>(matcher: &M,
+                                 sources: &Sources,
+                                 cache_path: &str)
+                                 -> Result<(), BuildError> {
+        let temporary_path = format!("{}.{}.{}.tmp",
+                                     cache_path,
+                                     process::id(),
+                                     TEMPORARY_FILE_COUNTER.fetch_add(1, Ordering::SeqCst));
+        let result = OpenOptions::new()
+                         .write(true)
+                         .create_new(true)
+                         .open(&temporary_path)
+                         .and_then(|mut file| file.write_all(&cache::write(matcher, sources)))
+                         .and_then(|_| fs::rename(&temporary_path, cache_path));
+        if result.is_err() {
+            let _ = fs::remove_file(&temporary_path);
+        }
+        Ok(try!(result))
     }
 
+    // Loads a single pattern file, its includes are not loaded.
     pub fn load_file(pattern_file_path: &str) -> Result<PatternFile, BuildError>
     {
         let path = path::Path::new(pattern_file_path);
@@ -58,3 +259,105 @@ impl PatternLoader {
         }
     }
 }
+
+fn canonicalize(path: &Path) -> Result<PathBuf, BuildError> {
+    fs::canonicalize(path)
+        .map_err(|error| BuildError::InFile(path.display().to_string(), Box::new(error.into())))
+}
+
+fn is_pattern_file(path: &Path) -> bool {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") | Some("yaml") | Some("yml") | Some("YAML") | Some("YML") => true,
+        _ => false,
+    }
+}
+
+#[derive(Default)]
+struct Loader {
+    // every file is loaded only once, even if it's included more times
+    loaded_files: BTreeSet<PathBuf>,
+    // the directories are tracked too, so symlink loops are not followed
+    loaded_directories: BTreeSet<PathBuf>,
+    uuids: BTreeMap<Uuid, Location>,
+    results: Vec<BuildResult>,
+    sources: Sources,
+}
+
+impl Loader {
+    fn load_path(&mut self, path: &Path) -> Result<(), BuildError> {
+        let in_file = |error| BuildError::InFile(path.display().to_string(), Box::new(error));
+
+        if try!(fs::metadata(path).map_err(|error| in_file(error.into()))).is_dir() {
+            self.load_directory(path)
+        } else {
+            self.load_file(path)
+        }
+    }
+
+    // The entries are sorted, so the order of the patterns doesn't depend
+    // on the file system.
+    fn load_directory(&mut self, path: &Path) -> Result<(), BuildError> {
+        let in_file = |error| BuildError::InFile(path.display().to_string(), Box::new(error));
+        let mut entries = Vec::new();
+
+        let canonical_path = try!(canonicalize(path));
+        if !self.loaded_directories.insert(canonical_path.clone()) {
+            debug!("Pattern directory is already loaded: directory={}", path.display());
+            return Ok(());
+        }
+        self.sources.push_directory(canonical_path);
+
+        for entry in try!(fs::read_dir(path).map_err(|error| in_file(error.into()))) {
+            let entry = try!(entry.map_err(|error| in_file(error.into())));
+            entries.push(entry.path());
+        }
+        entries.sort();
+
+        for entry in entries {
+            if entry.is_dir() || is_pattern_file(&entry) {
+                try!(self.load_path(&entry));
+            }
+        }
+        Ok(())
+    }
+
+    fn load_file(&mut self, path: &Path) -> Result<(), BuildError> {
+        let file_name = path.display().to_string();
+        let in_file = |error| BuildError::InFile(file_name.clone(), Box::new(error));
+
+        let canonical_path = try!(canonicalize(path));
+        if !self.loaded_files.insert(canonical_path.clone()) {
+            debug!("Pattern file is already loaded: file={}", file_name);
+            return Ok(());
+        }
+        self.sources.push_file(canonical_path);
+
+        let path_str = try!(path.to_str().ok_or(in_file(BuildError::NotUtf8FileName)));
+        let file = try!(PatternLoader::load_file(path_str).map_err(&in_file));
+        let PatternFile { patterns, includes } = file;
+
+        for (index, mut pattern) in patterns.into_iter().enumerate() {
+            let location = Location::new(file_name.clone(), index);
+            pattern.set_location(Some(location.clone()));
+
+            let first = self.uuids.get(pattern.uuid()).cloned();
+            let result = match first {
+                Some(first) => {
+                    let error = BuildError::DuplicateUuid(pattern.uuid().clone(), first);
+                    Err(BuildError::InPattern(location, Box::new(error)))
+                }
+                None => {
+                    self.uuids.insert(pattern.uuid().clone(), location);
+                    Ok(pattern)
+                }
+            };
+            self.results.push(result);
+        }
+
+        let directory = path.parent().unwrap_or(Path::new(""));
+        for include in includes {
+            try!(self.load_path(&directory.join(include)));
+        }
+        Ok(())
+    }
+}
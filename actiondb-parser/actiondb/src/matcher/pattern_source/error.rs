@@ -1,4 +1,7 @@
 use matcher::pattern::testmessage;
+use matcher::pattern::Location;
+use matcher::cache::CacheError;
+use uuid::Uuid;
 
 use serde_json;
 use serde_yaml;
@@ -14,6 +17,13 @@ pub enum BuildError {
     DeserYaml(serde_yaml::Error),
     UnsupportedFileExtension,
     NotUtf8FileName,
+    // the UUID is already used by the pattern at the location
+    DuplicateUuid(Uuid, Location),
+    // an error of the file with the given path
+    InFile(String, Box<BuildError>),
+    // an error of the pattern at the location
+    InPattern(Location, Box<BuildError>),
+    Cache(CacheError),
 }
 
 impl From<testmessage::Error> for BuildError {
@@ -28,6 +38,12 @@ impl From<io::Error> for BuildError {
     }
 }
 
+impl From<CacheError> for BuildError {
+    fn from(error: CacheError) -> BuildError {
+        BuildError::Cache(error)
+    }
+}
+
 impl From<serde_json::Error> for BuildError {
     fn from(error: serde_json::Error) -> BuildError {
         BuildError::DeserJson(error)
@@ -51,6 +67,17 @@ impl fmt::Display for BuildError {
                 formatter.write_fmt(format_args!("The given file extension is not suppoted")),
             BuildError::NotUtf8FileName =>
                 formatter.write_str("The given filename contains non Utf-8 characters"),
+            BuildError::DuplicateUuid(ref uuid, ref location) => {
+                write!(formatter,
+                       "The UUID is already used by an other pattern: uuid={} {}",
+                       uuid.hyphenated(),
+                       location)
+            }
+            BuildError::InFile(ref file, ref error) => write!(formatter, "{}: {}", file, error),
+            BuildError::InPattern(ref location, ref error) => {
+                write!(formatter, "{} index={}: {}", location.file(), location.index(), error)
+            }
+            BuildError::Cache(ref error) => error.fmt(formatter),
         }
     }
 }
@@ -64,6 +91,10 @@ impl error::Error for BuildError {
             BuildError::DeserYaml(ref error) => error.description(),
             BuildError::UnsupportedFileExtension => "The given file extension is not supported",
             BuildError::NotUtf8FileName => "The given filename contains non Utf-8 characters",
+            BuildError::DuplicateUuid(..) => "The UUID is already used by an other pattern",
+            BuildError::InFile(_, ref error) |
+            BuildError::InPattern(_, ref error) => error.description(),
+            BuildError::Cache(ref error) => error.description(),
         }
     }
 
@@ -73,7 +104,12 @@ impl error::Error for BuildError {
             BuildError::Io(ref error) => error.cause(),
             BuildError::DeserJson(ref error) => error.cause(),
             BuildError::DeserYaml(ref error) => error.cause(),
-            BuildError::UnsupportedFileExtension | BuildError::NotUtf8FileName => None,
+            BuildError::InFile(_, ref error) |
+            BuildError::InPattern(_, ref error) => error.cause(),
+            BuildError::UnsupportedFileExtension |
+            BuildError::NotUtf8FileName |
+            BuildError::DuplicateUuid(..) |
+            BuildError::Cache(_) => None,
         }
     }
 }
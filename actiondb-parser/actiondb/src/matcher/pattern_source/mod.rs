@@ -4,7 +4,7 @@ use matcher::pattern::{Pattern, PatternSource};
 use matcher::pattern::testmessage::{self, TestMessage};
 use matcher::{Matcher, MatcherFactory};
 use matcher::result::MatchResult;
-use matcher::pattern::source::BuildResult;
+use matcher::pattern::Location;
 pub use self::error::BuildError;
 
 mod error;
@@ -12,30 +12,94 @@ mod error;
 pub trait FromPatternSource {
     fn from_source<F: MatcherFactory>(from: &mut PatternSource) -> Result<F::Matcher, BuildError> {
         let mut matcher = F::new_matcher();
+        let mut added = Vec::new();
         for pattern in from {
-            try!(Self::check_pattern::<F::Matcher>(&mut matcher, pattern));
+            added.push(Self::add_pattern::<F::Matcher>(&mut matcher, try!(pattern)));
         }
+        matcher.sort_parsers();
+        for added_pattern in &added {
+            try!(Self::check_pattern::<F::Matcher>(&matcher, added_pattern));
+        }
+        Self::report_ambiguous_test_messages(&matcher, &added);
         Ok(matcher)
     }
 
+    // The patterns which fail their test messages are left out of the
+    // returned matcher: it's built again from the valid patterns only.
     fn from_source_ignore_errors<F: MatcherFactory>(from: &mut PatternSource) -> F::Matcher {
         let mut matcher = F::new_matcher();
+        let mut added = Vec::new();
         for pattern in from {
-            let result = Self::check_pattern::<F::Matcher>(&mut matcher, pattern);
-            if let Err(error) = result {
-                error!("{}", error);
+            match pattern {
+                Ok(pattern) => {
+                    let (pattern, added_pattern) = Self::split_test_messages(pattern);
+                    matcher.add_pattern(pattern.clone());
+                    added.push((pattern, added_pattern));
+                }
+                Err(error) => error!("{}", error),
+            }
+        }
+        matcher.sort_parsers();
+        let mut valid_matcher = F::new_matcher();
+        let mut checked = Vec::new();
+        for (pattern, added_pattern) in added {
+            match Self::check_pattern::<F::Matcher>(&matcher, &added_pattern) {
+                Ok(()) => {
+                    valid_matcher.add_pattern(pattern);
+                    checked.push(added_pattern);
+                }
+                Err(error) => error!("{}", error),
             }
         }
-        matcher
+        valid_matcher.sort_parsers();
+        Self::report_ambiguous_test_messages(&valid_matcher, &checked);
+        valid_matcher
     }
 
-    fn check_pattern<M: Matcher>(matcher: &mut M, result: BuildResult) -> Result<(), BuildError> {
-        let mut pattern = try!(result);
-        let uuid = pattern.uuid().to_owned();
-        let test_messages = Self::extract_test_messages(&mut pattern);
+    // The test messages are taken out of the pattern, so they aren't kept in
+    // the matcher. They are checked when every pattern is added.
+    fn add_pattern<M: Matcher>(matcher: &mut M, pattern: Pattern) -> AddedPattern {
+        let (pattern, added) = Self::split_test_messages(pattern);
         matcher.add_pattern(pattern);
-        debug!("validating pattern: {}", uuid.hyphenated().to_string());
-        Self::check_test_messages(matcher, &test_messages, &uuid)
+        added
+    }
+
+    fn split_test_messages(mut pattern: Pattern) -> (Pattern, AddedPattern) {
+        let added = AddedPattern {
+            uuid: pattern.uuid().to_owned(),
+            location: pattern.location().cloned(),
+            test_messages: Self::extract_test_messages(&mut pattern),
+        };
+        (pattern, added)
+    }
+
+    fn check_pattern<M: Matcher>(matcher: &M, added: &AddedPattern) -> Result<(), BuildError> {
+        debug!("validating pattern: {}", added.uuid.hyphenated().to_string());
+        if let Err(error) = Self::check_test_messages(matcher, &added.test_messages, &added.uuid) {
+            return Err(match added.location {
+                Some(ref location) => BuildError::InPattern(location.clone(), Box::new(error)),
+                None => error,
+            });
+        }
+        Ok(())
+    }
+
+    // A checked test message is parsed to its own pattern, but the other
+    // patterns which match it too are reported.
+    fn report_ambiguous_test_messages<M: Matcher>(matcher: &M, added: &[AddedPattern]) {
+        for added_pattern in added {
+            for message in &added_pattern.test_messages {
+                for result in matcher.parse_all(message.message()) {
+                    if result.pattern().uuid() != &added_pattern.uuid {
+                        warn!("Test message matches more than one pattern: uuid={} \
+                               other_uuid={} message='{}'",
+                              added_pattern.uuid.hyphenated(),
+                              result.pattern().uuid().hyphenated(),
+                              message.message());
+                    }
+                }
+            }
+        }
     }
 
     fn extract_test_messages(pattern: &mut Pattern) -> Vec<TestMessage> {
@@ -76,3 +140,11 @@ pub trait FromPatternSource {
 
 impl<T> FromPatternSource for T where T: Matcher {
 }
+
+// A pattern which is added to the matcher, with the test messages which
+// are checked against it.
+pub struct AddedPattern {
+    uuid: Uuid,
+    location: Option<Location>,
+    test_messages: Vec<TestMessage>,
+}
@@ -1,12 +1,14 @@
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 
-use parsers::ParseResult;
+use parsers::{ParseResult, ValueType};
 use matcher::Pattern;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MatchResult<'a, 'b> {
     pattern: &'a Pattern,
-    values: BTreeMap<&'a str, &'b str>,
+    values: BTreeMap<Cow<'a, str>, Cow<'b, str>>,
+    value_types: BTreeMap<Cow<'a, str>, ValueType>,
 }
 
 impl <'a, 'b> MatchResult<'a, 'b> {
@@ -14,12 +16,24 @@ impl <'a, 'b> MatchResult<'a, 'b> {
         MatchResult {
             pattern: pattern,
             values: BTreeMap::new(),
+            value_types: BTreeMap::new(),
         }
     }
 
     pub fn insert(&mut self, result: ParseResult<'a, 'b>) {
-        if let Some(name) = result.parser().name() {
-            self.values.insert(name, result.value());
+        let name = result.parser().name();
+        let value_type = result.parser().value_type();
+        let (value, additional_values) = result.into_values();
+
+        if let Some(name) = name {
+            self.values.insert(Cow::Borrowed(name), value);
+            if value_type != ValueType::String {
+                self.value_types.insert(Cow::Borrowed(name), value_type);
+            }
+        }
+        for (key, value) in additional_values {
+            self.value_types.remove(&key);
+            self.values.insert(key, value);
         }
     }
 
@@ -27,14 +41,36 @@ impl <'a, 'b> MatchResult<'a, 'b> {
         self.pattern
     }
 
-    pub fn values(&self) -> &BTreeMap<&'a str, &'b str> {
+    pub fn values(&self) -> &BTreeMap<Cow<'a, str>, Cow<'b, str>> {
         &self.values
     }
+
+    // Values without a type (e.g. the additional values of a parser) are
+    // strings.
+    pub fn value_type(&self, key: &str) -> ValueType {
+        self.value_types.get(key).cloned().unwrap_or_default()
+    }
+}
+
+// Sorts the results by the number of literal bytes matched in them (the
+// rest of the message is consumed by parsers), so the most specific result
+// is the first. Only the first result of every pattern is kept.
+pub fn rank_by_specificity<'a, 'b>(mut results: Vec<(usize, MatchResult<'a, 'b>)>)
+                                   -> Vec<MatchResult<'a, 'b>> {
+    results.sort_by(|first, second| second.0.cmp(&first.0));
+
+    let mut ranked: Vec<MatchResult> = Vec::new();
+    for (_, result) in results {
+        if ranked.iter().all(|other| other.pattern().uuid() != result.pattern().uuid()) {
+            ranked.push(result);
+        }
+    }
+    ranked
 }
 
 #[cfg(test)]
 mod test {
-    use parsers::{IntParser, ParseResult};
+    use parsers::{IntParser, SetParser, ParseResult, ValueType};
     use super::MatchResult;
     use matcher::Pattern;
     use std::collections::BTreeMap;
@@ -46,7 +82,7 @@ mod test {
         let parser_with_name = IntParser::with_name("name".to_owned());
         let expected_values = {
             let mut map = BTreeMap::new();
-            map.insert("name", "c");
+            map.insert("name".into(), "c".into());
             map
         };
         let pattern = Pattern::with_random_uuid();
@@ -56,4 +92,39 @@ mod test {
         match_result.insert(ParseResult::new(&parser_with_name, "c"));
         assert_eq!(match_result.values(), &expected_values);
     }
+
+    #[test]
+    fn test_given_match_result_when_a_parse_result_with_additional_values_is_inserted_then_they_are_inserted_too
+        () {
+        let parser = IntParser::with_name("name".to_owned());
+        let expected_values = {
+            let mut map = BTreeMap::new();
+            map.insert("name".into(), "a".into());
+            map.insert("name.extra".into(), "b".into());
+            map
+        };
+        let pattern = Pattern::with_random_uuid();
+        let mut match_result = MatchResult::new(&pattern);
+        let mut parse_result = ParseResult::new(&parser, "a");
+        parse_result.push_additional_value("name.extra".into(), "b".into());
+        match_result.insert(parse_result);
+        assert_eq!(match_result.values(), &expected_values);
+    }
+
+    #[test]
+    fn test_given_match_result_when_parse_results_are_inserted_then_the_types_of_their_values_are_stored
+        () {
+        let int_parser = IntParser::with_name("port".to_owned());
+        let set_parser = SetParser::from_str("user", "a-z");
+        let pattern = Pattern::with_random_uuid();
+        let mut match_result = MatchResult::new(&pattern);
+        let mut parse_result = ParseResult::new(&int_parser, "22");
+        parse_result.push_additional_value("port.name".into(), "ssh".into());
+        match_result.insert(parse_result);
+        match_result.insert(ParseResult::new(&set_parser, "joe"));
+        assert_eq!(ValueType::Int, match_result.value_type("port"));
+        assert_eq!(ValueType::String, match_result.value_type("port.name"));
+        assert_eq!(ValueType::String, match_result.value_type("user"));
+        assert_eq!(ValueType::String, match_result.value_type("missing"));
+    }
 }
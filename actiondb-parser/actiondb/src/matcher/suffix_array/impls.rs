@@ -5,21 +5,29 @@ use super::interface::{
     ParserEntry
 };
 
-use parsers::{Parser, ParseResult};
+use parsers::{Parser, ParserFactory};
 use matcher::{
     Matcher,
-    Pattern
+    Pattern,
+    DEFAULT_BACKTRACKING_BUDGET
 };
+use matcher::cache::{self, Cacheable, CacheError, Encoder, Decoder};
 use matcher::compiled_pattern::TokenType;
-use matcher::result::MatchResult;
+use matcher::explain::Explanation;
+use matcher::lint::{self, Overlap, PatternTree};
+use matcher::result::{self, MatchResult};
 use utils::CommonPrefix;
+use uuid::Uuid;
 
 use std::borrow::Borrow;
+use std::cmp;
+use std::collections::BTreeSet;
 
 #[derive(Debug, Clone)]
 pub struct SuffixTable {
     literal_entries: Vec<LiteralE>,
     parser_entries: Vec<ParserE>,
+    backtracking_budget: usize,
 }
 
 impl SuffixTable {
@@ -60,16 +68,194 @@ impl SuffixTable {
         }
     }
 
-    fn parse_with_parsers<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>> {
+    // The literal entries which are prefixes of the value are tried from the
+    // longest one, then the parsers, so a literal wins over a parser only if
+    // the rest of the value matches after it.
+    fn parse_with_budget<'a, 'b>(&'a self,
+                                 value: &'b str,
+                                 budget: &mut usize)
+                                 -> Option<MatchResult<'a, 'b>> {
+        for pos in self.prefix_entries(value) {
+            let child = &self.literal_entries[pos];
+            let remaining = value.ltrunc(child.literal().len());
+
+            let result = if remaining.is_empty() {
+                child.pattern().map(MatchResult::new)
+            } else {
+                child.child().and_then(|child| child.parse_with_budget(remaining, budget))
+            };
+            if result.is_some() {
+                return result;
+            }
+        }
+        self.parse_with_parsers(value, budget)
+    }
+
+    fn parse_with_parsers<'a, 'b>(&'a self,
+                                  value: &'b str,
+                                  budget: &mut usize)
+                                  -> Option<MatchResult<'a, 'b>> {
         for parser in &self.parser_entries {
-            if let Some(result) = parser.parse(value) {
+            if let Some(result) = parser.parse(value, budget) {
                 return Some(result);
             }
         }
         None
     }
 
-    fn insert_parser(&mut self, parser: Box<Parser>) -> &mut Entry<SA=SuffixTable> {
+    pub fn backtracking_budget(&self) -> usize {
+        self.backtracking_budget
+    }
+
+    // It walks the same path as parse_with_budget() and records it.
+    fn explain_with_budget<'a>(&'a self,
+                               value: &str,
+                               budget: &mut usize,
+                               explanation: &mut Explanation)
+                               -> Option<&'a Pattern> {
+        explanation.reach(value, |patterns| self.collect_patterns(patterns));
+
+        let prefix_entries = self.prefix_entries(value);
+        for &pos in &prefix_entries {
+            let child = &self.literal_entries[pos];
+            let matched = child.literal().len();
+            explanation.push_literal(value, child.literal(), matched);
+            let remaining = value.ltrunc(matched);
+            explanation.reach(remaining, |patterns| collect_entry_patterns(child, patterns));
+
+            let pattern = if remaining.is_empty() {
+                child.pattern()
+            } else {
+                child.child().and_then(|child| {
+                    child.explain_with_budget(remaining, budget, explanation)
+                })
+            };
+            if pattern.is_some() {
+                return pattern;
+            }
+        }
+
+        // the closest literal is recorded even if it doesn't match fully
+        if prefix_entries.is_empty() {
+            if let Some(child) = self.longest_common_prefix(value) {
+                let matched = child.literal().common_prefix_len(value);
+                explanation.push_literal(value, child.literal(), matched);
+                explanation.reach(value.ltrunc(matched),
+                                  |patterns| collect_entry_patterns(child, patterns));
+            }
+        }
+        for parser in &self.parser_entries {
+            if let Some(pattern) = parser.explain(value, budget, explanation) {
+                return Some(pattern);
+            }
+        }
+        None
+    }
+
+    // Every literal entry which is a prefix of the value and every parser
+    // entry is tried. The results are pushed with the number of literal
+    // bytes matched in them. The budget is shared only by the alternatives
+    // on the same path.
+    fn parse_all_with_budget<'a, 'b>(&'a self,
+                                     value: &'b str,
+                                     literal_length: usize,
+                                     budget: usize,
+                                     results: &mut Vec<(usize, MatchResult<'a, 'b>)>) {
+        for pos in self.prefix_entries(value).into_iter().rev() {
+            let entry = &self.literal_entries[pos];
+            let prefix_length = entry.literal().len();
+            let remaining = value.ltrunc(prefix_length);
+            let literal_length = literal_length + prefix_length;
+
+            if remaining.is_empty() {
+                if let Some(pattern) = entry.pattern() {
+                    results.push((literal_length, MatchResult::new(pattern)));
+                }
+            }
+            if let Some(child) = entry.child() {
+                child.parse_all_with_budget(remaining, literal_length, budget, results);
+            }
+        }
+        for parser in &self.parser_entries {
+            parser.parse_all(value, literal_length, budget, results);
+        }
+    }
+
+    // Returns the positions of the literal entries which are prefixes of the
+    // value, the longest one first. The greatest entry less than the probe
+    // has every shorter prefix entry as its own prefix, so the next probe is
+    // its common prefix with the value.
+    fn prefix_entries(&self, value: &str) -> Vec<usize> {
+        let mut positions = Vec::new();
+        let mut end = value.len();
+
+        while end > 0 {
+            let probe = &value[..end];
+            let pos = match self.literal_entries
+                                .binary_search_by(|entry| entry.literal()[..].cmp(probe)) {
+                Ok(pos) => {
+                    positions.push(pos);
+                    pos
+                }
+                Err(pos) => pos,
+            };
+            if pos == 0 {
+                break;
+            }
+            end = self.literal_entries[pos - 1].literal().common_prefix_len(probe);
+        }
+        positions
+    }
+
+    fn collect_patterns<'a>(&'a self, patterns: &mut Vec<&'a Pattern>) {
+        for entry in &self.literal_entries {
+            collect_entry_patterns(entry, patterns);
+        }
+        for entry in &self.parser_entries {
+            collect_entry_patterns(entry, patterns);
+        }
+    }
+
+    // The entries whose literal is extended by other literals of the table
+    // get the rest of those literals, because they are compared to the
+    // parsers of the entry's child table.
+    fn collect_overlaps_with_literals<'a>(&'a self,
+                                          extending: Vec<(&'a str, BTreeSet<Uuid>)>,
+                                          overlaps: &mut Vec<Overlap>) {
+        let mut literals = self.literal_entries
+                               .iter()
+                               .map(|entry| (&entry.literal()[..], collect_entry_reachable(entry)))
+                               .collect::<Vec<_>>();
+        literals.extend(extending);
+        let parsers = self.parser_entries
+                          .iter()
+                          .map(|entry| (&**entry.parser(), collect_entry_reachable(entry)))
+                          .collect::<Vec<_>>();
+        lint::find_overlaps(&literals, &parsers, overlaps);
+
+        for entry in &self.literal_entries {
+            if let Some(child) = entry.child() {
+                let prefix = entry.literal();
+                let extending = literals.iter()
+                                        .filter(|&&(literal, _)| {
+                                            literal.len() > prefix.len() &&
+                                            literal.starts_with(&prefix[..])
+                                        })
+                                        .map(|&(literal, ref uuids)| {
+                                            (&literal[prefix.len()..], uuids.clone())
+                                        })
+                                        .collect();
+                child.collect_overlaps_with_literals(extending, overlaps);
+            }
+        }
+        for entry in &self.parser_entries {
+            if let Some(child) = entry.child() {
+                child.collect_overlaps_with_literals(Vec::new(), overlaps);
+            }
+        }
+    }
+
+    fn insert_parser(&mut self, parser: Box<Parser>) -> &mut ParserE {
         let pos = self.parser_entries.iter().position(|x| {
             x.parser.hash_os() == parser.hash_os()
         });
@@ -82,6 +268,27 @@ impl SuffixTable {
         }
     }
 
+    // The parser entries are tried in order, so the ones leading to
+    // patterns with higher priority and then the more specific parsers are
+    // moved forward. Otherwise the insertion order is kept. It's done once
+    // for the whole table after the patterns are inserted.
+    fn sort_parser_entries(&mut self) {
+        self.parser_entries.sort_by(|first, second| {
+            (second.priority, second.parser.specificity())
+                .cmp(&(first.priority, first.parser.specificity()))
+        });
+        for entry in &mut self.literal_entries {
+            if let Some(child) = entry.child_mut() {
+                child.sort_parser_entries();
+            }
+        }
+        for entry in &mut self.parser_entries {
+            if let Some(child) = entry.child_mut() {
+                child.sort_parser_entries();
+            }
+        }
+    }
+
     pub fn longest_common_prefix<'a, 'b>(&'a self, value: &'b str) -> Option<&'a LiteralE> {
         let result = self.literal_entries.binary_search_by(|probe| {
             let s: &str = probe.literal().borrow();
@@ -94,11 +301,51 @@ impl SuffixTable {
     }
 }
 
+fn collect_entry_patterns<'a, E>(entry: &'a E, patterns: &mut Vec<&'a Pattern>)
+    where E: Entry<SA=SuffixTable>
+{
+    if let Some(pattern) = entry.pattern() {
+        patterns.push(pattern);
+    }
+    if let Some(child) = entry.child() {
+        child.collect_patterns(patterns);
+    }
+}
+
+fn collect_entry_reachable<E>(entry: &E) -> BTreeSet<Uuid>
+    where E: Entry<SA=SuffixTable>
+{
+    let mut uuids = entry.child().map_or_else(BTreeSet::new, |child| child.collect_reachable());
+    if let Some(pattern) = entry.pattern() {
+        uuids.insert(pattern.uuid().clone());
+    }
+    uuids
+}
+
+impl PatternTree for SuffixTable {
+    fn collect_reachable(&self) -> BTreeSet<Uuid> {
+        let mut uuids = BTreeSet::new();
+
+        for entry in &self.literal_entries {
+            uuids.extend(collect_entry_reachable(entry));
+        }
+        for entry in &self.parser_entries {
+            uuids.extend(collect_entry_reachable(entry));
+        }
+        uuids
+    }
+
+    fn collect_overlaps(&self, overlaps: &mut Vec<Overlap>) {
+        self.collect_overlaps_with_literals(Vec::new(), overlaps);
+    }
+}
+
 impl Default for SuffixTable {
     fn default() -> Self {
         SuffixTable {
             literal_entries: Vec::new(),
-            parser_entries: Vec::new()
+            parser_entries: Vec::new(),
+            backtracking_budget: DEFAULT_BACKTRACKING_BUDGET,
         }
     }
 }
@@ -109,25 +356,99 @@ impl SuffixArray for SuffixTable {
     }
 
     fn insert(&mut self, mut pattern: Pattern) {
+        if pattern.has_alternatives() {
+            match pattern.expand_alternatives() {
+                Ok(variants) => {
+                    for variant in variants {
+                        self.insert(variant);
+                    }
+                }
+                Err(error) => {
+                    error!("Failed to insert pattern: uuid={} error={}",
+                           pattern.uuid().hyphenated(),
+                           error)
+                }
+            }
+            return;
+        }
+
         if let Some(token) = pattern.pop_first_token() {
-            let mut entry: &mut Entry<SA=SuffixTable> = match token {
+            match token {
                 TokenType::Literal(literal) => {
-                    self.insert_literal(literal)
+                    self.insert_literal(literal).insert(pattern);
                 },
                 TokenType::Parser(parser) => {
-                    self.insert_parser(parser)
+                    let priority = pattern.priority();
+                    let entry = self.insert_parser(parser);
+                    entry.raise_priority(priority);
+                    entry.insert(pattern);
+                },
+                TokenType::Alternation(_) | TokenType::Optional(_) => {
+                    unreachable!("groups are expanded before insertion")
                 }
-            };
-            entry.insert(pattern);
+            }
         }
     }
 }
 
+fn encode_entry<E>(entry: &E, encoder: &mut Encoder)
+    where E: Entry<SA=SuffixTable>
+{
+    encoder.write_option(entry.pattern(), cache::encode_pattern);
+    encoder.write_option(entry.child(), |encoder, child| child.encode(encoder));
+}
+
+// The entries are stored in their order, so they don't have to be sorted
+// again.
+impl Cacheable for SuffixTable {
+    fn cache_name() -> &'static str {
+        "suffix_array"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write_usize(self.literal_entries.len());
+        for entry in &self.literal_entries {
+            encoder.write_str(entry.literal());
+            encode_entry(entry, encoder);
+        }
+
+        encoder.write_usize(self.parser_entries.len());
+        for entry in &self.parser_entries {
+            cache::encode_parser(encoder, &*entry.parser);
+            encoder.write_i32(entry.priority);
+            encode_entry(entry, encoder);
+        }
+    }
+
+    fn decode<F: ParserFactory>(decoder: &mut Decoder) -> Result<SuffixTable, CacheError> {
+        let mut table = SuffixTable::new();
+
+        for _ in 0..try!(decoder.read_usize()) {
+            let mut entry = LiteralE::new(try!(decoder.read_string()));
+            entry.pattern = try!(decoder.read_option(cache::decode_pattern));
+            entry.child = try!(decoder.read_option(|decoder| SuffixTable::decode::<F>(decoder)));
+            table.literal_entries.push(entry);
+        }
+
+        for _ in 0..try!(decoder.read_usize()) {
+            let mut entry = ParserE::new(try!(cache::decode_parser::<F>(decoder)));
+            entry.priority = try!(decoder.read_i32());
+            entry.pattern = try!(decoder.read_option(cache::decode_pattern));
+            entry.child = try!(decoder.read_option(|decoder| SuffixTable::decode::<F>(decoder)));
+            table.parser_entries.push(entry);
+        }
+
+        Ok(table)
+    }
+}
+
 #[derive(Debug)]
 pub struct ParserE {
     pattern: Option<Pattern>,
     parser: Box<Parser>,
-    child: Option<SuffixTable>
+    child: Option<SuffixTable>,
+    // the highest priority of the patterns under this entry
+    priority: i32,
 }
 
 impl Clone for ParserE {
@@ -135,7 +456,8 @@ impl Clone for ParserE {
         ParserE {
             pattern: self.pattern.clone(),
             parser: self.parser.boxed_clone(),
-            child: self.child.clone()
+            child: self.child.clone(),
+            priority: self.priority,
         }
     }
 }
@@ -145,20 +467,109 @@ impl ParserE {
         ParserE {
             pattern: None,
             parser: parser,
-            child: None
+            child: None,
+            priority: i32::min_value(),
         }
     }
 
-    fn create_match_result<'a, 'b>(&'a self, kvpair: ParseResult<'a, 'b>) -> Option<MatchResult<'a, 'b>> {
+    fn raise_priority(&mut self, priority: i32) {
+        self.priority = cmp::max(self.priority, priority);
+    }
+
+    fn create_match_result<'a, 'b>(&'a self) -> Option<MatchResult<'a, 'b>> {
         if let Some(pattern) = self.pattern() {
-            let mut result = MatchResult::new(pattern);
-            result.insert(kvpair);
-            Some(result)
+            Some(MatchResult::new(pattern))
         } else {
             debug!("Value parsing ended before reaching a leaf. Please create a new, shorter pattern.");
             None
         }
     }
+
+    fn parse_remaining<'a, 'b>(&'a self,
+                               value: &'b str,
+                               budget: &mut usize)
+                               -> Option<MatchResult<'a, 'b>> {
+        match self.child() {
+            Some(child) if !value.is_empty() || self.pattern().is_none() => {
+                child.parse_with_budget(value, budget)
+            }
+            _ if value.is_empty() => self.create_match_result(),
+            _ => None,
+        }
+    }
+
+    // Unlike parse(), the alternative matches of the parser are tried even
+    // if the rest of the value can be parsed. Each of them continues with
+    // the budget left on this path.
+    fn parse_all<'a, 'b>(&'a self,
+                         value: &'b str,
+                         literal_length: usize,
+                         mut budget: usize,
+                         results: &mut Vec<(usize, MatchResult<'a, 'b>)>) {
+        let mut kvpair = self.parser.parse(value);
+
+        while let Some(result) = kvpair {
+            let match_length = result.match_length();
+            let remaining = value.ltrunc(match_length);
+            let first = results.len();
+
+            if remaining.is_empty() {
+                if let Some(pattern) = self.pattern() {
+                    results.push((literal_length, MatchResult::new(pattern)));
+                }
+            }
+            if let Some(child) = self.child() {
+                child.parse_all_with_budget(remaining, literal_length, budget, results);
+            }
+            for &mut (_, ref mut match_result) in &mut results[first..] {
+                match_result.insert(result.clone());
+            }
+            if budget == 0 {
+                debug!("The backtracking budget is exhausted");
+                return;
+            }
+            budget -= 1;
+            kvpair = self.parser.parse_next(value, match_length);
+        }
+    }
+
+    // It walks the same path as ParserEntry::parse() and records it.
+    fn explain<'a>(&'a self,
+                   value: &str,
+                   budget: &mut usize,
+                   explanation: &mut Explanation)
+                   -> Option<&'a Pattern> {
+        let mut match_length = self.parser.parse(value).map(|result| result.match_length());
+        explanation.push_parser(value, &*self.parser, match_length);
+
+        while let Some(length) = match_length {
+            let remaining = value.ltrunc(length);
+            explanation.reach(remaining, |patterns| collect_entry_patterns(self, patterns));
+
+            let pattern = match self.child() {
+                Some(child) if !remaining.is_empty() || self.pattern().is_none() => {
+                    child.explain_with_budget(remaining, budget, explanation)
+                }
+                _ if remaining.is_empty() => self.pattern(),
+                _ => None,
+            };
+            if pattern.is_some() {
+                return pattern;
+            }
+            if *budget == 0 {
+                return None;
+            }
+            *budget -= 1;
+            match_length = self.parser
+                               .parse_next(value, length)
+                               .map(|result| result.match_length());
+            if match_length.is_some() {
+                explanation.push_parser(value, &*self.parser, match_length);
+            }
+        }
+
+        None
+    }
 }
 
 impl Entry for ParserE {
@@ -183,28 +594,29 @@ impl ParserEntry for ParserE {
     fn parser(&self) -> &Box<Parser> {
         &self.parser
     }
-    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>> {
-        self.parser.parse(value).and_then(|kvpair| {
-            let value = value.ltrunc(kvpair.value().len());
+    // If the rest of the value can't be parsed, the alternative matches of
+    // the parser are tried while the budget lasts.
+    fn parse<'a, 'b>(&'a self, value: &'b str, budget: &mut usize) -> Option<MatchResult<'a, 'b>> {
+        let mut kvpair = self.parser.parse(value);
 
-            if let Some(child) = self.child() {
-                child.parse(value).and_then(|mut result| {
-                    result.insert(kvpair);
-                    Some(result)
-                })
-            } else if value.is_empty() {
-                self.create_match_result(kvpair)
-            } else {
-                None
+        while let Some(result) = kvpair {
+            let match_length = result.match_length();
+            let remaining = value.ltrunc(match_length);
+
+            if let Some(mut match_result) = self.parse_remaining(remaining, budget) {
+                match_result.insert(result);
+                return Some(match_result);
             }
-        })
-    }
-}
+            if *budget == 0 {
+                debug!("The backtracking budget is exhausted");
+                return None;
+            }
+            *budget -= 1;
+            kvpair = self.parser.parse_next(value, match_length);
+        }
 
-pub enum MatchType {
-    Exact,
-    Partial(usize),
-    None
+        None
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -222,18 +634,6 @@ impl LiteralE {
             child: None
         }
     }
-
-    pub fn determine_match_type(&self, value: &str) -> MatchType {
-        let common_prefix_len = self.literal().common_prefix_len(value);
-
-        if common_prefix_len == value.len() && common_prefix_len == self.literal().len() {
-            MatchType::Exact
-        } else if common_prefix_len < value.len() {
-            MatchType::Partial(common_prefix_len)
-        } else {
-            MatchType::None
-        }
-    }
 }
 
 impl Entry for LiteralE {
@@ -263,24 +663,30 @@ impl LiteralEntry for LiteralE {
 
 impl Matcher for SuffixTable {
     fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>> {
-        if let Some(child) = self.longest_common_prefix(value) {
-            match child.determine_match_type(value) {
-                MatchType::Exact => {
-                    child.pattern().and_then(|pattern| Some(MatchResult::new(pattern)))
-                },
-                MatchType::Partial(common_prefix_len) => {
-                    let value = value.ltrunc(common_prefix_len);
-                    child.child().and_then(|child| child.parse(value))
-                },
-                MatchType::None => None
-            }
-        } else {
-            self.parse_with_parsers(value)
-        }
+        let mut budget = self.backtracking_budget;
+        self.parse_with_budget(value, &mut budget)
+    }
+    fn parse_all<'a, 'b>(&'a self, value: &'b str) -> Vec<MatchResult<'a, 'b>> {
+        let mut results = Vec::new();
+        self.parse_all_with_budget(value, 0, self.backtracking_budget, &mut results);
+        result::rank_by_specificity(results)
+    }
+    fn explain(&self, value: &str) -> Explanation {
+        let mut explanation = Explanation::new(value);
+        let mut budget = self.backtracking_budget;
+        let pattern = self.explain_with_budget(value, &mut budget, &mut explanation);
+        explanation.set_matched(pattern);
+        explanation
+    }
+    fn set_backtracking_budget(&mut self, budget: usize) {
+        self.backtracking_budget = budget;
     }
     fn add_pattern(&mut self, pattern: Pattern) {
         self.insert(pattern);
     }
+    fn sort_parsers(&mut self) {
+        self.sort_parser_entries();
+    }
     fn boxed_clone(&self) -> Box<Matcher> {
         Box::new(self.clone())
     }
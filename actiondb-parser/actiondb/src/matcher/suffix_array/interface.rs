@@ -16,10 +16,16 @@ pub trait Entry {
     fn set_child(&mut self, child: Option<Self::SA>);
     fn insert(&mut self, pattern: Pattern) {
         if pattern.pattern().is_empty() {
-            self.set_pattern(Some(pattern));
+            // an identical pattern is replaced unless the old one has a higher priority
+            let replace = self.pattern().map_or(true, |old| old.priority() <= pattern.priority());
+            if replace {
+                self.set_pattern(Some(pattern));
+            }
         } else {
-            let sa = Self::SA::new();
-            self.set_child(Some(sa));
+            if self.child().is_none() {
+                let sa = Self::SA::new();
+                self.set_child(Some(sa));
+            }
             self.child_mut().expect("Failed to get a child").insert(pattern);
         }
     }
@@ -30,6 +36,6 @@ pub trait LiteralEntry: Entry + Clone {
 }
 
 pub trait ParserEntry: Entry + Clone {
-    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>>;
+    fn parse<'a, 'b>(&'a self, value: &'b str, budget: &mut usize) -> Option<MatchResult<'a, 'b>>;
     fn parser(&self) -> &Box<Parser>;
 }
@@ -8,6 +8,8 @@ use matcher::Matcher;
 
 use std::iter::FromIterator;
 use std::collections::BTreeMap;
+use std::borrow::Cow;
+use uuid::Uuid;
 
 fn create_populated_suffix_table() -> SuffixTable {
     let mut root = SuffixTable::new();
@@ -53,7 +55,10 @@ fn test_given_parser_trie_when_a_parser_is_not_matched_then_the_parser_stack_is_
     println!("root: {:?}", &root);
     {
         let result = root.parse("app42letree123");
-        let expected = BTreeMap::from_iter(vec![("end", "123"), ("middle", "42")].into_iter());
+        let expected: BTreeMap<Cow<str>, Cow<str>> =
+            BTreeMap::from_iter(vec![("end".into(), "123".into()),
+                                     ("middle".into(), "42".into())]
+                                    .into_iter());
 
         assert_eq!(&expected, result.expect("Failed to get result").values());
     }
@@ -121,6 +126,14 @@ fn test_given_suffix_array_when_during_parsing_the_parsed_value_is_not_empty_but
     assert_eq!(true, root.parse("apple").is_none());
 }
 
+#[test]
+fn test_given_suffix_table_when_a_literal_is_only_partially_matched_then_its_child_is_not_tried() {
+    let root = create_suffix_table_from_pattern("Accepted password for %{GREEDY:user}");
+    assert_eq!(true, root.parse("Accepted password for joe").is_some());
+    assert_eq!(true, root.parse("Failed password for joe").is_none());
+    assert_eq!(true, root.parse("Accepted key for joe").is_none());
+}
+
 #[test]
 fn test_given_suffix_array_when_a_literal_entry_is_found_then_it_is_returned() {
     let mut root = SuffixTable::new();
@@ -175,3 +188,230 @@ fn test_given_parser_when_it_receives_empty_message_and_there_is_no_matching_pat
 
     assert_eq!(true, root.parse("").is_none());
 }
+
+#[test]
+fn test_given_patterns_which_differ_only_in_the_type_of_the_address_parser_when_we_parse_messages_then_the_address_decides_the_pattern() {
+    let mut root = SuffixTable::new();
+    let mut ipv4_pattern = Pattern::with_random_uuid();
+    ipv4_pattern.set_pattern(::grammar::parser::pattern("from %{IPV4:ip} port %{INT:port}").unwrap());
+    let ipv4_uuid = ipv4_pattern.uuid().clone();
+    let mut ipv6_pattern = Pattern::with_random_uuid();
+    ipv6_pattern.set_pattern(::grammar::parser::pattern("from %{IPV6:ip} port %{INT:port}").unwrap());
+    let ipv6_uuid = ipv6_pattern.uuid().clone();
+    root.insert(ipv4_pattern);
+    root.insert(ipv6_pattern);
+
+    let result = root.parse("from 10.0.0.1 port 22").expect("Failed to parse an IPv4 address");
+    assert_eq!(&ipv4_uuid, result.pattern().uuid());
+    assert_eq!(Some(&"10.0.0.1".into()), result.values().get("ip"));
+
+    let result = root.parse("from fe80::1 port 22").expect("Failed to parse an IPv6 address");
+    assert_eq!(&ipv6_uuid, result.pattern().uuid());
+    assert_eq!(Some(&"fe80::1".into()), result.values().get("ip"));
+
+    assert_eq!(true, root.parse("from 10.0.0.256 port 22").is_none());
+}
+
+#[test]
+fn test_given_suffix_table_when_a_pattern_contains_a_regex_parser_then_its_named_captures_are_extracted
+    () {
+    let mut root = SuffixTable::new();
+    let mut pattern = Pattern::with_random_uuid();
+    let compiled_pattern = r#"user %{REGEX("(?P<user>\w+)@(?P<host>[\w.]+)"):email} logged in"#;
+    pattern.set_pattern(::grammar::parser::pattern(compiled_pattern).unwrap());
+    root.insert(pattern);
+
+    let result = root.parse("user joe@example.com logged in")
+                     .expect("Failed to parse a message with a regex");
+    let expected: BTreeMap<Cow<str>, Cow<str>> =
+        BTreeMap::from_iter(vec![("email".into(), "joe@example.com".into()),
+                                 ("host".into(), "example.com".into()),
+                                 ("user".into(), "joe".into())]
+                                .into_iter());
+    assert_eq!(&expected, result.values());
+    assert_eq!(true, root.parse("user joe logged in").is_none());
+}
+
+#[test]
+fn test_given_suffix_table_when_a_pattern_with_groups_is_inserted_then_all_of_its_variants_are_matched
+    () {
+    let mut root = SuffixTable::new();
+    let mut pattern = Pattern::with_random_uuid();
+    let compiled_pattern = "Accepted %{(publickey|password)} for %{INT:user}%{( [preauth])?}";
+    pattern.set_pattern(::grammar::parser::pattern(compiled_pattern).unwrap());
+    let uuid = pattern.uuid().clone();
+    root.insert(pattern);
+
+    for message in &["Accepted publickey for 42",
+                     "Accepted password for 42",
+                     "Accepted publickey for 42 [preauth]",
+                     "Accepted password for 42 [preauth]"] {
+        let result = root.parse(message).expect("Failed to parse a variant of the pattern");
+        assert_eq!(&uuid, result.pattern().uuid());
+        assert_eq!(Some(&"42".into()), result.values().get("user"));
+    }
+    assert_eq!(true, root.parse("Accepted keyboard-interactive for 42").is_none());
+}
+
+fn create_suffix_table_from_pattern(pattern: &str) -> SuffixTable {
+    let mut root = SuffixTable::new();
+    let mut pattern_1 = Pattern::with_random_uuid();
+    pattern_1.set_pattern(::grammar::parser::pattern(pattern).unwrap());
+    root.insert(pattern_1);
+    root
+}
+
+#[test]
+fn test_given_suffix_table_when_the_first_match_of_a_greedy_parser_is_wrong_then_the_next_one_is_tried
+    () {
+    let root = create_suffix_table_from_pattern("%{GREEDY:a} - %{GREEDY:b} - end");
+    let result = root.parse("x - y - end - z - end")
+                     .expect("Failed to backtrack to the next split");
+    assert_eq!(Some(&"x".into()), result.values().get("a"));
+    assert_eq!(Some(&"y - end - z".into()), result.values().get("b"));
+
+    let root = create_suffix_table_from_pattern("%{GREEDY:a} - %{INT:n} - end");
+    let result = root.parse("x - y - 5 - end").expect("Failed to backtrack to the next split");
+    assert_eq!(Some(&"x - y".into()), result.values().get("a"));
+    assert_eq!(Some(&"5".into()), result.values().get("n"));
+}
+
+#[test]
+fn test_given_suffix_table_when_a_set_parser_consumes_too_much_then_a_shorter_match_is_tried() {
+    let root = create_suffix_table_from_pattern("%{SET(\"a-z\"):word}bar %{INT:n}");
+    let result = root.parse("foobar 42").expect("Failed to backtrack to a shorter match");
+    assert_eq!(Some(&"foo".into()), result.values().get("word"));
+    assert_eq!(Some(&"42".into()), result.values().get("n"));
+}
+
+#[test]
+fn test_given_suffix_table_when_a_set_parser_has_a_maximum_length_then_shorter_matches_are_tried() {
+    let root = create_suffix_table_from_pattern("%{SET(\"a-z\",max_len=3):word}ar");
+    let result = root.parse("far").expect("Failed to backtrack within the maximum length");
+    assert_eq!(Some(&"f".into()), result.values().get("word"));
+    // a longer run doesn't match at all, so its prefixes are not tried
+    assert_eq!(true, root.parse("fooar").is_none());
+}
+
+#[test]
+fn test_given_suffix_table_when_the_backtracking_budget_is_exhausted_then_the_message_does_not_match
+    () {
+    let mut root = create_suffix_table_from_pattern("%{GREEDY:a} - %{INT:n} - end");
+    root.set_backtracking_budget(1);
+    assert_eq!(true, root.parse("x - y - 5 - end").is_some());
+    assert_eq!(true, root.parse("x - y - z - 5 - end").is_none());
+    root.set_backtracking_budget(0);
+    assert_eq!(true, root.parse("x - y - 5 - end").is_none());
+    assert_eq!(true, root.parse("x - 5 - end").is_some());
+}
+
+fn create_suffix_table_from_patterns(patterns: &[&str]) -> (SuffixTable, Vec<Uuid>) {
+    let mut root = SuffixTable::new();
+    let mut uuids = Vec::new();
+    for pattern in patterns {
+        let mut pattern_1 = Pattern::with_random_uuid();
+        pattern_1.set_pattern(::grammar::parser::pattern(pattern).unwrap());
+        uuids.push(pattern_1.uuid().clone());
+        root.insert(pattern_1);
+    }
+    root.sort_parsers();
+    (root, uuids)
+}
+
+#[test]
+fn test_given_suffix_table_when_the_literal_path_fails_then_the_parsers_are_tried() {
+    let (root, uuids) = create_suffix_table_from_patterns(&["foo %{INT:n}", "%{GREEDY:a} bar"]);
+    let result = root.parse("foo bar").expect("Failed to fall back to the parsers");
+    assert_eq!(&uuids[1], result.pattern().uuid());
+    assert_eq!(Some(&"foo".into()), result.values().get("a"));
+
+    let results = root.parse_all("foo bar");
+    assert_eq!(1, results.len());
+    assert_eq!(result.pattern().uuid(), results[0].pattern().uuid());
+}
+
+#[test]
+fn test_given_suffix_table_when_the_longest_literal_path_fails_then_a_shorter_one_is_tried() {
+    let (root, uuids) = create_suffix_table_from_patterns(&["foo bar %{INT:n}",
+                                                             "foo %{GREEDY:rest}"]);
+    let result = root.parse("foo bar baz").expect("Failed to fall back to a shorter literal");
+    assert_eq!(&uuids[1], result.pattern().uuid());
+    assert_eq!(Some(&"bar baz".into()), result.values().get("rest"));
+    assert_eq!(&uuids[0], root.parse("foo bar 42").unwrap().pattern().uuid());
+}
+
+#[test]
+fn test_given_suffix_table_when_every_match_is_requested_then_the_most_specific_one_is_the_first() {
+    let mut root = SuffixTable::new();
+    let patterns = ["user %{GREEDY:user} logged in",
+                    "user %{INT:uid} logged in",
+                    "user 42 logged in"];
+    let mut uuids = Vec::new();
+    for pattern in &patterns {
+        let mut pattern_1 = Pattern::with_random_uuid();
+        pattern_1.set_pattern(::grammar::parser::pattern(pattern).unwrap());
+        uuids.push(pattern_1.uuid().clone());
+        root.insert(pattern_1);
+    }
+
+    let results = root.parse_all("user 42 logged in");
+    assert_eq!(3, results.len());
+    assert_eq!(&uuids[2], results[0].pattern().uuid());
+    assert_eq!(true, results[0].values().is_empty());
+    assert_eq!(true, results[1..].iter().any(|result| result.values().get("uid").is_some()));
+    assert_eq!(true, results[1..].iter().any(|result| result.values().get("user").is_some()));
+
+    let results = root.parse_all("user joe logged in");
+    assert_eq!(1, results.len());
+    assert_eq!(&uuids[0], results[0].pattern().uuid());
+}
+
+fn create_pattern_with_priority(pattern: &str, priority: i32) -> Pattern {
+    let mut result = Pattern::with_random_uuid();
+    result.set_pattern(::grammar::parser::pattern(pattern).unwrap());
+    result.set_priority(priority);
+    result
+}
+
+#[test]
+fn test_given_suffix_table_when_parsers_overlap_then_the_more_specific_one_wins_regardless_of_the_order
+    () {
+    let greedy = create_pattern_with_priority("user %{GREEDY:user} logged in", 0);
+    let int = create_pattern_with_priority("user %{INT:uid} logged in", 0);
+    let mut root = SuffixTable::new();
+    root.insert(greedy.clone());
+    root.insert(int.clone());
+    root.sort_parsers();
+
+    let result = root.parse("user 42 logged in").expect("Failed to parse a message");
+    assert_eq!(int.uuid(), result.pattern().uuid());
+    let result = root.parse("user joe logged in").expect("Failed to parse a message");
+    assert_eq!(greedy.uuid(), result.pattern().uuid());
+}
+
+#[test]
+fn test_given_suffix_table_when_a_pattern_has_higher_priority_then_it_wins_over_more_specific_ones
+    () {
+    let greedy = create_pattern_with_priority("user %{GREEDY:user} logged in", 10);
+    let int = create_pattern_with_priority("user %{INT:uid} logged in", 0);
+    let mut root = SuffixTable::new();
+    root.insert(int.clone());
+    root.insert(greedy.clone());
+    root.sort_parsers();
+
+    let result = root.parse("user 42 logged in").expect("Failed to parse a message");
+    assert_eq!(greedy.uuid(), result.pattern().uuid());
+}
+
+#[test]
+fn test_given_suffix_table_when_an_identical_pattern_with_lower_priority_is_inserted_then_it_does_not_replace_the_old_one
+    () {
+    let first = create_pattern_with_priority("user %{INT:uid} logged in", 1);
+    let second = create_pattern_with_priority("user %{INT:uid} logged in", 0);
+    let mut root = SuffixTable::new();
+    root.insert(first.clone());
+    root.insert(second.clone());
+
+    let result = root.parse("user 42 logged in").expect("Failed to parse a message");
+    assert_eq!(first.uuid(), result.pattern().uuid());
+}
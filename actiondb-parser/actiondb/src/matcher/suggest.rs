@@ -0,0 +1,444 @@
+use uuid::Uuid;
+
+use matcher::Pattern;
+use matcher::compiled_pattern::{CompiledPattern, CompiledPatternBuilder};
+use matcher::pattern::testmessage::TestMessage;
+use parsers::{Parser, IntParser, Ipv4Parser, GreedyParser};
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::net::Ipv4Addr;
+use std::str::FromStr;
+
+pub const DEFAULT_SIMILARITY: f64 = 0.5;
+pub const DEFAULT_SAMPLES: usize = 3;
+
+const TOKEN_SEPARATOR: char = ' ';
+
+// Suggests patterns for a set of lines (e.g. the unmatched ones). The lines
+// are split into tokens at the spaces and the lines with the same number of
+// tokens are clustered by the ratio of their equal tokens, like Drain does.
+// The tokens which differ between the lines of a cluster (and the tokens
+// which contain a digit) become parsers in the suggested patterns.
+pub struct Suggester {
+    similarity: f64,
+    samples: usize,
+    // the clusters by the number of their tokens
+    clusters: HashMap<usize, Vec<Cluster>>,
+    cluster_count: usize,
+}
+
+impl Suggester {
+    pub fn new() -> Suggester {
+        Suggester::default()
+    }
+
+    // A line is added to the most similar cluster if the ratio of its equal
+    // tokens is at least `similarity` (between 0 and 1).
+    pub fn set_similarity(&mut self, similarity: f64) {
+        self.similarity = similarity;
+    }
+
+    // The number of lines of a cluster which become the test messages of
+    // its pattern.
+    pub fn set_samples(&mut self, samples: usize) {
+        self.samples = samples;
+    }
+
+    pub fn add_line(&mut self, line: &str) {
+        let tokens = line.split(TOKEN_SEPARATOR).collect::<Vec<_>>();
+        let similarity = self.similarity;
+        let samples = self.samples;
+        let clusters = self.clusters.entry(tokens.len()).or_insert_with(Vec::new);
+
+        let mut best: Option<(usize, f64)> = None;
+        for (index, cluster) in clusters.iter().enumerate() {
+            let cluster_similarity = cluster.similarity(&tokens);
+            if cluster_similarity >= similarity &&
+               best.map_or(true, |(_, best_similarity)| cluster_similarity > best_similarity) {
+                best = Some((index, cluster_similarity));
+            }
+        }
+
+        match best {
+            Some((index, _)) => clusters[index].add(line, &tokens, samples),
+            None => {
+                clusters.push(Cluster::new(self.cluster_count, line, &tokens, samples));
+                self.cluster_count += 1;
+            }
+        }
+    }
+
+    // Returns a suggested pattern for every cluster, the ones with the most
+    // lines are the first.
+    pub fn suggestions(&self) -> Vec<Suggestion> {
+        let mut clusters = self.clusters.values().flat_map(|clusters| clusters).collect::<Vec<_>>();
+        clusters.sort_by(|a, b| (b.count, a.index).cmp(&(a.count, b.index)));
+        clusters.into_iter().map(Cluster::suggest).collect()
+    }
+}
+
+impl Default for Suggester {
+    fn default() -> Suggester {
+        Suggester {
+            similarity: DEFAULT_SIMILARITY,
+            samples: DEFAULT_SAMPLES,
+            clusters: HashMap::new(),
+            cluster_count: 0,
+        }
+    }
+}
+
+pub struct Suggestion {
+    pattern: Pattern,
+    count: usize,
+}
+
+impl Suggestion {
+    pub fn pattern(&self) -> &Pattern {
+        &self.pattern
+    }
+
+    // The number of lines the pattern was suggested for.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn into_pattern(self) -> Pattern {
+        self.pattern
+    }
+}
+
+enum Token {
+    Constant(String),
+    // the distinct values of the token in the lines of the cluster
+    Variable(BTreeSet<String>),
+}
+
+struct Cluster {
+    // the order of the creation of the cluster
+    index: usize,
+    tokens: Vec<Token>,
+    count: usize,
+    samples: Vec<String>,
+}
+
+impl Cluster {
+    fn new(index: usize, line: &str, tokens: &[&str], samples: usize) -> Cluster {
+        let tokens = tokens.iter()
+                           .map(|token| {
+                               if is_variable(token) {
+                                   Token::Variable(Some(token.to_string()).into_iter().collect())
+                               } else {
+                                   Token::Constant(token.to_string())
+                               }
+                           })
+                           .collect();
+        let mut cluster = Cluster {
+            index: index,
+            tokens: tokens,
+            count: 0,
+            samples: Vec::new(),
+        };
+        cluster.count_line(line, samples);
+        cluster
+    }
+
+    // The ratio of the equal tokens among the positions where the cluster or
+    // the line has a constant token. It's 1 if there isn't any.
+    fn similarity(&self, tokens: &[&str]) -> f64 {
+        let mut constants = 0;
+        let mut equals = 0;
+        for (cluster_token, token) in self.tokens.iter().zip(tokens) {
+            match *cluster_token {
+                Token::Constant(ref constant) => {
+                    constants += 1;
+                    if constant == token {
+                        equals += 1;
+                    }
+                }
+                Token::Variable(_) if !is_variable(token) => constants += 1,
+                Token::Variable(_) => {}
+            }
+        }
+        if constants == 0 {
+            1.0
+        } else {
+            equals as f64 / constants as f64
+        }
+    }
+
+    fn add(&mut self, line: &str, tokens: &[&str], samples: usize) {
+        for (cluster_token, token) in self.tokens.iter_mut().zip(tokens) {
+            let values = match *cluster_token {
+                Token::Constant(ref constant) if constant == token => continue,
+                Token::Constant(ref constant) => {
+                    vec![constant.clone(), token.to_string()].into_iter().collect()
+                }
+                Token::Variable(ref mut values) => {
+                    values.insert(token.to_string());
+                    continue;
+                }
+            };
+            *cluster_token = Token::Variable(values);
+        }
+        self.count_line(line, samples);
+    }
+
+    fn count_line(&mut self, line: &str, samples: usize) {
+        self.count += 1;
+        if self.samples.len() < samples {
+            self.samples.push(line.to_owned());
+        }
+    }
+
+    fn suggest(&self) -> Suggestion {
+        let mut pieces = Vec::new();
+        let mut literal = String::new();
+        let mut variables = Vec::new();
+
+        for (position, token) in self.tokens.iter().enumerate() {
+            if position > 0 {
+                literal.push(TOKEN_SEPARATOR);
+            }
+            match *token {
+                Token::Constant(ref constant) => literal.push_str(constant),
+                Token::Variable(ref values) => {
+                    let variable = Variable::new(values, variables.len() + 1);
+                    literal.push_str(&variable.prefix);
+                    pieces.push((literal.clone(), variable.variable_type, variable.name.clone()));
+                    literal = variable.suffix.clone();
+                    variables.push((position, variable));
+                }
+            }
+        }
+
+        let test_messages = self.samples
+                                .iter()
+                                .map(|sample| test_message(sample, &variables))
+                                .collect();
+        let pattern = Pattern::new(None,
+                                   Uuid::new_v4(),
+                                   build_pattern(pieces, literal),
+                                   Some(test_messages),
+                                   None,
+                                   None);
+        Suggestion {
+            pattern: pattern,
+            count: self.count,
+        }
+    }
+}
+
+// Builds the pattern from the literals before the parsers and the last
+// literal. GREEDY ends at the literal after it, like in the grammar.
+fn build_pattern(pieces: Vec<(String, VariableType, String)>, last: String) -> CompiledPattern {
+    let mut builder = CompiledPatternBuilder::new();
+    let mut literals = pieces.iter().skip(1).map(|piece| &piece.0).chain(Some(&last));
+    for (literal, variable_type, name) in pieces.iter().cloned() {
+        let next_literal = literals.next().cloned().unwrap_or_default();
+        if !literal.is_empty() {
+            builder.literal(literal);
+        }
+        let parser: Box<Parser> = match variable_type {
+            VariableType::Int => Box::new(IntParser::with_name(name)),
+            VariableType::Ipv4 => Box::new(Ipv4Parser::with_name(name)),
+            VariableType::Greedy => {
+                let mut parser = GreedyParser::with_name(name);
+                if !next_literal.is_empty() {
+                    parser.set_end_string(Some(next_literal));
+                }
+                Box::new(parser)
+            }
+        };
+        builder.parser(parser);
+    }
+    if !last.is_empty() {
+        builder.literal(last);
+    }
+    builder.build()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum VariableType {
+    Int,
+    Ipv4,
+    Greedy,
+}
+
+// A variable token is a parser between the common prefix and suffix of its
+// values. They can't contain digits and they end at punctuation, so e.g.
+// `port=22` and `port=80` become `port=%{INT}`.
+struct Variable {
+    name: String,
+    prefix: String,
+    suffix: String,
+    variable_type: VariableType,
+}
+
+impl Variable {
+    fn new(values: &BTreeSet<String>, index: usize) -> Variable {
+        let (prefix, suffix) = common_affixes(values);
+        let middles = values.iter()
+                            .map(|value| &value[prefix.len()..value.len() - suffix.len()])
+                            .collect::<Vec<_>>();
+        let variable_type = if middles.iter().all(|middle| is_int(middle)) {
+            VariableType::Int
+        } else if middles.iter().all(|middle| Ipv4Addr::from_str(middle).is_ok()) {
+            VariableType::Ipv4
+        } else {
+            VariableType::Greedy
+        };
+        Variable {
+            name: format!("var{}", index),
+            prefix: prefix,
+            suffix: suffix,
+            variable_type: variable_type,
+        }
+    }
+
+    fn value<'a>(&self, token: &'a str) -> &'a str {
+        &token[self.prefix.len()..token.len() - self.suffix.len()]
+    }
+}
+
+fn test_message(line: &str, variables: &[(usize, Variable)]) -> TestMessage {
+    let tokens = line.split(TOKEN_SEPARATOR).collect::<Vec<_>>();
+    let values = variables.iter()
+                          .map(|&(position, ref variable)| {
+                              (variable.name.clone(), variable.value(tokens[position]).to_owned())
+                          })
+                          .collect::<BTreeMap<_, _>>();
+    TestMessage::new(line.to_owned(), values, None)
+}
+
+fn is_variable(token: &str) -> bool {
+    token.chars().any(|c| c.is_digit(10))
+}
+
+fn is_int(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_digit(10))
+}
+
+fn is_boundary(c: char) -> bool {
+    !c.is_alphanumeric()
+}
+
+// Returns the common prefix and suffix of the values which leave at least
+// one character of every value for the parser.
+fn common_affixes(values: &BTreeSet<String>) -> (String, String) {
+    let shortest = values.iter().map(|value| value.chars().count()).min().unwrap_or(0);
+    let first = values.iter().next().map_or("", |value| &value[..]);
+
+    let prefix = first.chars()
+                      .enumerate()
+                      .take_while(|&(index, c)| {
+                          !c.is_digit(10) &&
+                          values.iter().all(|value| value.chars().nth(index) == Some(c))
+                      })
+                      .map(|(_, c)| c)
+                      .collect::<String>();
+    let prefix = trim_to_boundary(prefix.chars(), shortest.saturating_sub(1));
+
+    let suffix = first.chars()
+                      .rev()
+                      .enumerate()
+                      .take_while(|&(index, c)| {
+                          !c.is_digit(10) &&
+                          values.iter().all(|value| value.chars().rev().nth(index) == Some(c))
+                      })
+                      .map(|(_, c)| c)
+                      .collect::<String>();
+    let suffix_length = shortest.saturating_sub(prefix.chars().count() + 1);
+    let suffix = trim_to_boundary(suffix.chars(), suffix_length).chars().rev().collect();
+
+    (prefix, suffix)
+}
+
+// Returns the longest part of `affix` (read from the parser's side) which is
+// at most `max_length` characters long and ends at a punctuation character.
+fn trim_to_boundary<I: Iterator<Item = char>>(affix: I, max_length: usize) -> String {
+    let affix = affix.take(max_length).collect::<Vec<_>>();
+    let length = affix.iter().rposition(|c| is_boundary(*c)).map_or(0, |position| position + 1);
+    affix[..length].iter().cloned().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use matcher::{FromPatternSource, MatcherFactory};
+    use matcher::pattern::file::PatternFile;
+    use matcher::suffix_array::SuffixArrayMatcherFactory;
+    use matcher::trie::factory::TrieMatcherFactory;
+    use grammar;
+    use super::Suggester;
+
+    use serde_json;
+
+    const LINES: &'static [&'static str] =
+        &["sshd[123]: Accepted publickey for alice from 10.0.0.1 port 22",
+          "sshd[456]: Accepted publickey for bob from 10.0.0.2 port 2222",
+          "crond[7]: job started",
+          "sshd[789]: Accepted publickey for carol from 192.168.1.1 port 22",
+          "crond[8]: job finished"];
+
+    fn suggest(lines: &[&str]) -> Suggester {
+        let mut suggester = Suggester::new();
+        for line in lines {
+            suggester.add_line(line);
+        }
+        suggester
+    }
+
+    #[test]
+    fn test_given_similar_lines_when_patterns_are_suggested_then_the_variable_tokens_become_typed_parsers
+        () {
+        let suggestions = suggest(LINES).suggestions();
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggestions[0].count(), 3);
+        assert_eq!(grammar::format_pattern(suggestions[0].pattern().pattern()),
+                   "sshd[%{INT:var1}]: Accepted publickey for %{GREEDY:var2} from %{IPV4:var3} \
+                    port %{INT:var4}");
+        assert_eq!(suggestions[1].count(), 2);
+        assert_eq!(grammar::format_pattern(suggestions[1].pattern().pattern()),
+                   "crond[%{INT:var1}]: job %{GREEDY:var2}");
+    }
+
+    fn assert_test_messages_match<F: MatcherFactory>(json: &str) {
+        let file = serde_json::from_str::<PatternFile>(json).unwrap();
+        assert!(F::Matcher::from_source::<F>(&mut file.into_iter()).is_ok());
+    }
+
+    #[test]
+    fn test_given_suggested_patterns_when_they_are_written_and_loaded_then_their_test_messages_match
+        () {
+        let patterns = suggest(LINES)
+                           .suggestions()
+                           .into_iter()
+                           .map(|suggestion| suggestion.into_pattern())
+                           .collect();
+        let json = PatternFile::new(patterns).to_json().unwrap();
+
+        let file = serde_json::from_str::<PatternFile>(&json).unwrap();
+        assert_eq!(file.patterns()[0].test_message_count(), 3);
+        assert_test_messages_match::<TrieMatcherFactory>(&json);
+        assert_test_messages_match::<SuffixArrayMatcherFactory>(&json);
+    }
+
+    #[test]
+    fn test_given_lines_with_different_number_of_tokens_when_patterns_are_suggested_then_they_are_not_clustered
+        () {
+        let suggestions = suggest(&["job started", "job started now"]).suggestions();
+        assert_eq!(suggestions.len(), 2);
+    }
+
+    #[test]
+    fn test_given_samples_limit_when_patterns_are_suggested_then_only_that_many_test_messages_are_added
+        () {
+        let mut suggester = suggest(&[]);
+        suggester.set_samples(1);
+        suggester.add_line("user 1 logged in");
+        suggester.add_line("user 2 logged in");
+        let suggestions = suggester.suggestions();
+        assert_eq!(suggestions[0].count(), 2);
+        assert_eq!(suggestions[0].pattern().test_messages().unwrap().len(), 1);
+    }
+}
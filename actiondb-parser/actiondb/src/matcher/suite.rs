@@ -2,8 +2,71 @@ use matcher::Matcher;
 use matcher::MatcherFactory;
 use parsers::ParserFactory;
 
+use std::fmt;
+use std::str::FromStr;
+
 pub trait MatcherSuite {
     type Matcher: Matcher;
     type ParserFactory: ParserFactory;
     type MatcherFactory: MatcherFactory<Matcher=Self::Matcher>;
 }
+
+// The matcher implementations which can be selected at runtime (e.g. in a
+// configuration file). Every kind has its own MatcherSuite.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MatcherKind {
+    Trie,
+    SuffixArray,
+}
+
+impl MatcherKind {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            MatcherKind::Trie => "trie",
+            MatcherKind::SuffixArray => "suffix-array",
+        }
+    }
+
+    pub fn names() -> &'static [&'static str] {
+        &["trie", "suffix-array"]
+    }
+}
+
+impl Default for MatcherKind {
+    fn default() -> MatcherKind {
+        MatcherKind::SuffixArray
+    }
+}
+
+impl FromStr for MatcherKind {
+    type Err = ();
+
+    fn from_str(kind: &str) -> Result<MatcherKind, ()> {
+        match kind {
+            "trie" => Ok(MatcherKind::Trie),
+            "suffix-array" => Ok(MatcherKind::SuffixArray),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for MatcherKind {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MatcherKind;
+
+    #[test]
+    fn test_given_matcher_kind_name_when_it_is_converted_to_kind_then_it_can_be_converted_back() {
+        for name in MatcherKind::names() {
+            let kind = name.parse::<MatcherKind>()
+                           .expect("Failed to convert a matcher kind name");
+            assert_eq!(kind.as_str(), *name);
+        }
+        assert_eq!("radix".parse::<MatcherKind>(), Err(()));
+    }
+}
@@ -1,6 +1,7 @@
-use matcher::Matcher;
+use matcher::{Matcher, DEFAULT_BACKTRACKING_BUDGET};
 use super::SuffixTree;
-use matcher::result::MatchResult;
+use matcher::result::{self, MatchResult};
+use matcher::explain::Explanation;
 use matcher::pattern::Pattern;
 use matcher::trie::node::interface::SuffixTree as STree;
 
@@ -8,9 +9,23 @@ impl Matcher for SuffixTree {
     fn parse<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>> {
         self.parse(text)
     }
+    fn parse_all<'a, 'b>(&'a self, text: &'b str) -> Vec<MatchResult<'a, 'b>> {
+        let mut results = Vec::new();
+        SuffixTree::parse_all(self, text, 0, DEFAULT_BACKTRACKING_BUDGET, &mut results);
+        result::rank_by_specificity(results)
+    }
+    fn explain(&self, text: &str) -> Explanation {
+        let mut explanation = Explanation::new(text);
+        let pattern = SuffixTree::explain(self, text, &mut explanation);
+        explanation.set_matched(pattern);
+        explanation
+    }
     fn add_pattern(&mut self, pattern: Pattern) {
         self.insert(pattern);
     }
+    fn sort_parsers(&mut self) {
+        self.sort_parser_children();
+    }
     fn boxed_clone(&self) -> Box<Matcher> {
         Box::new(self.clone())
     }
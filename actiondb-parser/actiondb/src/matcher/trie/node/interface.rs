@@ -16,10 +16,16 @@ pub trait Entry {
     fn set_child(&mut self, child: Option<Self::ST>);
     fn insert(&mut self, pattern: Pattern) {
         if pattern.pattern().is_empty() {
-            self.set_pattern(Some(pattern));
+            // an identical pattern is replaced unless the old one has a higher priority
+            let replace = self.pattern().map_or(true, |old| old.priority() <= pattern.priority());
+            if replace {
+                self.set_pattern(Some(pattern));
+            }
         } else {
-            let sa = Self::ST::new();
-            self.set_child(Some(sa));
+            if self.child().is_none() {
+                let sa = Self::ST::new();
+                self.set_child(Some(sa));
+            }
             self.child_mut().expect("Failed to get a child").insert(pattern);
         }
     }
@@ -1,8 +1,14 @@
-use parsers::{Parser, ParseResult};
+use parsers::{Parser, ParseResult, ParserFactory};
 use utils::{SortedVec, CommonPrefix};
 use matcher::pattern::Pattern;
+use matcher::cache::{self, Cacheable, CacheError, Encoder, Decoder};
 use matcher::result::MatchResult;
 use matcher::compiled_pattern::TokenType;
+use matcher::lint::{self, Overlap, PatternTree};
+use matcher::explain::Explanation;
+use uuid::Uuid;
+
+use std::collections::BTreeSet;
 
 mod literal;
 mod parser;
@@ -177,6 +183,96 @@ impl SuffixTree {
         }
     }
 
+    // Unlike parse(), it doesn't stop at the first matching literal child,
+    // the parser children are tried too. The parsers can be under a child
+    // with an empty literal, so it's tried besides the matching one. The
+    // results are pushed with the number of literal bytes matched in them.
+    pub fn parse_all<'a, 'b>(&'a self,
+                             text: &'b str,
+                             literal_length: usize,
+                             budget: usize,
+                             results: &mut Vec<(usize, MatchResult<'a, 'b>)>) {
+        let cmp_str = |probe: &LiteralNode| probe.cmp_str(text);
+        let mut positions = Vec::new();
+
+        if self.literal_children.get(0).map_or(false, |child| child.literal().is_empty()) {
+            positions.push(0);
+        }
+        if !text.is_empty() {
+            if let Ok(pos) = self.literal_children.binary_search_by(&cmp_str) {
+                positions.push(pos);
+            }
+        }
+        for pos in positions {
+            let child = self.literal_children.get(pos).expect("Failed to get a literal child");
+
+            if text.starts_with(child.literal()) {
+                let remaining = text.ltrunc(child.literal().len());
+                let literal_length = literal_length + child.literal().len();
+
+                if remaining.is_empty() {
+                    if let Some(pattern) = child.pattern() {
+                        results.push((literal_length, MatchResult::new(pattern)));
+                    }
+                }
+                if let Some(node) = child.node() {
+                    node.parse_all(remaining, literal_length, budget, results);
+                }
+            }
+        }
+        for child in &self.parser_children {
+            child.parse_all(text, literal_length, budget, results);
+        }
+    }
+
+    // It walks the same path as parse() and records it.
+    pub fn explain<'a>(&'a self, text: &str, explanation: &mut Explanation) -> Option<&'a Pattern> {
+        explanation.reach(text, |patterns| self.collect_patterns(patterns));
+
+        match self.search(text) {
+            LiteralLookupResult::Found(pos) => {
+                let child = self.literal_children
+                                .get(pos)
+                                .expect("Failed to get a looked up child");
+                explanation.push_literal(text, child.literal(), child.literal().len());
+                child.pattern()
+            }
+            LiteralLookupResult::GoDown(pos, remaining) => {
+                let child = self.literal_children
+                                .get(pos)
+                                .expect("Failed to get a looked up child");
+                explanation.push_literal(text, child.literal(), child.literal().len());
+                child.node().and_then(|node| node.explain(remaining, explanation))
+            }
+            LiteralLookupResult::NotFound => {
+                let cmp_str = |probe: &LiteralNode| probe.cmp_str(text);
+                if let Ok(pos) = self.literal_children.binary_search_by(&cmp_str) {
+                    let child = self.literal_children.get(pos).expect("Failed to get a child");
+                    let matched = child.literal().common_prefix_len(text);
+                    explanation.push_literal(text, child.literal(), matched);
+                    explanation.reach(text.ltrunc(matched),
+                                      |patterns| collect_entry_patterns(child, patterns));
+                }
+                for child in &self.parser_children {
+                    if let Some(pattern) = child.explain(text, explanation) {
+                        return Some(pattern);
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    pub fn collect_patterns<'a>(&'a self, patterns: &mut Vec<&'a Pattern>) {
+        for i in 0..self.literal_children.len() {
+            let child = self.literal_children.get(i).expect("Failed to get a literal child");
+            collect_entry_patterns(child, patterns);
+        }
+        for child in &self.parser_children {
+            collect_entry_patterns(child, patterns);
+        }
+    }
+
     fn create_match_result_if_child_is_leaf<'a, 'b>(child: &'a LiteralNode)
                                                     -> Option<MatchResult<'a, 'b>> {
         if let Some(pattern) = child.pattern() {
@@ -274,6 +370,30 @@ impl SuffixTree {
         }
     }
 
+    // The parser children are tried in order, so the ones leading to
+    // patterns with higher priority and then the more specific parsers are
+    // moved forward. Otherwise the insertion order is kept. It's done once
+    // for the whole tree after the patterns are inserted.
+    pub fn sort_parser_children(&mut self) {
+        self.parser_children.sort_by(|first, second| {
+            (second.priority(), second.parser().specificity())
+                .cmp(&(first.priority(), first.parser().specificity()))
+        });
+        for index in 0..self.literal_children.len() {
+            let child = self.literal_children
+                            .get_mut(index)
+                            .expect("Failed to get a literal child");
+            if let Some(node) = child.child_mut() {
+                node.sort_parser_children();
+            }
+        }
+        for child in &mut self.parser_children {
+            if let Some(node) = child.child_mut() {
+                node.sort_parser_children();
+            }
+        }
+    }
+
     pub fn insert_parser(&mut self, parser: Box<Parser>) -> &mut ParserNode {
         if let Some(item) = self.lookup_parser(&*parser) {
             self.parser_children.get_mut(item).unwrap()
@@ -285,6 +405,129 @@ impl SuffixTree {
     }
 }
 
+pub fn collect_entry_patterns<'a, E>(entry: &'a E, patterns: &mut Vec<&'a Pattern>)
+    where E: Entry<ST=SuffixTree>
+{
+    if let Some(pattern) = entry.pattern() {
+        patterns.push(pattern);
+    }
+    if let Some(child) = entry.child() {
+        child.collect_patterns(patterns);
+    }
+}
+
+fn collect_entry_reachable<E>(entry: &E) -> BTreeSet<Uuid>
+    where E: Entry<ST=SuffixTree>
+{
+    let mut uuids = entry.child().map_or_else(BTreeSet::new, |child| child.collect_reachable());
+    if let Some(pattern) = entry.pattern() {
+        uuids.insert(pattern.uuid().clone());
+    }
+    uuids
+}
+
+impl PatternTree for SuffixTree {
+    fn collect_reachable(&self) -> BTreeSet<Uuid> {
+        let mut uuids = BTreeSet::new();
+
+        for i in 0..self.literal_children.len() {
+            let child = self.literal_children.get(i).expect("Failed to get a literal child");
+            uuids.extend(collect_entry_reachable(child));
+        }
+        for child in &self.parser_children {
+            uuids.extend(collect_entry_reachable(child));
+        }
+        uuids
+    }
+
+    fn collect_overlaps(&self, overlaps: &mut Vec<Overlap>) {
+        let mut literals = Vec::new();
+        for i in 0..self.literal_children.len() {
+            let child = self.literal_children.get(i).expect("Failed to get a literal child");
+            literals.push((child.literal(), collect_entry_reachable(child)));
+        }
+        let parsers = self.parser_children
+                          .iter()
+                          .map(|child| (child.parser(), collect_entry_reachable(child)))
+                          .collect::<Vec<_>>();
+        lint::find_overlaps(&literals, &parsers, overlaps);
+
+        for i in 0..self.literal_children.len() {
+            let child = self.literal_children.get(i).expect("Failed to get a literal child");
+            if let Some(node) = child.child() {
+                node.collect_overlaps(overlaps);
+            }
+        }
+        for child in &self.parser_children {
+            if let Some(node) = child.child() {
+                node.collect_overlaps(overlaps);
+            }
+        }
+    }
+}
+
+fn encode_entry<E>(entry: &E, encoder: &mut Encoder)
+    where E: Entry<ST=SuffixTree>
+{
+    encoder.write_option(entry.pattern(), cache::encode_pattern);
+    encoder.write_option(entry.child(), |encoder, child| child.encode(encoder));
+}
+
+fn decode_entry<E, F>(entry: &mut E, decoder: &mut Decoder) -> Result<(), CacheError>
+    where E: Entry<ST=SuffixTree>,
+          F: ParserFactory
+{
+    entry.set_pattern(try!(decoder.read_option(cache::decode_pattern)));
+    entry.set_child(try!(decoder.read_option(|decoder| SuffixTree::decode::<F>(decoder))));
+    Ok(())
+}
+
+impl Cacheable for SuffixTree {
+    fn cache_name() -> &'static str {
+        "trie"
+    }
+
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.write_usize(self.literal_children.len());
+        for i in 0..self.literal_children.len() {
+            let child = self.literal_children.get(i).expect("Failed to get a literal child");
+            encoder.write_str(child.literal());
+            encoder.write_bool(child.has_value());
+            encode_entry(child, encoder);
+        }
+
+        encoder.write_usize(self.parser_children.len());
+        for child in &self.parser_children {
+            cache::encode_parser(encoder, child.parser());
+            encoder.write_i32(child.priority());
+            encode_entry(child, encoder);
+        }
+    }
+
+    fn decode<F: ParserFactory>(decoder: &mut Decoder) -> Result<SuffixTree, CacheError> {
+        let mut literal_children = Vec::new();
+        for _ in 0..try!(decoder.read_usize()) {
+            let mut child = LiteralNode::new(try!(decoder.read_string()));
+            child.set_has_value(try!(decoder.read_bool()));
+            try!(decode_entry::<_, F>(&mut child, decoder));
+            literal_children.push(child);
+        }
+
+        let mut parser_children = Vec::new();
+        for _ in 0..try!(decoder.read_usize()) {
+            let mut child = ParserNode::new(try!(cache::decode_parser::<F>(decoder)));
+            child.raise_priority(try!(decoder.read_i32()));
+            try!(decode_entry::<_, F>(&mut child, decoder));
+            parser_children.push(child);
+        }
+
+        Ok(SuffixTree {
+            literal_children: SortedVec::from_vec(literal_children),
+            parser_children: parser_children,
+        })
+    }
+}
+
 impl Default for SuffixTree {
     fn default() -> Self {
         SuffixTree {
@@ -302,16 +545,37 @@ impl self::interface::SuffixTree for SuffixTree {
         }
     }
     fn insert(&mut self, mut pattern: Pattern) {
+        if pattern.has_alternatives() {
+            match pattern.expand_alternatives() {
+                Ok(variants) => {
+                    for variant in variants {
+                        self.insert(variant);
+                    }
+                }
+                Err(error) => {
+                    error!("Failed to insert pattern: uuid={} error={}",
+                           pattern.uuid().hyphenated(),
+                           error)
+                }
+            }
+            return;
+        }
+
         if let Some(token) = pattern.pop_first_token() {
-            let mut entry: &mut Entry<ST = Self> = match token {
+            match token {
                 TokenType::Literal(literal) => {
-                    self.insert_literal(&literal)
+                    self.insert_literal(&literal).insert(pattern);
                 }
                 TokenType::Parser(parser) => {
-                    self.insert_parser(parser)
+                    let priority = pattern.priority();
+                    let node = self.insert_parser(parser);
+                    node.raise_priority(priority);
+                    node.insert(pattern);
+                }
+                TokenType::Alternation(_) | TokenType::Optional(_) => {
+                    unreachable!("groups are expanded before insertion")
                 }
-            };
-            entry.insert(pattern);
+            }
         }
     }
 
@@ -324,9 +588,11 @@ mod test {
     use matcher::compiled_pattern::CompiledPatternBuilder;
     use matcher::pattern::Pattern;
     use matcher::trie::node::interface::SuffixTree as STree;
+    use matcher::DEFAULT_BACKTRACKING_BUDGET;
 
     use std::iter::FromIterator;
     use std::collections::BTreeMap;
+    use std::borrow::Cow;
 
     #[test]
     fn given_empty_trie_when_literals_are_inserted_then_they_can_be_looked_up() {
@@ -507,7 +773,10 @@ mod test {
         println!("root: {:?}", &root);
         {
             let result = root.parse("app42letree123");
-            let expected = BTreeMap::from_iter(vec![("end", "123"), ("middle", "42")].into_iter());
+            let expected: BTreeMap<Cow<str>, Cow<str>> =
+                BTreeMap::from_iter(vec![("end".into(), "123".into()),
+                                         ("middle".into(), "42".into())]
+                                        .into_iter());
             assert_eq!(result.unwrap().values(), &expected);
         }
     }
@@ -607,7 +876,9 @@ mod test {
 
         trie.insert(pattern);
         println!("{:?}", &trie);
-        let expected = BTreeMap::from_iter(vec![("test", "23")].into_iter());
+        let expected: BTreeMap<Cow<str>, Cow<str>> =
+            BTreeMap::from_iter(vec![("test".into(), "23".into())]
+                                    .into_iter());
         match trie.parse("app23le") {
             Some(res) => {
                 println!("{:?}", res);
@@ -621,7 +892,11 @@ mod test {
     fn test_given_pattern_with_two_neighbouring_parser_when_the_pattern_is_inserted_into_the_trie_then_everything_is_ok
         () {
         let mut trie = SuffixTree::new();
-        let expected = BTreeMap::from_iter(vec![("test", "ccc"), ("test2", "12"), ("test3", "le")].into_iter());
+        let expected: BTreeMap<Cow<str>, Cow<str>> =
+            BTreeMap::from_iter(vec![("test".into(), "ccc".into()),
+                                     ("test2".into(), "12".into()),
+                                     ("test3".into(), "le".into())]
+                                    .into_iter());
         let cp_1 = CompiledPatternBuilder::new()
                       .literal("app")
                       .parser(Box::new(SetParser::from_str("test", "abcd")))
@@ -643,4 +918,94 @@ mod test {
             None => unreachable!(),
         }
     }
+    #[test]
+    fn test_given_patterns_with_common_prefix_when_they_differ_only_in_the_address_parser_then_both_can_be_matched
+        () {
+        let mut trie = SuffixTree::new();
+        let mut ipv4_pattern = Pattern::with_random_uuid();
+        ipv4_pattern.set_pattern(::grammar::parser::pattern("from %{IPV4:ip} port").unwrap());
+        let ipv4_uuid = ipv4_pattern.uuid().clone();
+        let mut ipv6_pattern = Pattern::with_random_uuid();
+        ipv6_pattern.set_pattern(::grammar::parser::pattern("from %{IPV6:ip} port").unwrap());
+        let ipv6_uuid = ipv6_pattern.uuid().clone();
+        trie.insert(ipv4_pattern);
+        trie.insert(ipv6_pattern);
+
+        assert_eq!(trie.parse("from 127.0.0.1 port").unwrap().pattern().uuid(), &ipv4_uuid);
+        assert_eq!(trie.parse("from ::1 port").unwrap().pattern().uuid(), &ipv6_uuid);
+        assert_eq!(trie.parse("from localhost port").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_pattern_with_groups_when_it_is_inserted_into_the_trie_then_all_of_its_variants_are_matched
+        () {
+        let mut trie = SuffixTree::new();
+        let mut pattern = Pattern::with_random_uuid();
+        let compiled_pattern = "Accepted %{(publickey|password)} for %{INT:user}%{( [preauth])?}";
+        pattern.set_pattern(::grammar::parser::pattern(compiled_pattern).unwrap());
+        let uuid = pattern.uuid().clone();
+        trie.insert(pattern);
+
+        for message in &["Accepted publickey for 42",
+                         "Accepted password for 42",
+                         "Accepted publickey for 42 [preauth]",
+                         "Accepted password for 42 [preauth]"] {
+            let result = trie.parse(message).expect("Failed to parse a variant of the pattern");
+            assert_eq!(&uuid, result.pattern().uuid());
+            assert_eq!(Some(&"42".into()), result.values().get("user"));
+        }
+        assert_eq!(trie.parse("Accepted keyboard-interactive for 42").is_none(), true);
+        assert_eq!(trie.parse("Accepted password for 42 [post]").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_trie_when_every_match_is_requested_then_the_parsers_are_tried_after_the_literals
+        () {
+        let mut root = SuffixTree::new();
+        let patterns = ["user %{GREEDY:user} logged in",
+                        "user %{INT:uid} logged in",
+                        "user 42 logged in"];
+        let mut uuids = Vec::new();
+        for pattern in &patterns {
+            let mut pattern_1 = Pattern::with_random_uuid();
+            pattern_1.set_pattern(::grammar::parser::pattern(pattern).unwrap());
+            uuids.push(pattern_1.uuid().clone());
+            root.insert(pattern_1);
+        }
+
+        let result = root.parse("user 42 logged in").expect("Failed to parse a message");
+        assert_eq!(&uuids[2], result.pattern().uuid());
+
+        let mut results = Vec::new();
+        root.parse_all("user 42 logged in", 0, DEFAULT_BACKTRACKING_BUDGET, &mut results);
+        let mut specificities = results.iter()
+                                       .map(|&(literal_length, _)| literal_length)
+                                       .collect::<Vec<usize>>();
+        specificities.sort();
+        assert_eq!(vec![15, 15, 17], specificities);
+    }
+
+    #[test]
+    fn test_given_trie_when_parsers_overlap_then_the_more_specific_or_the_higher_priority_one_wins
+        () {
+        let mut greedy = Pattern::with_random_uuid();
+        greedy.set_pattern(::grammar::parser::pattern("user %{GREEDY:user} logged in").unwrap());
+        let mut int = Pattern::with_random_uuid();
+        int.set_pattern(::grammar::parser::pattern("user %{INT:uid} logged in").unwrap());
+
+        let mut root = SuffixTree::new();
+        root.insert(greedy.clone());
+        root.insert(int.clone());
+        root.sort_parser_children();
+        let result = root.parse("user 42 logged in").expect("Failed to parse a message");
+        assert_eq!(int.uuid(), result.pattern().uuid());
+
+        greedy.set_priority(10);
+        let mut root = SuffixTree::new();
+        root.insert(int.clone());
+        root.insert(greedy.clone());
+        root.sort_parser_children();
+        let result = root.parse("user 42 logged in").expect("Failed to parse a message");
+        assert_eq!(greedy.uuid(), result.pattern().uuid());
+    }
 }
@@ -1,15 +1,20 @@
-use matcher::trie::node::SuffixTree;
+use matcher::trie::node::{SuffixTree, collect_entry_patterns};
+use matcher::explain::Explanation;
 use matcher::trie::node::interface::{Entry, ParserEntry};
 use matcher::result::MatchResult;
 use matcher::Pattern;
 use parsers::{Parser, ParseResult};
 use utils::CommonPrefix;
 
+use std::cmp;
+
 #[derive(Debug)]
 pub struct ParserNode {
     parser: Box<Parser>,
     pattern: Option<Pattern>,
     node: Option<SuffixTree>,
+    // the highest priority of the patterns under this node
+    priority: i32,
 }
 
 impl ParserNode {
@@ -18,9 +23,18 @@ impl ParserNode {
             parser: parser,
             pattern: None,
             node: None,
+            priority: i32::min_value(),
         }
     }
 
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    pub fn raise_priority(&mut self, priority: i32) {
+        self.priority = cmp::max(self.priority, priority);
+    }
+
     pub fn parser(&self) -> &Parser {
         &*self.parser
     }
@@ -36,13 +50,15 @@ impl ParserNode {
     pub fn parse<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>> {
         if let Some(parsed_kwpair) = self.parser.parse(text) {
             trace!("parse(): parsed_kwpair = {:?}", &parsed_kwpair);
-            let text = text.ltrunc(parsed_kwpair.value().len());
+            let text = text.ltrunc(parsed_kwpair.match_length());
 
+            // A pattern can end in a node which has children (e.g. with an
+            // optional group at the end of the pattern)
             return match self.node() {
-                Some(node) => {
+                Some(node) if !text.is_empty() || self.pattern.is_none() => {
                     node.parse_then_push_kvpair(text, parsed_kwpair)
                 }
-                None => {
+                _ => {
                     self.push_last_kvpair(text, parsed_kwpair)
                 }
             };
@@ -50,6 +66,59 @@ impl ParserNode {
         None
     }
 
+    // The alternative matches of the parser are tried too, each of them
+    // continues with the budget left on this path.
+    pub fn parse_all<'a, 'b>(&'a self,
+                             text: &'b str,
+                             literal_length: usize,
+                             mut budget: usize,
+                             results: &mut Vec<(usize, MatchResult<'a, 'b>)>) {
+        let mut kvpair = self.parser.parse(text);
+
+        while let Some(result) = kvpair {
+            let match_length = result.match_length();
+            let remaining = text.ltrunc(match_length);
+            let first = results.len();
+
+            if remaining.is_empty() {
+                if let Some(pattern) = self.pattern() {
+                    results.push((literal_length, MatchResult::new(pattern)));
+                }
+            }
+            if let Some(node) = self.node() {
+                node.parse_all(remaining, literal_length, budget, results);
+            }
+            for &mut (_, ref mut match_result) in &mut results[first..] {
+                match_result.insert(result.clone());
+            }
+            if budget == 0 {
+                debug!("The backtracking budget is exhausted");
+                return;
+            }
+            budget -= 1;
+            kvpair = self.parser.parse_next(text, match_length);
+        }
+    }
+
+    // It walks the same path as parse() and records it.
+    pub fn explain<'a>(&'a self, text: &str, explanation: &mut Explanation) -> Option<&'a Pattern> {
+        let match_length = self.parser.parse(text).map(|kvpair| kvpair.match_length());
+        explanation.push_parser(text, self.parser(), match_length);
+
+        match_length.and_then(|match_length| {
+            let text = text.ltrunc(match_length);
+            explanation.reach(text, |patterns| collect_entry_patterns(self, patterns));
+
+            match self.node() {
+                Some(node) if !text.is_empty() || self.pattern.is_none() => {
+                    node.explain(text, explanation)
+                }
+                _ if text.is_empty() => self.pattern(),
+                _ => None,
+            }
+        })
+    }
+
     fn push_last_kvpair<'a, 'b>(&'a self,
                                 text: &'b str,
                                 kvpair: ParseResult<'a, 'b>)
@@ -87,13 +156,15 @@ impl ParserEntry for ParserNode {
     fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>> {
         if let Some(parsed_kwpair) = self.parser.parse(value) {
             trace!("parse(): parsed_kwpair = {:?}", &parsed_kwpair);
-            let text = value.ltrunc(parsed_kwpair.value().len());
+            let text = value.ltrunc(parsed_kwpair.match_length());
 
+            // A pattern can end in a node which has children (e.g. with an
+            // optional group at the end of the pattern)
             return match self.node() {
-                Some(node) => {
+                Some(node) if !text.is_empty() || self.pattern.is_none() => {
                     node.parse_then_push_kvpair(text, parsed_kwpair)
                 }
-                None => {
+                _ => {
                     self.push_last_kvpair(text, parsed_kwpair)
                 }
             };
@@ -111,6 +182,7 @@ impl Clone for ParserNode {
             parser: self.parser.boxed_clone(),
             pattern: self.pattern.clone(),
             node: self.node.clone(),
+            priority: self.priority,
         }
     }
 }
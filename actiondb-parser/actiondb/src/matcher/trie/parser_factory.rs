@@ -1,5 +1,8 @@
 use parsers::{GreedyParser, IntParser, OptionalParameter, Parser, ParserFactory, SetParser,
-              HasLengthConstraint};
+              HasLengthConstraint, Ipv4Parser, Ipv6Parser, IpAddrParser, NumberParser,
+              HasValueConstraint, QStringParser, TimestampParser, RegexParser,
+              KvParser};
+use regex;
 
 macro_rules! set_optinal_param {
     ($parser:expr, $param:expr) => {
@@ -15,6 +18,26 @@ macro_rules! set_optinal_param {
                     _ => ()
                 }
             }
+            _ => ()
+        }
+    }
+}
+
+macro_rules! set_optional_value_param {
+    ($parser:expr, $param:expr) => {
+        match $param {
+            OptionalParameter::Float(key, value) => {
+                match key {
+                    "min" => {
+                        $parser.set_min_value(Some(value));
+                    },
+                    "max" => {
+                        $parser.set_max_value(Some(value));
+                    },
+                    _ => ()
+                }
+            }
+            _ => ()
         }
     }
 }
@@ -29,6 +52,16 @@ macro_rules! set_optional_params {
     }
 }
 
+macro_rules! set_optional_value_params {
+    ($parser:expr, $opt_params:expr) => {
+        if let Some(opt_params) = $opt_params {
+            for i in opt_params.into_iter() {
+                set_optional_value_param!($parser, i);
+            }
+        }
+    }
+}
+
 pub struct TrieParserFactory;
 
 impl ParserFactory for TrieParserFactory {
@@ -59,4 +92,101 @@ impl ParserFactory for TrieParserFactory {
         parser.set_name(name);
         Box::new(parser)
     }
+    fn new_number(name: Option<&str>,
+                  opt_params: Option<Vec<OptionalParameter>>)
+                  -> Box<Parser> {
+        let mut parser = NumberParser::new();
+        set_optional_value_params!(&mut parser, opt_params);
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_qstring<'a>(quotes: &str,
+                       name: Option<&str>,
+                       opt_params: Option<Vec<OptionalParameter<'a>>>)
+                       -> Box<Parser> {
+        let mut parser = QStringParser::new(quotes);
+        if let Some(opt_params) = opt_params {
+            for param in opt_params {
+                match param {
+                    OptionalParameter::Str("escape", escape) => {
+                        parser.set_escape_character(escape.chars().next());
+                    }
+                    OptionalParameter::Bool("unescape", unescape) => {
+                        parser.set_unescape(unescape);
+                    }
+                    _ => (),
+                }
+            }
+        }
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_timestamp<'a>(name: Option<&str>,
+                         opt_params: Option<Vec<OptionalParameter<'a>>>)
+                         -> Box<Parser> {
+        let mut parser = TimestampParser::default();
+        if let Some(opt_params) = opt_params {
+            for param in opt_params {
+                match param {
+                    OptionalParameter::Str("format", format) => {
+                        parser.set_format(&format);
+                    }
+                    OptionalParameter::Bool("normalize", normalize) => {
+                        parser.set_normalize(normalize);
+                    }
+                    _ => (),
+                }
+            }
+        }
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_regex(regex: &str, name: Option<&str>) -> Result<Box<Parser>, regex::Error> {
+        let mut parser = try!(RegexParser::new(regex));
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Ok(Box::new(parser))
+    }
+    fn new_kv<'a>(name: Option<&str>,
+                  opt_params: Option<Vec<OptionalParameter<'a>>>)
+                  -> Box<Parser> {
+        let mut parser = KvParser::new();
+        if let Some(opt_params) = opt_params {
+            for param in opt_params {
+                match param {
+                    OptionalParameter::Str("pair_separator", separator) => {
+                        parser.set_pair_separator(&separator);
+                    }
+                    OptionalParameter::Str("value_separator", separator) => {
+                        parser.set_value_separator(&separator);
+                    }
+                    _ => (),
+                }
+            }
+        }
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_ipv4(name: Option<&str>) -> Box<Parser> {
+        let mut parser = Ipv4Parser::new();
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_ipv6(name: Option<&str>) -> Box<Parser> {
+        let mut parser = Ipv6Parser::new();
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
+    fn new_ipaddr(name: Option<&str>) -> Box<Parser> {
+        let mut parser = IpAddrParser::new();
+        let name = name.map(|name| name.to_owned());
+        parser.set_name(name);
+        Box::new(parser)
+    }
 }
@@ -1,5 +1,5 @@
 use std::hash::{SipHasher, Hash, Hasher};
-use super::{ParserBase, Parser, ObjectSafeHash, ParseResult};
+use super::{ParserBase, Parser, ObjectSafeHash, ParseResult, OptionalParameter};
 
 #[derive(Clone, Debug, Hash)]
 pub struct GreedyParser {
@@ -58,6 +58,29 @@ impl Parser for GreedyParser {
         }
     }
 
+    // The alternatives end at the next occurrences of the end string.
+    fn parse_next<'a, 'b>(&'a self,
+                          value: &'b str,
+                          previous_match_length: usize)
+                          -> Option<ParseResult<'a, 'b>> {
+        self.end_string.as_ref().and_then(|end_string| {
+            value[previous_match_length..].chars().next().and_then(|c| {
+                let begin = previous_match_length + c.len_utf8();
+                value[begin..]
+                    .find(end_string)
+                    .map(|pos| ParseResult::new(self, &value[..begin + pos]))
+            })
+        })
+    }
+
+    fn kind(&self) -> &'static str {
+        "GREEDY"
+    }
+
+    fn specificity(&self) -> u8 {
+        0
+    }
+
     fn name(&self) -> Option<&str> {
         self.base.name()
     }
@@ -69,6 +92,13 @@ impl Parser for GreedyParser {
     fn boxed_clone(&self) -> Box<Parser> {
         Box::new(self.clone())
     }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        self.end_string
+            .iter()
+            .map(|end_string| OptionalParameter::Str("end_string", end_string.clone()))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -90,4 +120,17 @@ mod test {
         assert_eq!(res.parser().name(), Some("name"));
         assert_eq!(res.value(), "qux ");
     }
+
+    #[test]
+    fn test_given_greedy_parser_when_the_next_match_is_requested_then_it_ends_at_the_next_end_string
+        () {
+        let parser = GreedyParser::from_str("name", " - ");
+        let value = "a - b - c";
+        let first = parser.parse(value).unwrap();
+        assert_eq!(first.value(), "a");
+        let second = parser.parse_next(value, first.match_length()).unwrap();
+        assert_eq!(second.value(), "a - b");
+        assert_eq!(parser.parse_next(value, second.match_length()).is_none(), true);
+        assert_eq!(GreedyParser::new().parse_next(value, value.len()).is_none(), true);
+    }
 }
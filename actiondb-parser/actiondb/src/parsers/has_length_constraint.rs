@@ -1,9 +1,22 @@
+use parsers::OptionalParameter;
+
 pub trait HasLengthConstraint {
     fn min_length(&self) -> Option<usize>;
     fn set_min_length(&mut self, length: Option<usize>);
     fn max_length(&self) -> Option<usize>;
     fn set_max_length(&mut self, length: Option<usize>);
 
+    fn length_parameters(&self) -> Vec<OptionalParameter<'static>> {
+        let mut parameters = Vec::new();
+        if let Some(length) = self.min_length() {
+            parameters.push(OptionalParameter::Int("min_len", length));
+        }
+        if let Some(length) = self.max_length() {
+            parameters.push(OptionalParameter::Int("max_len", length));
+        }
+        parameters
+    }
+
     fn is_match_length_ok(&self, match_length: usize) -> bool {
         match_length > 0 && self.is_min_length_ok(match_length) &&
         self.is_max_length_ok(match_length)
@@ -0,0 +1,37 @@
+use parsers::OptionalParameter;
+
+pub trait HasValueConstraint {
+    fn min_value(&self) -> Option<f64>;
+    fn set_min_value(&mut self, value: Option<f64>);
+    fn max_value(&self) -> Option<f64>;
+    fn set_max_value(&mut self, value: Option<f64>);
+
+    fn value_parameters(&self) -> Vec<OptionalParameter<'static>> {
+        let mut parameters = Vec::new();
+        if let Some(value) = self.min_value() {
+            parameters.push(OptionalParameter::Float("min", value));
+        }
+        if let Some(value) = self.max_value() {
+            parameters.push(OptionalParameter::Float("max", value));
+        }
+        parameters
+    }
+
+    fn is_value_ok(&self, value: f64) -> bool {
+        self.is_min_value_ok(value) && self.is_max_value_ok(value)
+    }
+
+    fn is_min_value_ok(&self, value: f64) -> bool {
+        match self.min_value() {
+            Some(x) => value >= x,
+            None => true,
+        }
+    }
+
+    fn is_max_value_ok(&self, value: f64) -> bool {
+        match self.max_value() {
+            Some(x) => value <= x,
+            None => true,
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use std::hash::{SipHasher, Hash, Hasher};
 
-use parsers::{Parser, ObjectSafeHash, SetParser, ParseResult, HasLengthConstraint};
+use parsers::{Parser, ObjectSafeHash, SetParser, ParseResult, HasLengthConstraint,
+              OptionalParameter};
+use parsers::ValueType;
 
 #[derive(Clone, Debug, Hash)]
 pub struct IntParser {
@@ -9,7 +11,7 @@ pub struct IntParser {
 
 impl IntParser {
     pub fn with_name<S: Into<String>>(name: S) -> IntParser {
-        let delegate = SetParser::with_name(name.into(), "0123456789");
+        let delegate = SetParser::with_name(name.into(), "0-9");
         IntParser { delegate: delegate }
     }
 
@@ -20,7 +22,30 @@ impl IntParser {
 
 impl Parser for IntParser {
     fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
-        self.delegate.parse(value)
+        // The result has to refer to this parser, otherwise its value type
+        // would be the delegate's one
+        self.delegate.parse(value).map(|result| {
+            let match_length = result.match_length();
+            ParseResult::with_match_length(self, result.into_values().0, match_length)
+        })
+    }
+
+    fn parse_next<'a, 'b>(&'a self,
+                          value: &'b str,
+                          previous_match_length: usize)
+                          -> Option<ParseResult<'a, 'b>> {
+        self.delegate.parse_next(value, previous_match_length).map(|result| {
+            let match_length = result.match_length();
+            ParseResult::with_match_length(self, result.into_values().0, match_length)
+        })
+    }
+
+    fn kind(&self) -> &'static str {
+        "INT"
+    }
+
+    fn specificity(&self) -> u8 {
+        4
     }
 
     fn name(&self) -> Option<&str> {
@@ -34,11 +59,19 @@ impl Parser for IntParser {
     fn boxed_clone(&self) -> Box<Parser> {
         Box::new(self.clone())
     }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        self.length_parameters()
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::Int
+    }
 }
 
 impl Default for IntParser {
     fn default() -> Self {
-        IntParser { delegate: SetParser::new("0123456789") }
+        IntParser { delegate: SetParser::new("0-9") }
     }
 }
 
@@ -68,7 +101,7 @@ impl ObjectSafeHash for IntParser {
 
 #[cfg(test)]
 mod test {
-    use parsers::{IntParser, Parser, HasLengthConstraint};
+    use parsers::{IntParser, Parser, HasLengthConstraint, ValueType};
 
     #[test]
     fn test_given_int_parser_when_the_match_is_empty_then_the_result_isnt_successful() {
@@ -84,6 +117,7 @@ mod test {
         let res = parser.parse("1234asd").unwrap();
         assert_eq!(res.parser().name(), Some(parser_name));
         assert_eq!(res.value(), "1234");
+        assert_eq!(res.parser().value_type(), ValueType::Int);
     }
 
     #[test]
@@ -0,0 +1,100 @@
+use std::cmp;
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{Parser, ObjectSafeHash, ParseResult, ParserBase, Ipv4Parser, Ipv6Parser};
+use parsers::ValueType;
+
+#[derive(Clone, Debug, Hash)]
+pub struct IpAddrParser {
+    base: ParserBase,
+}
+
+impl IpAddrParser {
+    pub fn with_name<S: Into<String>>(name: S) -> IpAddrParser {
+        IpAddrParser { base: ParserBase::with_name(name.into()) }
+    }
+
+    pub fn new() -> IpAddrParser {
+        IpAddrParser::default()
+    }
+
+    fn calculate_match_length(value: &str) -> Option<usize> {
+        let ipv4_len = Ipv4Parser::calculate_match_length(value);
+        let ipv6_len = Ipv6Parser::calculate_match_length(value);
+
+        match (ipv4_len, ipv6_len) {
+            (Some(ipv4_len), Some(ipv6_len)) => Some(cmp::max(ipv4_len, ipv6_len)),
+            (Some(len), None) | (None, Some(len)) => Some(len),
+            (None, None) => None,
+        }
+    }
+}
+
+impl Default for IpAddrParser {
+    fn default() -> Self {
+        IpAddrParser { base: ParserBase::new() }
+    }
+}
+
+impl Parser for IpAddrParser {
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+        IpAddrParser::calculate_match_length(value).map(|len| ParseResult::new(self, &value[..len]))
+    }
+
+    fn kind(&self) -> &'static str {
+        "IPADDR"
+    }
+
+    fn specificity(&self) -> u8 {
+        4
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::Ip
+    }
+}
+
+impl ObjectSafeHash for IpAddrParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:ipaddr".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{IpAddrParser, Parser};
+
+    #[test]
+    fn test_given_ipaddr_parser_when_the_value_starts_with_an_ipv4_or_ipv6_address_then_it_matches
+        () {
+        let parser = IpAddrParser::with_name("ip");
+        let res = parser.parse("192.168.1.1: foo").unwrap();
+        assert_eq!(res.parser().name(), Some("ip"));
+        assert_eq!(res.value(), "192.168.1.1");
+        assert_eq!(parser.parse("2001:db8::1 foo").unwrap().value(), "2001:db8::1");
+        assert_eq!(parser.parse("::ffff:10.0.0.1 foo").unwrap().value(),
+                   "::ffff:10.0.0.1");
+    }
+
+    #[test]
+    fn test_given_ipaddr_parser_when_the_value_is_not_an_address_then_it_does_not_match() {
+        let parser = IpAddrParser::new();
+        assert_eq!(parser.parse("localhost").is_none(), true);
+        assert_eq!(parser.parse("").is_none(), true);
+    }
+}
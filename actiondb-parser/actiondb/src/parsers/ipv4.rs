@@ -0,0 +1,162 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{Parser, ObjectSafeHash, ParseResult, ParserBase, ValueType};
+
+const OCTET_COUNT: usize = 4;
+const MAX_OCTET_LENGTH: usize = 3;
+
+#[derive(Clone, Debug, Hash)]
+pub struct Ipv4Parser {
+    base: ParserBase,
+}
+
+impl Ipv4Parser {
+    pub fn with_name<S: Into<String>>(name: S) -> Ipv4Parser {
+        Ipv4Parser { base: ParserBase::with_name(name.into()) }
+    }
+
+    pub fn new() -> Ipv4Parser {
+        Ipv4Parser::default()
+    }
+
+    // Returns the length of the longest prefix of `value` which is a valid
+    // dotted-decimal IPv4 address.
+    pub fn calculate_match_length(value: &str) -> Option<usize> {
+        let bytes = value.as_bytes();
+        let mut pos = 0;
+
+        for i in 0..OCTET_COUNT {
+            if i > 0 {
+                if bytes.get(pos) != Some(&b'.') {
+                    return None;
+                }
+                pos += 1;
+            }
+            match Ipv4Parser::calculate_octet_length(&bytes[pos..]) {
+                Some(len) => pos += len,
+                None => return None,
+            }
+        }
+
+        Some(pos)
+    }
+
+    // An octet is a decimal number without leading zeros. It has to end at a
+    // non-digit, so a longer run of digits doesn't match partially.
+    fn calculate_octet_length(bytes: &[u8]) -> Option<usize> {
+        let mut value: u32 = 0;
+        let mut len = 0;
+
+        for c in bytes {
+            if *c >= b'0' && *c <= b'9' {
+                if len == MAX_OCTET_LENGTH {
+                    return None;
+                }
+                value = value * 10 + (*c - b'0') as u32;
+                len += 1;
+            } else {
+                break;
+            }
+        }
+
+        if len > 0 && value <= 255 && (len == 1 || bytes[0] != b'0') {
+            Some(len)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for Ipv4Parser {
+    fn default() -> Self {
+        Ipv4Parser { base: ParserBase::new() }
+    }
+}
+
+impl Parser for Ipv4Parser {
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+        Ipv4Parser::calculate_match_length(value).map(|len| ParseResult::new(self, &value[..len]))
+    }
+
+    fn kind(&self) -> &'static str {
+        "IPV4"
+    }
+
+    fn specificity(&self) -> u8 {
+        5
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::Ip
+    }
+}
+
+impl ObjectSafeHash for Ipv4Parser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:ipv4".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{Ipv4Parser, Parser};
+
+    #[test]
+    fn test_given_ipv4_parser_when_the_value_starts_with_a_valid_address_then_it_matches() {
+        let parser = Ipv4Parser::with_name("ip");
+        let res = parser.parse("192.168.1.254 port 22").unwrap();
+        assert_eq!(res.parser().name(), Some("ip"));
+        assert_eq!(res.value(), "192.168.1.254");
+    }
+
+    #[test]
+    fn test_given_ipv4_parser_when_the_address_is_followed_by_a_dot_then_the_dot_is_not_consumed() {
+        let parser = Ipv4Parser::new();
+        assert_eq!(parser.parse("10.0.0.1.").unwrap().value(), "10.0.0.1");
+    }
+
+    #[test]
+    fn test_given_ipv4_parser_when_an_octet_is_out_of_range_then_it_does_not_match() {
+        let parser = Ipv4Parser::new();
+        assert_eq!(parser.parse("10.0.256.1").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_ipv4_parser_when_the_address_is_incomplete_then_it_does_not_match() {
+        let parser = Ipv4Parser::new();
+        assert_eq!(parser.parse("10.0.1").is_none(), true);
+        assert_eq!(parser.parse("10.0..1").is_none(), true);
+        assert_eq!(parser.parse("").is_none(), true);
+        assert_eq!(parser.parse("garbage").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_ipv4_parser_when_the_last_octet_is_followed_by_a_digit_then_it_does_not_match() {
+        let parser = Ipv4Parser::new();
+        assert_eq!(parser.parse("10.0.0.1234").is_none(), true);
+        assert_eq!(parser.parse("10.0.0.12 34").unwrap().value(), "10.0.0.12");
+    }
+
+    #[test]
+    fn test_given_ipv4_parser_when_an_octet_has_leading_zeros_then_it_does_not_match() {
+        let parser = Ipv4Parser::new();
+        assert_eq!(parser.parse("10.01.0.1").is_none(), true);
+        assert_eq!(parser.parse("10.0.0.001").is_none(), true);
+        assert_eq!(parser.parse("10.0.0.0").unwrap().value(), "10.0.0.0");
+    }
+}
@@ -0,0 +1,124 @@
+use std::hash::{SipHasher, Hash, Hasher};
+use std::net::Ipv6Addr;
+use std::str::FromStr;
+
+use parsers::{Parser, ObjectSafeHash, ParseResult, ParserBase, ValueType};
+
+// "ffff:ffff:ffff:ffff:ffff:ffff:255.255.255.255" is the longest textual
+// representation of an IPv6 address
+const MAX_ADDRESS_LENGTH: usize = 45;
+
+#[derive(Clone, Debug, Hash)]
+pub struct Ipv6Parser {
+    base: ParserBase,
+}
+
+impl Ipv6Parser {
+    pub fn with_name<S: Into<String>>(name: S) -> Ipv6Parser {
+        Ipv6Parser { base: ParserBase::with_name(name.into()) }
+    }
+
+    pub fn new() -> Ipv6Parser {
+        Ipv6Parser::default()
+    }
+
+    // Returns the length of the longest prefix of `value` which is a valid
+    // IPv6 address.
+    pub fn calculate_match_length(value: &str) -> Option<usize> {
+        let candidate_len = value.bytes()
+                                 .take(MAX_ADDRESS_LENGTH)
+                                 .take_while(Ipv6Parser::is_address_character)
+                                 .count();
+
+        (2..candidate_len + 1).rev().find(|len| Ipv6Addr::from_str(&value[..*len]).is_ok())
+    }
+
+    fn is_address_character(c: &u8) -> bool {
+        match *c {
+            b'0'...b'9' | b'a'...b'f' | b'A'...b'F' | b':' | b'.' => true,
+            _ => false,
+        }
+    }
+}
+
+impl Default for Ipv6Parser {
+    fn default() -> Self {
+        Ipv6Parser { base: ParserBase::new() }
+    }
+}
+
+impl Parser for Ipv6Parser {
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+        Ipv6Parser::calculate_match_length(value).map(|len| ParseResult::new(self, &value[..len]))
+    }
+
+    fn kind(&self) -> &'static str {
+        "IPV6"
+    }
+
+    fn specificity(&self) -> u8 {
+        5
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::Ip
+    }
+}
+
+impl ObjectSafeHash for Ipv6Parser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:ipv6".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{Ipv6Parser, Parser};
+
+    #[test]
+    fn test_given_ipv6_parser_when_the_value_starts_with_a_valid_address_then_it_matches() {
+        let parser = Ipv6Parser::with_name("ip");
+        let res = parser.parse("2001:db8::ff00:42:8329 port 22").unwrap();
+        assert_eq!(res.parser().name(), Some("ip"));
+        assert_eq!(res.value(), "2001:db8::ff00:42:8329");
+    }
+
+    #[test]
+    fn test_given_ipv6_parser_when_the_address_is_followed_by_a_colon_then_the_colon_is_not_consumed
+        () {
+        let parser = Ipv6Parser::new();
+        assert_eq!(parser.parse("fe80::1: disconnected").unwrap().value(), "fe80::1");
+        assert_eq!(parser.parse("::1").unwrap().value(), "::1");
+    }
+
+    #[test]
+    fn test_given_ipv6_parser_when_the_address_has_an_embedded_ipv4_address_then_it_matches() {
+        let parser = Ipv6Parser::new();
+        assert_eq!(parser.parse("::ffff:192.0.2.128]").unwrap().value(),
+                   "::ffff:192.0.2.128");
+    }
+
+    #[test]
+    fn test_given_ipv6_parser_when_the_value_is_not_an_address_then_it_does_not_match() {
+        let parser = Ipv6Parser::new();
+        assert_eq!(parser.parse("192.168.1.1").is_none(), true);
+        assert_eq!(parser.parse("deadbeef").is_none(), true);
+        assert_eq!(parser.parse(":").is_none(), true);
+        assert_eq!(parser.parse("").is_none(), true);
+    }
+}
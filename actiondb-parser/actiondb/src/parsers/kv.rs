@@ -0,0 +1,219 @@
+use std::borrow::Cow;
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{Parser, ObjectSafeHash, ParseResult, ParserBase, QStringParser,
+              OptionalParameter};
+
+const DEFAULT_PAIR_SEPARATOR: &'static str = " ";
+const DEFAULT_VALUE_SEPARATOR: &'static str = "=";
+
+#[derive(Clone, Debug, Hash)]
+pub struct KvParser {
+    base: ParserBase,
+    pair_separator: String,
+    value_separator: String,
+    quoted_value_parser: QStringParser,
+}
+
+impl KvParser {
+    pub fn with_name<S: Into<String>>(name: S) -> KvParser {
+        let mut parser = KvParser::new();
+        parser.set_name(Some(name.into()));
+        parser
+    }
+
+    pub fn new() -> KvParser {
+        KvParser::default()
+    }
+
+    pub fn set_pair_separator(&mut self, separator: &str) {
+        self.pair_separator = separator.to_owned();
+    }
+
+    pub fn set_value_separator(&mut self, separator: &str) {
+        self.value_separator = separator.to_owned();
+    }
+
+    // Returns the length of a `key=value` pair at the beginning of `value`,
+    // its key and its (unquoted) value.
+    fn parse_pair<'b>(&self, value: &'b str) -> Option<(usize, &'b str, Cow<'b, str>)> {
+        value.find(&self.value_separator[..]).and_then(|key_len| {
+            let key = &value[..key_len];
+            if key.is_empty() || key.contains(&self.pair_separator[..]) {
+                return None;
+            }
+
+            let value_begin = key_len + self.value_separator.len();
+            let rest = &value[value_begin..];
+
+            let (value_len, value) = match self.quoted_value_parser.parse(rest) {
+                Some(result) => (result.match_length(), result.into_values().0),
+                None => {
+                    let value_len = rest.find(&self.pair_separator[..]).unwrap_or(rest.len());
+                    (value_len, Cow::Borrowed(&rest[..value_len]))
+                }
+            };
+            Some((value_begin + value_len, key, value))
+        })
+    }
+
+    fn skip_pair_separators(&self, value: &str) -> usize {
+        let mut pos = 0;
+        while !self.pair_separator.is_empty() && value[pos..].starts_with(&self.pair_separator[..]) {
+            pos += self.pair_separator.len();
+        }
+        pos
+    }
+
+    fn create_key(&self, key: &str) -> String {
+        match self.name() {
+            Some(prefix) => format!("{}.{}", prefix, key),
+            None => key.to_owned(),
+        }
+    }
+}
+
+impl Default for KvParser {
+    fn default() -> Self {
+        let mut quoted_value_parser = QStringParser::new("\"\"");
+        quoted_value_parser.set_escape_character(Some('\\'));
+
+        KvParser {
+            base: ParserBase::new(),
+            pair_separator: DEFAULT_PAIR_SEPARATOR.to_owned(),
+            value_separator: DEFAULT_VALUE_SEPARATOR.to_owned(),
+            quoted_value_parser: quoted_value_parser,
+        }
+    }
+}
+
+impl Parser for KvParser {
+    // Consumes the pairs while they follow each other. Pair separators after
+    // the last pair are not consumed.
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+        let mut pairs = Vec::new();
+        let mut match_length = 0;
+
+        loop {
+            let begin = if pairs.is_empty() {
+                0
+            } else {
+                match self.skip_pair_separators(&value[match_length..]) {
+                    0 => break,
+                    separators_len => match_length + separators_len,
+                }
+            };
+
+            match self.parse_pair(&value[begin..]) {
+                Some((pair_len, key, pair_value)) => {
+                    pairs.push((key, pair_value));
+                    match_length = begin + pair_len;
+                }
+                None => break,
+            }
+        }
+
+        if pairs.is_empty() {
+            return None;
+        }
+
+        let mut result = ParseResult::new(self, &value[..match_length]);
+        for (key, pair_value) in pairs {
+            result.push_additional_value(Cow::Owned(self.create_key(key)), pair_value);
+        }
+        Some(result)
+    }
+
+    fn kind(&self) -> &'static str {
+        "KV"
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        vec![OptionalParameter::Str("pair_separator", self.pair_separator.clone()),
+             OptionalParameter::Str("value_separator", self.value_separator.clone())]
+    }
+}
+
+impl ObjectSafeHash for KvParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:kv".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{Parser, KvParser, ObjectSafeHash};
+
+    #[test]
+    fn test_given_kv_parser_when_the_value_contains_pairs_then_they_are_added_with_the_prefix() {
+        let parser = KvParser::with_name("fw");
+        let res = parser.parse("action=drop src=1.2.3.4 dport=443").unwrap();
+        assert_eq!(res.parser().name(), Some("fw"));
+        assert_eq!(res.value(), "action=drop src=1.2.3.4 dport=443");
+        assert_eq!(res.additional_values(),
+                   &[("fw.action".into(), "drop".into()),
+                     ("fw.src".into(), "1.2.3.4".into()),
+                     ("fw.dport".into(), "443".into())]);
+    }
+
+    #[test]
+    fn test_given_kv_parser_without_name_when_the_value_contains_pairs_then_the_keys_are_not_prefixed
+        () {
+        let parser = KvParser::new();
+        let res = parser.parse("proto=tcp").unwrap();
+        assert_eq!(res.additional_values(), &[("proto".into(), "tcp".into())]);
+    }
+
+    #[test]
+    fn test_given_kv_parser_when_the_pairs_are_followed_by_other_text_then_it_is_not_consumed() {
+        let parser = KvParser::new();
+        let res = parser.parse("action=drop  proto=tcp  [end]").unwrap();
+        assert_eq!(res.value(), "action=drop  proto=tcp");
+        assert_eq!(res.additional_values().len(), 2);
+        assert_eq!(parser.parse("no pairs here").is_none(), true);
+        assert_eq!(parser.parse("=value").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_kv_parser_when_a_value_is_quoted_then_it_is_unquoted() {
+        let parser = KvParser::new();
+        let res = parser.parse(r#"msg="access \"denied\"" user= rule=12"#).unwrap();
+        assert_eq!(res.additional_values(),
+                   &[("msg".into(), r#"access "denied""#.into()),
+                     ("user".into(), "".into()),
+                     ("rule".into(), "12".into())]);
+    }
+
+    #[test]
+    fn test_given_kv_parser_with_custom_separators_when_the_value_is_parsed_then_they_are_used() {
+        let mut parser = KvParser::with_name("kv");
+        parser.set_pair_separator(", ");
+        parser.set_value_separator(": ");
+        let res = parser.parse("user: joe, shell: /bin/zsh").unwrap();
+        assert_eq!(res.additional_values(),
+                   &[("kv.user".into(), "joe".into()), ("kv.shell".into(), "/bin/zsh".into())]);
+    }
+
+    #[test]
+    fn test_given_kv_parsers_when_they_have_different_separators_then_their_hashes_are_different() {
+        let p1 = KvParser::with_name("kv");
+        let mut p2 = KvParser::with_name("kv");
+        p2.set_pair_separator(";");
+        assert_eq!(p1.hash_os() == p2.hash_os(), false);
+    }
+}
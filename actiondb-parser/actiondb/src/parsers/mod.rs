@@ -2,42 +2,107 @@ mod set;
 mod base;
 mod int;
 pub mod has_length_constraint;
+pub mod has_value_constraint;
 mod greedy;
+mod number;
+mod qstring;
+mod timestamp;
+mod regex;
+mod kv;
+mod ipv4;
+mod ipv6;
+mod ipaddr;
+mod value_type;
 
+use std::borrow::Cow;
 use std::fmt::Debug;
 pub use self::set::SetParser;
 pub use self::base::ParserBase;
 pub use self::int::IntParser;
 pub use self::has_length_constraint::HasLengthConstraint;
+pub use self::has_value_constraint::HasValueConstraint;
 pub use self::greedy::GreedyParser;
+pub use self::number::NumberParser;
+pub use self::qstring::QStringParser;
+pub use self::timestamp::TimestampParser;
+pub use self::regex::RegexParser;
+pub use self::kv::KvParser;
+pub use self::ipv4::Ipv4Parser;
+pub use self::ipv6::Ipv6Parser;
+pub use self::ipaddr::IpAddrParser;
+pub use self::value_type::ValueType;
 
 pub trait ObjectSafeHash {
     fn hash_os(&self) -> u64;
 }
 
-pub trait Parser: Debug + ObjectSafeHash {
+pub trait Parser: Debug + ObjectSafeHash + Send + Sync {
     fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>>;
+    // The parser's keyword in the pattern grammar (e.g. `GREEDY`).
+    fn kind(&self) -> &'static str;
     fn name(&self) -> Option<&str>;
     fn set_name(&mut self, Option<String>);
     fn boxed_clone(&self) -> Box<Parser>;
+    // The type of the value extracted by the parser (not the types of its
+    // additional values).
+    fn value_type(&self) -> ValueType {
+        ValueType::String
+    }
+    // Parsers which accept less inputs are more specific, the matchers try
+    // them first. GREEDY is the least specific one with 0.
+    fn specificity(&self) -> u8 {
+        2
+    }
+    // Returns the next possible match after a match which was
+    // `previous_match_length` bytes long. It's used by the matchers to
+    // backtrack, most of the parsers don't have alternative matches.
+    fn parse_next<'a, 'b>(&'a self,
+                          _value: &'b str,
+                          _previous_match_length: usize)
+                          -> Option<ParseResult<'a, 'b>> {
+        None
+    }
+    // The arguments which create the same parser with its ParserFactory
+    // method. The required arguments are returned too, with the names of
+    // the method's parameters (`set`, `quotes`, `regex` and `end_string`).
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        Vec::new()
+    }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum OptionalParameter<'a> {
     Int(&'a str, usize),
+    Float(&'a str, f64),
+    Str(&'a str, String),
+    Bool(&'a str, bool),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ParseResult<'a, 'b> {
     parser: &'a Parser,
-    value: &'b str,
+    value: Cow<'b, str>,
+    match_length: usize,
+    additional_values: Vec<(Cow<'a, str>, Cow<'b, str>)>,
 }
 
 impl<'a, 'b> ParseResult<'a, 'b> {
     pub fn new(parser: &'a Parser, value: &'b str) -> ParseResult<'a, 'b> {
+        ParseResult::with_match_length(parser, Cow::Borrowed(value), value.len())
+    }
+
+    // Used by parsers which consume more characters than the value they
+    // extract (e.g. the enclosing quotes of a quoted string) or which
+    // extract a transformed value.
+    pub fn with_match_length(parser: &'a Parser,
+                             value: Cow<'b, str>,
+                             match_length: usize)
+                             -> ParseResult<'a, 'b> {
         ParseResult {
             parser: parser,
             value: value,
+            match_length: match_length,
+            additional_values: Vec::new(),
         }
     }
 
@@ -45,8 +110,26 @@ impl<'a, 'b> ParseResult<'a, 'b> {
         self.parser
     }
 
-    pub fn value(&self) -> &'b str {
-        self.value
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn match_length(&self) -> usize {
+        self.match_length
+    }
+
+    // Values which are extracted by the parser besides its main value and
+    // have their own keys (e.g. a normalized form of the parsed value).
+    pub fn push_additional_value(&mut self, key: Cow<'a, str>, value: Cow<'b, str>) {
+        self.additional_values.push((key, value));
+    }
+
+    pub fn additional_values(&self) -> &[(Cow<'a, str>, Cow<'b, str>)] {
+        &self.additional_values
+    }
+
+    pub fn into_values(self) -> (Cow<'b, str>, Vec<(Cow<'a, str>, Cow<'b, str>)>) {
+        (self.value, self.additional_values)
     }
 }
 
@@ -59,4 +142,21 @@ pub trait ParserFactory: {
                    opt_params: Option<Vec<OptionalParameter>>)
                    -> Box<Parser>;
     fn new_greedy(name: Option<&str>, end_string: Option<&str>) -> Box<Parser>;
+    fn new_number(name: Option<&str>,
+                   opt_params: Option<Vec<OptionalParameter>>)
+                   -> Box<Parser>;
+    fn new_qstring<'a>(quotes: &str,
+                       name: Option<&str>,
+                       opt_params: Option<Vec<OptionalParameter<'a>>>)
+                       -> Box<Parser>;
+    fn new_timestamp<'a>(name: Option<&str>,
+                         opt_params: Option<Vec<OptionalParameter<'a>>>)
+                         -> Box<Parser>;
+    fn new_regex(regex: &str, name: Option<&str>) -> Result<Box<Parser>, ::regex::Error>;
+    fn new_kv<'a>(name: Option<&str>,
+                  opt_params: Option<Vec<OptionalParameter<'a>>>)
+                  -> Box<Parser>;
+    fn new_ipv4(name: Option<&str>) -> Box<Parser>;
+    fn new_ipv6(name: Option<&str>) -> Box<Parser>;
+    fn new_ipaddr(name: Option<&str>) -> Box<Parser>;
 }
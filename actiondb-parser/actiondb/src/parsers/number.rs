@@ -0,0 +1,226 @@
+use std::hash::{SipHasher, Hash, Hasher};
+use std::str::FromStr;
+
+use parsers::{Parser, ObjectSafeHash, ParseResult, ParserBase, HasValueConstraint,
+              OptionalParameter};
+use parsers::ValueType;
+
+#[derive(Clone, Debug)]
+pub struct NumberParser {
+    base: ParserBase,
+    min_value: Option<f64>,
+    max_value: Option<f64>,
+}
+
+impl NumberParser {
+    pub fn with_name<S: Into<String>>(name: S) -> NumberParser {
+        NumberParser {
+            base: ParserBase::with_name(name.into()),
+            min_value: None,
+            max_value: None,
+        }
+    }
+
+    pub fn new() -> NumberParser {
+        NumberParser::default()
+    }
+
+    // Returns the length of the longest prefix of `value` which looks like
+    // a (possibly signed) decimal number with an optional fraction and
+    // exponent part. A trailing dot or exponent sign without digits is not
+    // consumed.
+    fn calculate_match_length(value: &str) -> Option<usize> {
+        let bytes = value.as_bytes();
+        let mut pos = 0;
+
+        if let Some(&b'-') | Some(&b'+') = bytes.get(pos) {
+            pos += 1;
+        }
+
+        let integer_len = NumberParser::count_digits(&bytes[pos..]);
+        pos += integer_len;
+
+        let fraction_len = NumberParser::calculate_part_length(&bytes[pos..], b".");
+        pos += fraction_len;
+
+        if integer_len == 0 && fraction_len == 0 {
+            return None;
+        }
+
+        pos += NumberParser::calculate_exponent_length(&bytes[pos..]);
+        Some(pos)
+    }
+
+    fn count_digits(bytes: &[u8]) -> usize {
+        bytes.iter().take_while(|c| **c >= b'0' && **c <= b'9').count()
+    }
+
+    // a separator character followed by at least one digit
+    fn calculate_part_length(bytes: &[u8], separators: &[u8]) -> usize {
+        match bytes.first() {
+            Some(c) if separators.contains(c) => {
+                let digits = NumberParser::count_digits(&bytes[1..]);
+                if digits > 0 {
+                    digits + 1
+                } else {
+                    0
+                }
+            }
+            _ => 0,
+        }
+    }
+
+    fn calculate_exponent_length(bytes: &[u8]) -> usize {
+        match bytes.first() {
+            Some(&b'e') | Some(&b'E') => {
+                let signed_len = NumberParser::calculate_part_length(&bytes[1..], b"+-");
+                if signed_len > 0 {
+                    signed_len + 1
+                } else {
+                    NumberParser::calculate_part_length(bytes, b"eE")
+                }
+            }
+            _ => 0,
+        }
+    }
+}
+
+impl Default for NumberParser {
+    fn default() -> Self {
+        NumberParser {
+            base: ParserBase::new(),
+            min_value: None,
+            max_value: None,
+        }
+    }
+}
+
+impl HasValueConstraint for NumberParser {
+    fn min_value(&self) -> Option<f64> {
+        self.min_value
+    }
+    fn set_min_value(&mut self, value: Option<f64>) {
+        self.min_value = value;
+    }
+    fn max_value(&self) -> Option<f64> {
+        self.max_value
+    }
+    fn set_max_value(&mut self, value: Option<f64>) {
+        self.max_value = value;
+    }
+}
+
+impl Parser for NumberParser {
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+        NumberParser::calculate_match_length(value).and_then(|len| {
+            let number = &value[..len];
+            match f64::from_str(number) {
+                Ok(parsed) if self.is_value_ok(parsed) => Some(ParseResult::new(self, number)),
+                _ => None,
+            }
+        })
+    }
+
+    fn kind(&self) -> &'static str {
+        "NUMBER"
+    }
+
+    fn specificity(&self) -> u8 {
+        3
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        self.value_parameters()
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::Float
+    }
+}
+
+impl Hash for NumberParser {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.base.hash(state);
+        self.min_value.map(|value| value.to_bits()).hash(state);
+        self.max_value.map(|value| value.to_bits()).hash(state);
+    }
+}
+
+impl ObjectSafeHash for NumberParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:number".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{NumberParser, Parser, HasValueConstraint, ObjectSafeHash};
+
+    #[test]
+    fn test_given_number_parser_when_the_value_is_a_signed_or_fractional_number_then_it_matches() {
+        let parser = NumberParser::with_name("latency");
+        let res = parser.parse("-0.25 ms").unwrap();
+        assert_eq!(res.parser().name(), Some("latency"));
+        assert_eq!(res.value(), "-0.25");
+        assert_eq!(parser.parse("1.5e3ms").unwrap().value(), "1.5e3");
+        assert_eq!(parser.parse("+42,").unwrap().value(), "+42");
+        assert_eq!(parser.parse(".5").unwrap().value(), ".5");
+        assert_eq!(parser.parse("2E-3").unwrap().value(), "2E-3");
+    }
+
+    #[test]
+    fn test_given_number_parser_when_the_number_is_followed_by_a_dot_or_an_exponent_without_digits_then_they_are_not_consumed
+        () {
+        let parser = NumberParser::new();
+        assert_eq!(parser.parse("42.").unwrap().value(), "42");
+        assert_eq!(parser.parse("42 e").unwrap().value(), "42");
+        assert_eq!(parser.parse("42e+").unwrap().value(), "42");
+        assert_eq!(parser.parse("42error").unwrap().value(), "42");
+    }
+
+    #[test]
+    fn test_given_number_parser_when_the_value_is_not_a_number_then_it_does_not_match() {
+        let parser = NumberParser::new();
+        assert_eq!(parser.parse("").is_none(), true);
+        assert_eq!(parser.parse("-").is_none(), true);
+        assert_eq!(parser.parse(".e3").is_none(), true);
+        assert_eq!(parser.parse("abc").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_number_parser_with_value_constraints_when_the_number_is_out_of_range_then_it_does_not_match
+        () {
+        let mut parser = NumberParser::new();
+        parser.set_min_value(Some(-1.0));
+        parser.set_max_value(Some(100.0));
+        assert_eq!(parser.parse("-0.5").unwrap().value(), "-0.5");
+        assert_eq!(parser.parse("100").unwrap().value(), "100");
+        assert_eq!(parser.parse("100.01").is_none(), true);
+        assert_eq!(parser.parse("-2").is_none(), true);
+        assert_eq!(parser.parse("1e3").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_number_parsers_when_they_have_different_constraints_then_their_hashes_are_different
+        () {
+        let p1 = NumberParser::with_name("number");
+        let mut p2 = NumberParser::with_name("number");
+        p2.set_max_value(Some(1.0));
+        assert_eq!(p1.hash_os() == p2.hash_os(), false);
+    }
+}
@@ -0,0 +1,213 @@
+use std::borrow::Cow;
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{Parser, ObjectSafeHash, ParseResult, ParserBase, OptionalParameter};
+
+#[derive(Clone, Debug, Hash)]
+pub struct QStringParser {
+    base: ParserBase,
+    quotes: Vec<(char, char)>,
+    escape_character: Option<char>,
+    unescape: bool,
+}
+
+impl QStringParser {
+    pub fn with_name<S: Into<String>>(name: S, quotes: &str) -> QStringParser {
+        let mut parser = QStringParser::new(quotes);
+        parser.set_name(Some(name.into()));
+        parser
+    }
+
+    pub fn new(quotes: &str) -> QStringParser {
+        QStringParser {
+            base: ParserBase::new(),
+            quotes: QStringParser::create_quote_pairs_from_str(quotes),
+            escape_character: None,
+            unescape: true,
+        }
+    }
+
+    pub fn set_quotes(&mut self, quotes: &str) {
+        self.quotes = QStringParser::create_quote_pairs_from_str(quotes);
+    }
+
+    pub fn set_escape_character(&mut self, escape_character: Option<char>) {
+        self.escape_character = escape_character;
+    }
+
+    pub fn set_unescape(&mut self, unescape: bool) {
+        self.unescape = unescape;
+    }
+
+    // Every two characters form an opening and a closing quote. A trailing
+    // single character is used as both.
+    fn create_quote_pairs_from_str(quotes: &str) -> Vec<(char, char)> {
+        let quotes: Vec<char> = quotes.chars().collect();
+        quotes.chunks(2)
+              .map(|pair| (pair[0], *pair.last().unwrap()))
+              .collect()
+    }
+
+    fn find_closing_quote(&self, value: &str) -> Option<char> {
+        value.chars().next().and_then(|first| {
+            self.quotes
+                .iter()
+                .find(|&&(opening, _)| opening == first)
+                .map(|&(_, closing)| closing)
+        })
+    }
+
+    // Returns the byte position of the closing quote in `value` and whether
+    // an escape character was found before it.
+    fn find_end_of_quoted_string(&self, value: &str, closing_quote: char) -> Option<(usize, bool)> {
+        let mut escaped = false;
+        let mut has_escape = false;
+
+        for (pos, c) in value.char_indices() {
+            if escaped {
+                escaped = false;
+            } else if Some(c) == self.escape_character {
+                escaped = true;
+                has_escape = true;
+            } else if c == closing_quote {
+                return Some((pos, has_escape));
+            }
+        }
+
+        None
+    }
+
+    fn unescape(&self, value: &str) -> String {
+        let mut unescaped = String::with_capacity(value.len());
+        let mut escaped = false;
+
+        for c in value.chars() {
+            if !escaped && Some(c) == self.escape_character {
+                escaped = true;
+            } else {
+                escaped = false;
+                unescaped.push(c);
+            }
+        }
+
+        unescaped
+    }
+}
+
+impl Parser for QStringParser {
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+        self.find_closing_quote(value).and_then(|closing_quote| {
+            let opening_quote_len = value.chars().next().unwrap().len_utf8();
+            let content = &value[opening_quote_len..];
+
+            self.find_end_of_quoted_string(content, closing_quote).map(|(end, has_escape)| {
+                let quoted = &content[..end];
+                let match_length = opening_quote_len + end + closing_quote.len_utf8();
+                let quoted = if has_escape && self.unescape {
+                    Cow::Owned(self.unescape(quoted))
+                } else {
+                    Cow::Borrowed(quoted)
+                };
+                ParseResult::with_match_length(self, quoted, match_length)
+            })
+        })
+    }
+
+    fn kind(&self) -> &'static str {
+        "QSTRING"
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        let quotes = self.quotes
+                         .iter()
+                         .flat_map(|&(opening, closing)| vec![opening, closing])
+                         .collect();
+        let mut parameters = vec![OptionalParameter::Str("quotes", quotes)];
+        if let Some(escape_character) = self.escape_character {
+            parameters.push(OptionalParameter::Str("escape", escape_character.to_string()));
+        }
+        parameters.push(OptionalParameter::Bool("unescape", self.unescape));
+        parameters
+    }
+}
+
+impl ObjectSafeHash for QStringParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:qstring".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{Parser, QStringParser, ObjectSafeHash};
+
+    #[test]
+    fn test_given_qstring_parser_when_the_value_is_quoted_then_the_unquoted_value_is_returned() {
+        let parser = QStringParser::with_name("user", "\"\"");
+        let res = parser.parse("\"John Doe\" logged in").unwrap();
+        assert_eq!(res.parser().name(), Some("user"));
+        assert_eq!(res.value(), "John Doe");
+        assert_eq!(res.match_length(), "\"John Doe\"".len());
+    }
+
+    #[test]
+    fn test_given_qstring_parser_when_the_value_is_not_quoted_or_not_terminated_then_it_does_not_match
+        () {
+        let parser = QStringParser::new("\"\"");
+        assert_eq!(parser.parse("John Doe").is_none(), true);
+        assert_eq!(parser.parse("\"John Doe").is_none(), true);
+        assert_eq!(parser.parse("").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_qstring_parser_with_escape_character_when_the_value_contains_escaped_quotes_then_they_are_unescaped
+        () {
+        let mut parser = QStringParser::new("\"\"");
+        parser.set_escape_character(Some('\\'));
+        let res = parser.parse(r#""John \"JJ\" Doe" logged in"#).unwrap();
+        assert_eq!(res.value(), r#"John "JJ" Doe"#);
+        assert_eq!(res.match_length(), r#""John \"JJ\" Doe""#.len());
+        assert_eq!(parser.parse(r#""C:\\" logged in"#).unwrap().value(), r#"C:\"#);
+    }
+
+    #[test]
+    fn test_given_qstring_parser_when_unescaping_is_disabled_then_the_value_is_returned_as_is() {
+        let mut parser = QStringParser::new("\"\"");
+        parser.set_escape_character(Some('\\'));
+        parser.set_unescape(false);
+        let res = parser.parse(r#""John \"JJ\" Doe""#).unwrap();
+        assert_eq!(res.value(), r#"John \"JJ\" Doe"#);
+    }
+
+    #[test]
+    fn test_given_qstring_parser_with_asymmetric_quotes_when_the_value_is_quoted_with_any_of_them_then_it_matches
+        () {
+        let parser = QStringParser::new("[]{}«»");
+        assert_eq!(parser.parse("[foo bar] baz").unwrap().value(), "foo bar");
+        assert_eq!(parser.parse("{foo [bar]} baz").unwrap().value(), "foo [bar]");
+        assert_eq!(parser.parse("«foo» baz").unwrap().value(), "foo");
+        assert_eq!(parser.parse("(foo) baz").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_qstring_parsers_when_they_have_different_quotes_then_their_hashes_are_different() {
+        let p1 = QStringParser::with_name("name", "\"\"");
+        let p2 = QStringParser::with_name("name", "''");
+        assert_eq!(p1.hash_os() == p2.hash_os(), false);
+    }
+}
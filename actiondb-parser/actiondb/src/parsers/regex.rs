@@ -0,0 +1,132 @@
+use std::borrow::Cow;
+use std::hash::{SipHasher, Hash, Hasher};
+
+use regex::{self, Regex};
+
+use parsers::{Parser, ObjectSafeHash, ParseResult, ParserBase, OptionalParameter};
+
+#[derive(Clone, Debug)]
+pub struct RegexParser {
+    base: ParserBase,
+    pattern: String,
+    regex: Regex,
+}
+
+impl RegexParser {
+    pub fn with_name<S: Into<String>>(name: S, pattern: &str) -> Result<RegexParser, regex::Error> {
+        let mut parser = try!(RegexParser::new(pattern));
+        parser.set_name(Some(name.into()));
+        Ok(parser)
+    }
+
+    // The regular expression is anchored to the beginning of the parsed
+    // value, so it can't skip characters before its match.
+    pub fn new(pattern: &str) -> Result<RegexParser, regex::Error> {
+        let regex = try!(Regex::new(&format!("^(?:{})", pattern)));
+        Ok(RegexParser {
+            base: ParserBase::new(),
+            pattern: pattern.to_owned(),
+            regex: regex,
+        })
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+}
+
+impl Parser for RegexParser {
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+        self.regex.captures(value).and_then(|captures| {
+            captures.at(0).map(|matched| {
+                let mut result = ParseResult::new(self, matched);
+                for name in self.regex.capture_names().filter_map(|name| name) {
+                    if let Some(captured) = captures.name(name) {
+                        result.push_additional_value(Cow::Borrowed(name), Cow::Borrowed(captured));
+                    }
+                }
+                result
+            })
+        })
+    }
+
+    fn kind(&self) -> &'static str {
+        "REGEX"
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        vec![OptionalParameter::Str("regex", self.pattern.clone())]
+    }
+}
+
+impl Hash for RegexParser {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.base.hash(state);
+        self.pattern.hash(state);
+    }
+}
+
+impl ObjectSafeHash for RegexParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:regex".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{Parser, RegexParser, ObjectSafeHash};
+
+    #[test]
+    fn test_given_regex_parser_when_the_value_starts_with_a_match_then_the_match_is_returned() {
+        let parser = RegexParser::with_name("session", "[a-f0-9]{4}-[a-f0-9]{4}").unwrap();
+        let res = parser.parse("dead-beef logged in").unwrap();
+        assert_eq!(res.parser().name(), Some("session"));
+        assert_eq!(res.value(), "dead-beef");
+        assert_eq!(res.additional_values().is_empty(), true);
+    }
+
+    #[test]
+    fn test_given_regex_parser_when_the_match_is_not_at_the_beginning_of_the_value_then_it_does_not_match
+        () {
+        let parser = RegexParser::new("[0-9]+|foo").unwrap();
+        assert_eq!(parser.parse("abc 123").is_none(), true);
+        assert_eq!(parser.parse("abc foo").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_regex_parser_with_named_groups_when_the_value_matches_then_the_captures_are_added
+        () {
+        let parser = RegexParser::new(r"(?P<user>\w+)@(?P<host>[\w.]+)(:(?P<port>\d+))?").unwrap();
+        let res = parser.parse("joe@example.com logged in").unwrap();
+        assert_eq!(res.value(), "joe@example.com");
+        assert_eq!(res.additional_values(),
+                   &[("user".into(), "joe".into()), ("host".into(), "example.com".into())]);
+    }
+
+    #[test]
+    fn test_given_invalid_regular_expression_when_the_parser_is_created_then_we_get_an_error() {
+        assert_eq!(RegexParser::new("(foo").is_err(), true);
+    }
+
+    #[test]
+    fn test_given_regex_parsers_when_they_have_different_patterns_then_their_hashes_are_different() {
+        let p1 = RegexParser::with_name("name", "[0-9]+").unwrap();
+        let p2 = RegexParser::with_name("name", "[0-9]*").unwrap();
+        assert_eq!(p1.hash_os() == p2.hash_os(), false);
+    }
+}
@@ -1,13 +1,142 @@
-use std::collections::BTreeSet;
-use std::iter::FromIterator;
 use std::hash::{SipHasher, Hash, Hasher};
 
-use parsers::{Parser, ObjectSafeHash, ParseResult, ParserBase, HasLengthConstraint};
+use parsers::{Parser, ObjectSafeHash, ParseResult, ParserBase, HasLengthConstraint,
+              OptionalParameter};
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq)]
+enum CharacterClass {
+    Alnum,
+    Alpha,
+    Digit,
+    Lower,
+    Upper,
+    Space,
+    Punct,
+    XDigit,
+}
+
+impl CharacterClass {
+    fn from_name(name: &str) -> Option<CharacterClass> {
+        match name {
+            "alnum" => Some(CharacterClass::Alnum),
+            "alpha" => Some(CharacterClass::Alpha),
+            "digit" => Some(CharacterClass::Digit),
+            "lower" => Some(CharacterClass::Lower),
+            "upper" => Some(CharacterClass::Upper),
+            "space" => Some(CharacterClass::Space),
+            "punct" => Some(CharacterClass::Punct),
+            "xdigit" => Some(CharacterClass::XDigit),
+            _ => None,
+        }
+    }
+
+    fn contains(&self, c: char) -> bool {
+        match *self {
+            CharacterClass::Alnum => c.is_alphanumeric(),
+            CharacterClass::Alpha => c.is_alphabetic(),
+            CharacterClass::Digit => c >= '0' && c <= '9',
+            CharacterClass::Lower => c.is_lowercase(),
+            CharacterClass::Upper => c.is_uppercase(),
+            CharacterClass::Space => c.is_whitespace(),
+            CharacterClass::Punct => c > ' ' && c < '\x7f' && !c.is_alphanumeric(),
+            CharacterClass::XDigit => c.is_digit(16),
+        }
+    }
+}
+
+// A set of characters built from its textual representation, like
+// `^a-z0-9[:space:]`.
+#[derive(Clone, Debug, Hash)]
+struct CharacterSet {
+    negated: bool,
+    ranges: Vec<(char, char)>,
+    classes: Vec<CharacterClass>,
+}
+
+impl CharacterSet {
+    // A leading `^` negates the set. A `-` between two characters creates a
+    // range, otherwise it's a literal `-`. Named classes are enclosed in
+    // `[:` and `:]`. Backslash escapes the next character, `\t`, `\n` and
+    // `\r` are the usual control characters.
+    fn from_str(set: &str) -> CharacterSet {
+        let (negated, set) = if set.starts_with('^') && set.len() > 1 {
+            (true, &set[1..])
+        } else {
+            (false, set)
+        };
+
+        let mut ranges = Vec::new();
+        let mut classes = Vec::new();
+        let mut rest = set;
+
+        while let Some((first, first_len)) = CharacterSet::next_char(rest) {
+            if let Some((class, class_len)) = CharacterSet::parse_class(rest) {
+                classes.push(class);
+                rest = &rest[class_len..];
+                continue;
+            }
+
+            rest = &rest[first_len..];
+
+            if rest.starts_with('-') {
+                if let Some((last, last_len)) = CharacterSet::next_char(&rest[1..]) {
+                    if first <= last {
+                        ranges.push((first, last));
+                        rest = &rest[1 + last_len..];
+                        continue;
+                    }
+                }
+            }
+            ranges.push((first, first));
+        }
+
+        CharacterSet {
+            negated: negated,
+            ranges: ranges,
+            classes: classes,
+        }
+    }
+
+    // Returns the next (possibly escaped) character and its length in bytes.
+    fn next_char(set: &str) -> Option<(char, usize)> {
+        let mut chars = set.chars();
+        chars.next().map(|c| {
+            if c != '\\' {
+                return (c, c.len_utf8());
+            }
+            match chars.next() {
+                Some('t') => ('\t', 2),
+                Some('n') => ('\n', 2),
+                Some('r') => ('\r', 2),
+                Some(escaped) => (escaped, 1 + escaped.len_utf8()),
+                None => ('\\', 1),
+            }
+        })
+    }
+
+    fn parse_class(set: &str) -> Option<(CharacterClass, usize)> {
+        if !set.starts_with("[:") {
+            return None;
+        }
+        set[2..].find(":]").and_then(|name_len| {
+            CharacterClass::from_name(&set[2..2 + name_len])
+                .map(|class| (class, 2 + name_len + 2))
+        })
+    }
+
+    fn contains(&self, c: char) -> bool {
+        let found = self.ranges.iter().any(|&(first, last)| c >= first && c <= last) ||
+                    self.classes.iter().any(|class| class.contains(c));
+        found != self.negated
+    }
+}
 
 #[derive(Clone, Debug, Hash)]
 pub struct SetParser {
     base: ParserBase,
-    character_set: BTreeSet<u8>,
+    // the textual representation of the character set
+    set: String,
+    character_set: CharacterSet,
     min_length: Option<usize>,
     max_length: Option<usize>,
 }
@@ -16,7 +145,8 @@ impl SetParser {
     pub fn with_name(name: String, set: &str) -> SetParser {
         SetParser {
             base: ParserBase::with_name(name),
-            character_set: SetParser::create_set_from_str(set),
+            set: set.to_owned(),
+            character_set: CharacterSet::from_str(set),
             min_length: None,
             max_length: None,
         }
@@ -25,7 +155,8 @@ impl SetParser {
     pub fn new(set: &str) -> SetParser {
         SetParser {
             base: ParserBase::new(),
-            character_set: SetParser::create_set_from_str(set),
+            set: set.to_owned(),
+            character_set: CharacterSet::from_str(set),
             min_length: None,
             max_length: None,
         }
@@ -36,26 +167,25 @@ impl SetParser {
     }
 
     pub fn set_character_set(&mut self, set: &str) {
-        self.character_set = SetParser::create_set_from_str(set);
+        self.set = set.to_owned();
+        self.character_set = CharacterSet::from_str(set);
     }
 
-    fn create_set_from_str(set: &str) -> BTreeSet<u8> {
-        let vset: Vec<u8> = set.bytes().collect();
-        BTreeSet::from_iter(vset)
-    }
-
-    fn calculate_match_length(&self, value: &str) -> usize {
+    // Returns the length of the match in characters and in bytes.
+    fn calculate_match_length(&self, value: &str) -> (usize, usize) {
         let mut match_len = 0;
+        let mut match_len_in_bytes = 0;
 
-        for c in value.bytes() {
-            if self.character_set.contains(&c) {
+        for c in value.chars() {
+            if self.character_set.contains(c) {
                 match_len += 1;
+                match_len_in_bytes += c.len_utf8();
             } else {
                 break;
             }
         }
 
-        match_len
+        (match_len, match_len_in_bytes)
     }
 }
 
@@ -76,15 +206,41 @@ impl HasLengthConstraint for SetParser {
 
 impl Parser for SetParser {
     fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
-        let match_len = self.calculate_match_length(value);
+        let (match_len, match_len_in_bytes) = self.calculate_match_length(value);
 
         if self.is_match_length_ok(match_len) {
-            Some(ParseResult::new(self, &value[..match_len]))
+            Some(ParseResult::new(self, &value[..match_len_in_bytes]))
         } else {
             None
         }
     }
 
+    // The alternatives are the shorter prefixes of the previous match which
+    // satisfy the length constraints.
+    fn parse_next<'a, 'b>(&'a self,
+                          value: &'b str,
+                          previous_match_length: usize)
+                          -> Option<ParseResult<'a, 'b>> {
+        let mut match_len_in_bytes = previous_match_length;
+
+        while let Some(c) = value[..match_len_in_bytes].chars().next_back() {
+            match_len_in_bytes -= c.len_utf8();
+            let match_len = value[..match_len_in_bytes].chars().count();
+
+            if self.is_match_length_ok(match_len) {
+                return Some(ParseResult::new(self, &value[..match_len_in_bytes]));
+            } else if match_len == 0 || !self.is_min_length_ok(match_len) {
+                break;
+            }
+        }
+
+        None
+    }
+
+    fn kind(&self) -> &'static str {
+        "SET"
+    }
+
     fn name(&self) -> Option<&str> {
         self.base.name()
     }
@@ -96,6 +252,12 @@ impl Parser for SetParser {
     fn boxed_clone(&self) -> Box<Parser> {
         Box::new(self.clone())
     }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        let mut parameters = vec![OptionalParameter::Str("set", self.set.clone())];
+        parameters.extend(self.length_parameters());
+        parameters
+    }
 }
 
 impl ObjectSafeHash for SetParser {
@@ -157,6 +319,63 @@ mod test {
         assert_eq!(res.value(), "11230");
     }
 
+    #[test]
+    fn test_given_set_with_ranges_when_parsed_then_every_character_of_the_ranges_matches() {
+        let p = SetParser::new("a-f0-9");
+        assert_eq!(p.parse("dead0beef-cafe").unwrap().value(), "dead0beef");
+        assert_eq!(p.parse("xyz").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_set_with_leading_or_trailing_dash_when_parsed_then_the_dash_is_a_literal() {
+        let p = SetParser::new("-a-c");
+        assert_eq!(p.parse("a-b-c-d").unwrap().value(), "a-b-c-");
+        let p = SetParser::new("0-9-");
+        assert_eq!(p.parse("555-1234 x").unwrap().value(), "555-1234");
+    }
+
+    #[test]
+    fn test_given_negated_set_when_parsed_then_it_matches_until_a_character_of_the_set() {
+        let p = SetParser::new(r"^ \t");
+        assert_eq!(p.parse("user@host\tfoo").unwrap().value(), "user@host");
+        assert_eq!(p.parse(" foo").is_none(), true);
+        let p = SetParser::new("^");
+        assert_eq!(p.parse("^^a").unwrap().value(), "^^");
+    }
+
+    #[test]
+    fn test_given_set_with_named_classes_when_parsed_then_the_characters_of_the_classes_match() {
+        let p = SetParser::new("[:alnum:]_");
+        assert_eq!(p.parse("user_01 logged in").unwrap().value(), "user_01");
+        let p = SetParser::new("^[:space:]");
+        assert_eq!(p.parse("árvíztűrő\ttükörfúrógép").unwrap().value(), "árvíztűrő");
+        let p = SetParser::new("[:foo:]");
+        assert_eq!(p.parse("[foo]").unwrap().value(), "[foo]");
+    }
+
+    #[test]
+    fn test_given_set_with_multibyte_characters_when_parsed_then_the_length_is_counted_in_characters
+        () {
+        let mut p = SetParser::new("á-ű");
+        p.set_max_length(Some(3));
+        let res = p.parse("éöü!").unwrap();
+        assert_eq!(res.value(), "éöü");
+        assert_eq!(res.match_length(), "éöü".len());
+        assert_eq!(p.parse("éöüő").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_set_parser_when_the_next_match_is_requested_then_a_shorter_prefix_is_returned() {
+        let mut p = SetParser::new("a-zé");
+        p.set_min_length(Some(2));
+        let value = "abé1";
+        let first = p.parse(value).unwrap();
+        assert_eq!(first.value(), "abé");
+        let second = p.parse_next(value, first.match_length()).unwrap();
+        assert_eq!(second.value(), "ab");
+        assert_eq!(p.parse_next(value, second.match_length()).is_none(), true);
+    }
+
     use parsers::ObjectSafeHash;
 
     #[test]
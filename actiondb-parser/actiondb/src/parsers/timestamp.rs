@@ -0,0 +1,528 @@
+use std::borrow::Cow;
+use std::hash::{SipHasher, Hash, Hasher};
+use std::str::FromStr;
+
+use chrono::{Datelike, Timelike, Local, NaiveDateTime};
+use chrono::format::{self, Fixed, Item, Numeric, Parsed, StrftimeItems};
+
+use parsers::{Parser, ObjectSafeHash, ParseResult, ParserBase, ValueType, OptionalParameter};
+
+const MAX_EPOCH_MILLIS_LENGTH: usize = 16;
+// the timestamps without a year are checked against a leap year
+const LEAP_YEAR: i32 = 2000;
+const MONTH_NAMES: &'static [&'static str] = &["january", "february", "march", "april", "may",
+                                               "june", "july", "august", "september", "october",
+                                               "november", "december"];
+const WEEKDAY_NAMES: &'static [&'static str] = &["monday", "tuesday", "wednesday", "thursday",
+                                                 "friday", "saturday", "sunday"];
+const NORMALIZED_NAME_SUFFIX: &'static str = ".iso8601";
+
+#[derive(Clone, Debug, Hash)]
+enum TimestampFormat {
+    Strftime(String),
+    EpochMillis,
+}
+
+#[derive(Clone, Debug, Hash)]
+pub struct TimestampParser {
+    base: ParserBase,
+    // the preset or the strftime-style format string
+    format: String,
+    formats: Vec<TimestampFormat>,
+    normalize: bool,
+    normalized_name: Option<String>,
+}
+
+impl TimestampParser {
+    pub fn with_name<S: Into<String>>(name: S, format: &str) -> TimestampParser {
+        let mut parser = TimestampParser::new(format);
+        parser.set_name(Some(name.into()));
+        parser
+    }
+
+    pub fn new(format: &str) -> TimestampParser {
+        TimestampParser {
+            base: ParserBase::new(),
+            format: format.to_owned(),
+            formats: TimestampParser::create_formats_from_str(format),
+            normalize: false,
+            normalized_name: None,
+        }
+    }
+
+    pub fn set_format(&mut self, format: &str) {
+        self.format = format.to_owned();
+        self.formats = TimestampParser::create_formats_from_str(format);
+    }
+
+    // Returns whether `format` is a preset or a strftime-style format string
+    // which chrono understands. The parsers with other formats never match.
+    pub fn is_valid_format(format: &str) -> bool {
+        TimestampParser::create_formats_from_str(format).iter().all(|format| {
+            match *format {
+                TimestampFormat::Strftime(ref format) => {
+                    !format.is_empty() &&
+                    StrftimeItems::new(format).all(|item| {
+                        match item {
+                            Item::Error => false,
+                            _ => true,
+                        }
+                    })
+                }
+                TimestampFormat::EpochMillis => true,
+            }
+        })
+    }
+
+    // If it's set, the timestamp is also inserted in ISO8601 format with the
+    // `.iso8601` suffix appended to the parser's name.
+    pub fn set_normalize(&mut self, normalize: bool) {
+        self.normalize = normalize;
+        self.update_normalized_name();
+    }
+
+    fn update_normalized_name(&mut self) {
+        self.normalized_name = if self.normalize {
+            self.base.name().map(|name| format!("{}{}", name, NORMALIZED_NAME_SUFFIX))
+        } else {
+            None
+        };
+    }
+
+    // `format` is either the name of a preset or a strftime-style format
+    // string.
+    fn create_formats_from_str(format: &str) -> Vec<TimestampFormat> {
+        let strftime = |formats: &[&str]| {
+            formats.iter()
+                   .map(|format| TimestampFormat::Strftime(format.to_string()))
+                   .collect()
+        };
+
+        match format {
+            "bsd" => strftime(&["%b %e %H:%M:%S"]),
+            "rfc3339" => strftime(&["%+"]),
+            "iso8601" => strftime(&["%+", "%Y-%m-%dT%H:%M:%S%.f"]),
+            "clf" => strftime(&["%d/%b/%Y:%H:%M:%S %z"]),
+            "epoch" => strftime(&["%s"]),
+            "epoch_millis" => vec![TimestampFormat::EpochMillis],
+            _ => strftime(&[format]),
+        }
+    }
+
+    fn parse_with_format(value: &str, format: &TimestampFormat) -> Option<(usize, Parsed)> {
+        match *format {
+            TimestampFormat::Strftime(ref format) => {
+                TimestampParser::parse_with_strftime_format(value, format)
+            }
+            TimestampFormat::EpochMillis => TimestampParser::parse_epoch_millis(value),
+        }
+    }
+
+    // The parsing of chrono is greedy, so the length of the timestamp is
+    // determined by scanning the items of `format` once, then the prefix is
+    // parsed and validated.
+    fn parse_with_strftime_format(value: &str, format: &str) -> Option<(usize, Parsed)> {
+        let length = match TimestampParser::scan_items(value, format) {
+            Some(length) if length > 0 => length,
+            _ => return None,
+        };
+
+        let mut parsed = Parsed::new();
+        if format::parse(&mut parsed, &value[..length], StrftimeItems::new(format)).is_ok() &&
+           TimestampParser::is_valid(&mut parsed) {
+            Some((length, parsed))
+        } else {
+            None
+        }
+    }
+
+    // Returns the length of the prefix of `value` which chrono consumes with
+    // the items of `format`. The values of the fields are not checked.
+    fn scan_items(value: &str, format: &str) -> Option<usize> {
+        let mut rest = value;
+        for item in StrftimeItems::new(format) {
+            let scanned = match item {
+                Item::Literal(literal) if rest.starts_with(literal) => Some(&rest[literal.len()..]),
+                Item::Literal(_) | Item::Error => None,
+                Item::Space(_) => Some(rest.trim_left()),
+                Item::Numeric(numeric, _) => {
+                    TimestampParser::scan_numeric(rest.trim_left(), numeric)
+                }
+                Item::Fixed(fixed) => TimestampParser::scan_fixed(rest, fixed),
+            };
+            match scanned {
+                Some(scanned) => rest = scanned,
+                None => return None,
+            }
+        }
+        Some(value.len() - rest.len())
+    }
+
+    fn scan_numeric(value: &str, numeric: Numeric) -> Option<&str> {
+        let width = match numeric {
+            Numeric::Year | Numeric::IsoYear => {
+                // a signed year can be longer than 4 digits
+                if value.starts_with('+') || value.starts_with('-') {
+                    return scan_digits(&value[1..], 1, usize::max_value());
+                }
+                4
+            }
+            Numeric::NumDaysFromSun | Numeric::WeekdayFromMon => 1,
+            Numeric::Ordinal => 3,
+            Numeric::Nanosecond => 9,
+            Numeric::Timestamp => usize::max_value(),
+            _ => 2,
+        };
+        scan_digits(value, 1, width)
+    }
+
+    fn scan_fixed(value: &str, fixed: Fixed) -> Option<&str> {
+        match fixed {
+            Fixed::ShortMonthName => scan_name(value, MONTH_NAMES, false),
+            Fixed::LongMonthName => scan_name(value, MONTH_NAMES, true),
+            Fixed::ShortWeekdayName => scan_name(value, WEEKDAY_NAMES, false),
+            Fixed::LongWeekdayName => scan_name(value, WEEKDAY_NAMES, true),
+            Fixed::LowerAmPm | Fixed::UpperAmPm => {
+                if value.len() >= 2 && value.is_char_boundary(2) {
+                    Some(&value[2..])
+                } else {
+                    None
+                }
+            }
+            Fixed::Nanosecond | Fixed::Nanosecond3 | Fixed::Nanosecond6 | Fixed::Nanosecond9 => {
+                if value.starts_with('.') {
+                    scan_digits(&value[1..], 1, usize::max_value())
+                } else {
+                    Some(value)
+                }
+            }
+            Fixed::TimezoneOffset | Fixed::TimezoneOffsetColon => {
+                scan_offset(value.trim_left(), false)
+            }
+            Fixed::TimezoneOffsetZ | Fixed::TimezoneOffsetColonZ => {
+                scan_zulu_offset(value.trim_left(), false)
+            }
+            Fixed::RFC3339 => scan_rfc3339(value),
+            Fixed::TimezoneName | Fixed::RFC2822 => None,
+        }
+    }
+
+    fn parse_epoch_millis(value: &str) -> Option<(usize, Parsed)> {
+        let length = value.bytes()
+                          .take(MAX_EPOCH_MILLIS_LENGTH)
+                          .take_while(|c| *c >= b'0' && *c <= b'9')
+                          .count();
+
+        i64::from_str(&value[..length])
+            .ok()
+            .and_then(|millis| {
+                NaiveDateTime::from_timestamp_opt(millis / 1000, (millis % 1000) as u32 * 1_000_000)
+            })
+            .and_then(|datetime| TimestampParser::create_parsed_from_datetime(&datetime).ok())
+            .map(|parsed| (length, parsed))
+    }
+
+    // The timestamp field of Parsed can't hold sub-second precision, so the
+    // date and time fields are filled instead.
+    fn create_parsed_from_datetime(datetime: &NaiveDateTime) -> format::ParseResult<Parsed> {
+        let mut parsed = Parsed::new();
+        try!(parsed.set_year(datetime.year() as i64));
+        try!(parsed.set_ordinal(datetime.ordinal() as i64));
+        try!(parsed.set_hour(datetime.hour() as i64));
+        try!(parsed.set_minute(datetime.minute() as i64));
+        try!(parsed.set_second(datetime.second() as i64));
+        try!(parsed.set_nanosecond(datetime.nanosecond() as i64));
+        try!(parsed.set_offset(0));
+        Ok(parsed)
+    }
+
+    // BSD syslog timestamps don't contain the year, so they are checked
+    // against a leap year, which accepts Feb 29 independently of the current
+    // date.
+    fn is_valid(parsed: &mut Parsed) -> bool {
+        if parsed.timestamp.is_some() && parsed.offset.is_none() {
+            parsed.offset = Some(0);
+        }
+        let mut checked = parsed.clone();
+        if TimestampParser::has_no_year(&checked) {
+            checked.year = Some(LEAP_YEAR);
+        }
+        checked.to_naive_datetime_with_offset(checked.offset.unwrap_or(0)).is_ok()
+    }
+
+    fn has_no_year(parsed: &Parsed) -> bool {
+        parsed.year.is_none() && parsed.year_mod_100.is_none() && parsed.isoyear.is_none() &&
+        parsed.timestamp.is_none()
+    }
+
+    // The timestamps without a year are normalized with the current one.
+    fn to_iso8601(parsed: &Parsed) -> Option<String> {
+        let mut parsed = parsed.clone();
+        if TimestampParser::has_no_year(&parsed) {
+            parsed.year = Some(Local::now().year());
+        }
+        if parsed.offset.is_some() {
+            parsed.to_datetime().ok().map(|datetime| datetime.to_rfc3339())
+        } else {
+            parsed.to_naive_datetime_with_offset(0)
+                  .ok()
+                  .map(|datetime| datetime.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+        }
+    }
+}
+
+fn scan_digits(value: &str, min: usize, max: usize) -> Option<&str> {
+    let length = value.bytes().take(max).take_while(|c| *c >= b'0' && *c <= b'9').count();
+    if length >= min {
+        Some(&value[length..])
+    } else {
+        None
+    }
+}
+
+// Month and weekday names are matched case-insensitively by their first
+// three letters. The long names are consumed entirely if they are present.
+fn scan_name<'a>(value: &'a str, names: &[&str], long: bool) -> Option<&'a str> {
+    let name = match names.iter().find(|name| starts_with_ignoring_case(value, &name[..3])) {
+        Some(name) => name,
+        None => return None,
+    };
+    if long && starts_with_ignoring_case(value, name) {
+        Some(&value[name.len()..])
+    } else {
+        Some(&value[3..])
+    }
+}
+
+fn starts_with_ignoring_case(value: &str, prefix: &str) -> bool {
+    value.len() >= prefix.len() &&
+    value.bytes().zip(prefix.bytes()).all(|(c, expected)| c | 32 == expected)
+}
+
+fn scan_rfc3339(value: &str) -> Option<&str> {
+    let fields: &[(usize, Option<u8>)] = &[(4, Some(b'-')),
+                                           (2, Some(b'-')),
+                                           (2, Some(b'T')),
+                                           (2, Some(b':')),
+                                           (2, Some(b':')),
+                                           (2, None)];
+    let mut rest = value;
+    for &(width, separator) in fields {
+        rest = match scan_digits(rest, width, width) {
+            Some(rest) => rest,
+            None => return None,
+        };
+        if let Some(separator) = separator {
+            match rest.bytes().next() {
+                Some(c) if c == separator || (separator == b'T' && c == b't') => {
+                    rest = &rest[1..]
+                }
+                _ => return None,
+            }
+        }
+    }
+    if rest.starts_with('.') {
+        rest = match scan_digits(&rest[1..], 1, usize::max_value()) {
+            Some(rest) => rest,
+            None => return None,
+        };
+    }
+    scan_zulu_offset(rest, true)
+}
+
+fn scan_zulu_offset(value: &str, colon_required: bool) -> Option<&str> {
+    if value.starts_with('Z') || value.starts_with('z') {
+        Some(&value[1..])
+    } else {
+        scan_offset(value, colon_required)
+    }
+}
+
+// The hours and minutes of an offset are separated by a colon or they can be
+// separated by any number of colons and whitespace.
+fn scan_offset(value: &str, colon_required: bool) -> Option<&str> {
+    if !value.starts_with('+') && !value.starts_with('-') {
+        return None;
+    }
+    let mut rest = match scan_digits(&value[1..], 2, 2) {
+        Some(rest) => rest,
+        None => return None,
+    };
+    if colon_required {
+        if !rest.starts_with(':') {
+            return None;
+        }
+        rest = &rest[1..];
+    } else {
+        rest = rest.trim_left_matches(|c: char| c == ':' || c.is_whitespace());
+    }
+    scan_digits(rest, 2, 2)
+}
+
+impl Default for TimestampParser {
+    fn default() -> Self {
+        TimestampParser::new("iso8601")
+    }
+}
+
+impl Parser for TimestampParser {
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+        self.formats
+            .iter()
+            .filter_map(|format| TimestampParser::parse_with_format(value, format))
+            .next()
+            .map(|(length, parsed)| {
+                let mut result = ParseResult::new(self, &value[..length]);
+                if let Some(normalized_name) = self.normalized_name.as_ref() {
+                    if let Some(normalized) = TimestampParser::to_iso8601(&parsed) {
+                        result.push_additional_value(Cow::Borrowed(normalized_name),
+                                                     Cow::Owned(normalized));
+                    }
+                }
+                result
+            })
+    }
+
+    fn kind(&self) -> &'static str {
+        "TIMESTAMP"
+    }
+
+    fn specificity(&self) -> u8 {
+        5
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+        self.update_normalized_name();
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+
+    fn parameters(&self) -> Vec<OptionalParameter<'static>> {
+        vec![OptionalParameter::Str("format", self.format.clone()),
+             OptionalParameter::Bool("normalize", self.normalize)]
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::Timestamp
+    }
+}
+
+impl ObjectSafeHash for TimestampParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:timestamp".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{Parser, TimestampParser, ObjectSafeHash};
+
+    #[test]
+    fn test_given_timestamp_parser_with_bsd_preset_when_the_value_starts_with_a_bsd_timestamp_then_it_matches
+        () {
+        let parser = TimestampParser::with_name("date", "bsd");
+        let res = parser.parse("Jun 25 14:09:58 lobotomy sshd[26665]").unwrap();
+        assert_eq!(res.parser().name(), Some("date"));
+        assert_eq!(res.value(), "Jun 25 14:09:58");
+        assert_eq!(parser.parse("Jun  5 14:09:58 lobotomy").unwrap().value(), "Jun  5 14:09:58");
+        assert_eq!(res.additional_values().is_empty(), true);
+    }
+
+    #[test]
+    fn test_given_timestamp_parser_when_the_timestamp_is_invalid_then_it_does_not_match() {
+        let parser = TimestampParser::new("bsd");
+        assert_eq!(parser.parse("Jun 31 14:09:58").is_none(), true);
+        assert_eq!(parser.parse("Jun 25 25:09:58").is_none(), true);
+        assert_eq!(parser.parse("Foo 25 14:09:58").is_none(), true);
+        assert_eq!(parser.parse("").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_timestamp_parser_with_iso8601_preset_when_the_value_has_optional_offset_then_it_matches
+        () {
+        let parser = TimestampParser::new("iso8601");
+        assert_eq!(parser.parse("2015-07-23T14:39:38+02:00 foo").unwrap().value(),
+                   "2015-07-23T14:39:38+02:00");
+        assert_eq!(parser.parse("2015-07-23T14:39:38.123Z foo").unwrap().value(),
+                   "2015-07-23T14:39:38.123Z");
+        assert_eq!(parser.parse("2015-07-23T14:39:38 PADD").unwrap().value(),
+                   "2015-07-23T14:39:38");
+    }
+
+    #[test]
+    fn test_given_timestamp_parser_with_normalization_when_the_value_is_parsed_then_the_iso8601_value_is_added
+        () {
+        let mut parser = TimestampParser::with_name("date", "clf");
+        parser.set_normalize(true);
+        let res = parser.parse("10/Oct/2000:13:55:36 -0700] \"GET /\"").unwrap();
+        assert_eq!(res.value(), "10/Oct/2000:13:55:36 -0700");
+        assert_eq!(res.additional_values(),
+                   &[("date.iso8601".into(), "2000-10-10T13:55:36-07:00".into())]);
+    }
+
+    #[test]
+    fn test_given_timestamp_parser_with_epoch_presets_when_the_value_is_parsed_then_it_is_normalized_to_utc
+        () {
+        let mut parser = TimestampParser::with_name("date", "epoch");
+        parser.set_normalize(true);
+        let res = parser.parse("1437655178 foo").unwrap();
+        assert_eq!(res.value(), "1437655178");
+        assert_eq!(res.additional_values(),
+                   &[("date.iso8601".into(), "2015-07-23T12:39:38+00:00".into())]);
+
+        let mut parser = TimestampParser::with_name("date", "epoch_millis");
+        parser.set_normalize(true);
+        let res = parser.parse("1437655178123 foo").unwrap();
+        assert_eq!(res.value(), "1437655178123");
+        assert_eq!(res.additional_values(),
+                   &[("date.iso8601".into(), "2015-07-23T12:39:38.123+00:00".into())]);
+    }
+
+    #[test]
+    fn test_given_timestamp_parser_with_strftime_format_when_the_value_matches_then_it_is_parsed() {
+        let mut parser = TimestampParser::with_name("date", "%Y.%m.%d %H:%M");
+        parser.set_normalize(true);
+        let res = parser.parse("2016.02.29 10:20 foo").unwrap();
+        assert_eq!(res.value(), "2016.02.29 10:20");
+        assert_eq!(res.additional_values(),
+                   &[("date.iso8601".into(), "2016-02-29T10:20:00".into())]);
+        assert_eq!(parser.parse("2015.02.29 10:20").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_timestamp_parser_with_bsd_preset_when_the_date_is_feb_29_then_it_matches() {
+        let parser = TimestampParser::new("bsd");
+        assert_eq!(parser.parse("Feb 29 10:20:30 host").unwrap().value(), "Feb 29 10:20:30");
+        assert_eq!(parser.parse("Feb 30 10:20:30 host").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_timestamp_parser_when_a_field_has_extra_digits_then_they_are_not_consumed() {
+        let parser = TimestampParser::new("%Y.%m.%d %H:%M");
+        assert_eq!(parser.parse("2016.02.29 10:205").unwrap().value(), "2016.02.29 10:20");
+        assert_eq!(parser.parse("2016.02.29 10:99").is_none(), true);
+
+        let parser = TimestampParser::new("%d %B %Y %H:%M");
+        assert_eq!(parser.parse("05 september 2016 10:20 foo").unwrap().value(),
+                   "05 september 2016 10:20");
+        assert_eq!(parser.parse("05 Sep 2016 10:20").unwrap().value(), "05 Sep 2016 10:20");
+    }
+
+    #[test]
+    fn test_given_timestamp_parsers_when_they_have_different_formats_then_their_hashes_are_different
+        () {
+        let p1 = TimestampParser::with_name("date", "bsd");
+        let p2 = TimestampParser::with_name("date", "rfc3339");
+        assert_eq!(p1.hash_os() == p2.hash_os(), false);
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+use std::str::FromStr;
+
+// The type of the values extracted by a parser. It can be used by the
+// consumers of the parse results to convert the values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ValueType {
+    String,
+    Int,
+    Float,
+    Ip,
+    Timestamp,
+}
+
+impl ValueType {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ValueType::String => "string",
+            ValueType::Int => "int",
+            ValueType::Float => "float",
+            ValueType::Ip => "ip",
+            ValueType::Timestamp => "timestamp",
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        *self == ValueType::Int || *self == ValueType::Float
+    }
+}
+
+impl Default for ValueType {
+    fn default() -> ValueType {
+        ValueType::String
+    }
+}
+
+impl FromStr for ValueType {
+    type Err = ();
+
+    fn from_str(value_type: &str) -> Result<ValueType, ()> {
+        match value_type {
+            "string" => Ok(ValueType::String),
+            "int" => Ok(ValueType::Int),
+            "float" => Ok(ValueType::Float),
+            "ip" => Ok(ValueType::Ip),
+            "timestamp" => Ok(ValueType::Timestamp),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ValueType;
+
+    #[test]
+    fn test_given_value_type_when_it_is_converted_to_string_then_it_can_be_converted_back() {
+        for value_type in &[ValueType::String,
+                            ValueType::Int,
+                            ValueType::Float,
+                            ValueType::Ip,
+                            ValueType::Timestamp] {
+            assert_eq!(Ok(*value_type), value_type.as_str().parse::<ValueType>());
+        }
+        assert_eq!(Err(()), "integer".parse::<ValueType>());
+    }
+}
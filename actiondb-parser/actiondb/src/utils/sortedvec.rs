@@ -10,6 +10,11 @@ impl <T: Ord> SortedVec<T> {
         SortedVec { array: vec![] }
     }
 
+    pub fn from_vec(mut array: Vec<T>) -> SortedVec<T> {
+        array.sort();
+        SortedVec { array: array }
+    }
+
     pub fn push(&mut self, value: T) {
         self.array.push(value);
         self.array.sort();
@@ -0,0 +1,136 @@
+use actiondb::matcher::{FromPatternSource, MatcherFactory, PatternLoader};
+use actiondb::matcher::pattern::file::{grok, patterndb, Issue, PatternFile};
+use actiondb::matcher::trie::factory::TrieMatcherFactory;
+use regex::Regex;
+
+use std::collections::BTreeMap;
+
+// The pattern files whose test messages are checked after the conversion.
+const PATTERN_FILES: &'static [&'static str] = &["tests/matcher/ssh_ok.json",
+                                                 "tests/matcher/ssh_groups_cover_all_variants.json",
+                                                 "tests/matcher/number_values_are_checked.json"];
+
+// Simplified stand-ins for the standard Grok patterns used by the exported
+// expressions. They are not the real definitions (e.g. the real IPV4 and
+// IPV6 are stricter), they only match the addresses of the test messages.
+const GROK_PATTERNS: &'static [(&'static str, &'static str)] =
+    &[("DATA", ".*?"),
+      ("GREEDYDATA", ".*"),
+      ("IPV4", r"[0-9]{1,3}(?:\.[0-9]{1,3}){3}"),
+      ("IPV6", "[0-9A-Fa-f]*:[0-9A-Fa-f:.]*"),
+      ("IP", "%{IPV6}|%{IPV4}")];
+
+#[test]
+fn test_given_pattern_files_when_they_are_exported_to_patterndb_and_imported_back_then_the_test_messages_match
+    () {
+    for file_name in PATTERN_FILES {
+        let file = PatternLoader::load_file(file_name).unwrap();
+        let test_message_count = count_test_messages(&file);
+        let (xml, issues) = patterndb::export(file.patterns(), "test");
+        assert!(issues.iter().all(|issue| {
+            match *issue {
+                Issue::Ignored{..} => true,
+                Issue::Skipped{..} => false,
+            }
+        }));
+
+        let (imported, _) = patterndb::import(xml.as_bytes()).unwrap();
+        assert_eq!(count_test_messages(&imported), test_message_count);
+        assert_test_messages_match::<TrieMatcherFactory>(imported);
+    }
+}
+
+#[test]
+fn test_given_pattern_files_when_they_are_exported_to_grok_then_the_expressions_match_the_test_messages
+    () {
+    for file_name in PATTERN_FILES {
+        let file = PatternLoader::load_file(file_name).unwrap();
+        let (grok, issues) = grok::export(file.patterns());
+        assert!(issues.iter().all(|issue| {
+            match *issue {
+                Issue::Ignored{..} => true,
+                Issue::Skipped{..} => false,
+            }
+        }));
+
+        let definitions = parse_grok_file(&grok);
+        for pattern in file.patterns() {
+            let expression = definitions.iter()
+                                        .find(|&(name, _)| {
+                                            name.ends_with(&pattern.uuid()
+                                                                   .simple()
+                                                                   .to_string()
+                                                                   .to_uppercase())
+                                        })
+                                        .map(|(_, expression)| expression)
+                                        .unwrap();
+            let mut names = Vec::new();
+            let regex = Regex::new(&expand_grok(expression, &definitions, &mut names)).unwrap();
+
+            for test_message in pattern.test_messages().unwrap_or(&[]) {
+                let captures = regex.captures(test_message.message()).expect(file_name);
+                // the values of the pattern are not exported
+                let values = test_message.values().iter().filter(|&(key, _)| {
+                    !pattern.values().map_or(false, |values| values.contains_key(key))
+                });
+                for (key, value) in values {
+                    let index = names.iter().position(|name| name == key).unwrap();
+                    assert_eq!(captures.name(&format!("g{}", index)), Some(&value[..]));
+                }
+            }
+        }
+    }
+}
+
+fn count_test_messages(file: &PatternFile) -> usize {
+    file.patterns().iter().fold(0, |count, pattern| count + pattern.test_message_count())
+}
+
+fn assert_test_messages_match<F: MatcherFactory>(file: PatternFile) {
+    assert!(F::Matcher::from_source::<F>(&mut file.into_iter()).is_ok());
+}
+
+fn parse_grok_file(grok: &str) -> BTreeMap<String, String> {
+    grok.lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut parts = line.splitn(2, ' ');
+            (parts.next().unwrap().to_owned(), parts.next().unwrap_or("").to_owned())
+        })
+        .collect()
+}
+
+// Replaces the `%{PATTERN:name}` references with the patterns' regexes. The
+// named references become capture groups called `g<index of the name>`.
+fn expand_grok(expression: &str,
+               definitions: &BTreeMap<String, String>,
+               names: &mut Vec<String>)
+               -> String {
+    let mut expanded = String::new();
+    let mut rest = expression;
+    while let Some(start) = rest.find("%{") {
+        expanded.push_str(&rest[..start]);
+        let end = rest[start..].find('}').unwrap() + start;
+        let mut reference = rest[start + 2..end].splitn(2, ':');
+        let pattern = reference.next().unwrap();
+        let regex = definitions.get(pattern)
+                               .map(|regex| &regex[..])
+                               .or_else(|| {
+                                   GROK_PATTERNS.iter()
+                                                .find(|&&(name, _)| name == pattern)
+                                                .map(|&(_, regex)| regex)
+                               })
+                               .unwrap();
+        let regex = expand_grok(regex, definitions, names);
+        match reference.next() {
+            Some(name) => {
+                expanded.push_str(&format!("(?P<g{}>{})", names.len(), regex));
+                names.push(name.to_owned());
+            }
+            None => expanded.push_str(&format!("(?:{})", regex)),
+        }
+        rest = &rest[end + 1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
@@ -1,7 +1,16 @@
-use actiondb::matcher::PatternLoader;
+use actiondb::Matcher;
+use actiondb::matcher::{PatternLoader, MatcherSuite};
 use actiondb::matcher::BuildError;
+use actiondb::matcher::cache::{Cacheable, CacheError};
+use actiondb::matcher::trie::TrieMatcherSuite;
+use actiondb::matcher::trie::factory::TrieMatcherFactory;
+use actiondb::matcher::suffix_array::{SuffixArrayMatcherFactory, SuffixArrayMatcherSuite};
 
 use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::thread;
 
 const DIR_PREFIX: &'static str = "tests/file";
 
@@ -55,3 +64,268 @@ fn test_given_not_existing_yaml_pattern_file_when_it_is_opened_we_get_io_error()
                    .err()
                    .expect("Successfully opened a non-existing YAML file");
 }
+
+#[test]
+fn test_given_directory_when_it_is_loaded_then_the_pattern_files_are_loaded_recursively() {
+    let matcher = PatternLoader::from_file::<SuffixArrayMatcherFactory>("tests/file/pattern_dir")
+                      .expect("Failed to load the pattern files from a directory");
+    assert!(matcher.parse("Accepted password for joe from ::1").is_some());
+    assert!(matcher.parse("usb 2-1: new high-speed USB device number 4").is_some());
+}
+
+#[test]
+fn test_given_pattern_file_when_it_includes_other_files_then_they_are_loaded_too() {
+    let file = PatternLoader::load_file("tests/file/includes.yaml")
+                   .expect("Failed to load a pattern file with includes");
+    assert_eq!(file.includes(),
+               &["pattern_dir/ssh.json".to_owned(), "pattern_dir/kernel".to_owned()]);
+
+    let matcher = PatternLoader::from_paths::<TrieMatcherFactory, _>(&["tests/file/includes.yaml"])
+                      .expect("Failed to load the included pattern files");
+    assert!(matcher.parse("(root) CMD (true)").is_some());
+    assert!(matcher.parse("Accepted password for joe from ::1").is_some());
+    assert!(matcher.parse("usb 2-1: new high-speed USB device number 4").is_some());
+}
+
+#[test]
+fn test_given_pattern_files_when_they_contain_the_same_uuid_then_we_get_an_error_with_both_locations
+    () {
+    let error = PatternLoader::from_file::<SuffixArrayMatcherFactory>("tests/file/duplicate_uuids")
+                    .err()
+                    .expect("Successfully loaded pattern files with duplicate UUIDs");
+
+    match error {
+        BuildError::InPattern(ref location, ref error) => {
+            assert_eq!(location.file(), "tests/file/duplicate_uuids/second.yaml");
+            assert_eq!(location.index(), 1);
+            match **error {
+                BuildError::DuplicateUuid(_, ref first) => {
+                    assert_eq!(first.file(), "tests/file/duplicate_uuids/first.yaml");
+                    assert_eq!(first.index(), 0);
+                }
+                _ => panic!("Unexpected error: {}", error),
+            }
+        }
+        _ => panic!("Unexpected error: {}", error),
+    }
+
+    let matcher = PatternLoader::from_file_ignore_errors::<SuffixArrayMatcherFactory>(
+        "tests/file/duplicate_uuids")
+                      .expect("Failed to load pattern files while ignoring their errors");
+    assert!(matcher.parse("service sshd started").is_some());
+    assert!(matcher.parse("service sshd failed").is_none());
+}
+
+#[test]
+fn test_given_pattern_file_when_a_test_message_fails_while_ignoring_errors_then_its_pattern_is_skipped
+    () {
+    let pattern_file_path = "tests/file/failing_test_message.yaml";
+    assert!(PatternLoader::from_file::<SuffixArrayMatcherFactory>(pattern_file_path).is_err());
+
+    let matcher = PatternLoader::from_file_ignore_errors::<SuffixArrayMatcherFactory>(
+        pattern_file_path)
+                      .expect("Failed to load a pattern file while ignoring its errors");
+    assert!(matcher.parse("service sshd started").is_none());
+    assert!(matcher.parse("service sshd stopped").is_some());
+
+    let matcher = PatternLoader::from_file_ignore_errors::<TrieMatcherFactory>(pattern_file_path)
+                      .expect("Failed to load a pattern file while ignoring its errors");
+    assert!(matcher.parse("service sshd started").is_none());
+    assert!(matcher.parse("service sshd stopped").is_some());
+}
+
+#[test]
+fn test_given_pattern_file_when_it_contains_the_same_uuid_twice_then_the_later_one_is_reported() {
+    let pattern_file_path = "tests/file/duplicate_uuids_in_a_file";
+    let error = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path)
+                    .err()
+                    .expect("Successfully loaded a pattern file with duplicate UUIDs");
+
+    match error {
+        BuildError::InPattern(ref location, ref error) => {
+            assert_eq!(location.index(), 1);
+            match **error {
+                BuildError::DuplicateUuid(_, ref first) => assert_eq!(first.index(), 0),
+                _ => panic!("Unexpected error: {}", error),
+            }
+        }
+        _ => panic!("Unexpected error: {}", error),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_given_pattern_directory_when_it_contains_a_symlink_loop_then_it_is_loaded_once() {
+    use std::os::unix::fs::symlink;
+
+    let directory = create_temporary_directory("symlink-loop");
+    fs::copy("tests/file/pattern_dir/ssh.json", directory.join("ssh.json"))
+        .expect("Failed to copy a pattern file");
+    symlink(&directory, directory.join("loop")).expect("Failed to create a symlink");
+
+    let matcher = PatternLoader::from_file::<TrieMatcherFactory>(directory.to_str().unwrap())
+                      .expect("Failed to load a pattern directory with a symlink loop");
+    assert!(matcher.parse("Accepted password for joe from ::1").is_some());
+}
+
+// Every test uses its own directory, so they can run in parallel.
+fn create_temporary_directory(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("actiondb-test-{}", name));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).expect("Failed to create a temporary directory");
+    path
+}
+
+fn assert_compiled_matcher_parses_like_the_original<MS>(name: &str)
+    where MS: MatcherSuite,
+          MS::Matcher: Cacheable
+{
+    let cache_path = create_temporary_directory(name).join("patterns.adbc");
+    let cache_path = cache_path.to_str().unwrap();
+    let original = PatternLoader::compile::<MS, _>(&["tests/file/includes.yaml"], cache_path)
+                       .expect("Failed to compile the pattern files");
+    let compiled = PatternLoader::from_cache::<MS>(cache_path)
+                       .expect("Failed to load the compiled patterns");
+
+    for message in &["(root) CMD (true)",
+                     "Accepted password for joe from ::1",
+                     "usb 2-1: new high-speed USB device number 4",
+                     "unknown message"] {
+        let expected = original.parse(message);
+        let result = compiled.parse(message);
+        assert_eq!(result.as_ref().map(|result| result.pattern().uuid()),
+                   expected.as_ref().map(|result| result.pattern().uuid()));
+        assert_eq!(result.as_ref().map(|result| result.values()),
+                   expected.as_ref().map(|result| result.values()));
+        assert_eq!(result.as_ref().map(|result| result.pattern().test_message_count()),
+                   expected.as_ref().map(|result| result.pattern().test_message_count()));
+    }
+}
+
+#[test]
+fn test_given_compiled_pattern_files_when_the_cache_is_loaded_then_it_parses_like_the_original_matcher
+    () {
+    assert_compiled_matcher_parses_like_the_original::<TrieMatcherSuite>("compiled-trie");
+    assert_compiled_matcher_parses_like_the_original::<SuffixArrayMatcherSuite>("compiled-sa");
+}
+
+#[test]
+fn test_given_compiled_pattern_file_when_the_pattern_file_changes_then_the_cache_is_invalidated() {
+    let directory = create_temporary_directory("stale-cache");
+    let pattern_path = directory.join("patterns.yaml");
+    let pattern_path = pattern_path.to_str().unwrap();
+    let cache_path = directory.join("patterns.adbc");
+    let cache_path = cache_path.to_str().unwrap();
+    fs::copy("tests/file/ssh_ok.yaml", pattern_path).expect("Failed to copy the pattern file");
+
+    PatternLoader::compile::<SuffixArrayMatcherSuite, _>(&[pattern_path], cache_path)
+        .expect("Failed to compile the pattern file");
+    assert!(PatternLoader::from_cache::<SuffixArrayMatcherSuite>(cache_path).is_ok());
+    assert!(PatternLoader::from_cache::<TrieMatcherSuite>(cache_path).is_err());
+
+    let mut file = File::create(pattern_path).expect("Failed to open the pattern file");
+    file.write_all(b"patterns:\n  - uuid: \"5b8e2d1a-3c4f-4e6a-9b7d-1f2e3d4c5b04\"\n    \
+                     pattern: \"service %{GREEDY:service} started\"\n")
+        .expect("Failed to write the pattern file");
+    match PatternLoader::from_cache::<SuffixArrayMatcherSuite>(cache_path) {
+        Err(BuildError::Cache(CacheError::Stale)) => (),
+        Err(error) => panic!("Unexpected error: {}", error),
+        Ok(_) => panic!("A stale cache was loaded"),
+    }
+
+    let matcher = PatternLoader::from_file_cached::<SuffixArrayMatcherSuite>(pattern_path,
+                                                                             cache_path)
+                      .expect("Failed to load the changed pattern file");
+    assert!(matcher.parse("service sshd started").is_some());
+    let matcher = PatternLoader::from_cache::<SuffixArrayMatcherSuite>(cache_path)
+                      .expect("The cache wasn't written again");
+    assert!(matcher.parse("service sshd started").is_some());
+}
+
+#[test]
+fn test_given_pattern_files_when_they_are_compiled_concurrently_then_the_writers_do_not_interfere
+    () {
+    let directory = create_temporary_directory("concurrent-cache");
+    let cache_path = directory.join("patterns.adbc");
+
+    let writers = (0..4)
+                      .map(|_| {
+                          let cache_path = cache_path.to_str().unwrap().to_owned();
+                          thread::spawn(move || {
+                              PatternLoader::compile::<SuffixArrayMatcherSuite, _>(
+                                  &["tests/file/includes.yaml"], &cache_path)
+                                  .is_ok()
+                          })
+                      })
+                      .collect::<Vec<_>>();
+    for writer in writers {
+        assert!(writer.join().expect("A cache writer panicked"));
+    }
+
+    let entries = fs::read_dir(&directory).expect("Failed to list the cache directory").count();
+    assert_eq!(entries, 1);
+    assert!(PatternLoader::from_cache::<SuffixArrayMatcherSuite>(cache_path.to_str().unwrap())
+                .is_ok());
+}
+
+#[test]
+fn test_given_loaded_pattern_files_when_one_of_them_is_removed_then_the_sources_are_changed() {
+    let directory = create_temporary_directory("watched-sources");
+    let pattern_path = directory.join("ssh.yaml");
+    fs::copy("tests/file/ssh_ok.yaml", &pattern_path).expect("Failed to copy the pattern file");
+
+    let directory = directory.to_str().unwrap();
+    let (matcher, sources) =
+        PatternLoader::from_file_with_sources::<SuffixArrayMatcherFactory>(directory, false)
+            .expect("Failed to load the pattern directory");
+    assert!(matcher.parse("Jun 25 14:09:58 lobotomy sshd[26665]: Accepted publickey for zts from \
+                           1.2.3.4 port 22 ssh2")
+                   .is_some());
+    assert_eq!(sources.files().len(), 1);
+    let modified = sources.modification_times();
+    assert!(modified.iter().all(|time| time.is_some()));
+    assert_eq!(sources.modification_times(), modified);
+
+    fs::remove_file(&pattern_path).expect("Failed to remove the pattern file");
+    assert!(sources.modification_times() != modified);
+}
+
+#[test]
+fn test_given_invalid_pattern_files_when_they_are_cached_while_ignoring_errors_then_the_valid_patterns_are_loaded
+    () {
+    let cache_path = create_temporary_directory("ignored-errors").join("patterns.adbc");
+    let cache_path = cache_path.to_str().unwrap();
+    let pattern_path = "tests/file/duplicate_uuids";
+
+    assert!(PatternLoader::from_file_cached_with_sources::<SuffixArrayMatcherSuite>(pattern_path,
+                                                                                    cache_path,
+                                                                                    false)
+                .is_err());
+    let (matcher, _) =
+        PatternLoader::from_file_cached_with_sources::<SuffixArrayMatcherSuite>(pattern_path,
+                                                                                cache_path,
+                                                                                true)
+            .expect("Failed to load pattern files while ignoring their errors");
+    assert!(matcher.parse("service sshd started").is_some());
+    assert!(matcher.parse("service sshd failed").is_none());
+}
+
+#[test]
+fn test_given_cache_written_while_ignoring_errors_when_strict_mode_is_set_then_it_is_not_used
+    () {
+    let cache_path = create_temporary_directory("strict-after-ignoring").join("patterns.adbc");
+    let cache_path = cache_path.to_str().unwrap();
+    let pattern_path = "tests/file/duplicate_uuids";
+
+    assert!(PatternLoader::from_file_cached_with_sources::<SuffixArrayMatcherSuite>(pattern_path,
+                                                                                    cache_path,
+                                                                                    true)
+                .is_ok());
+    match PatternLoader::from_file_cached_with_sources::<SuffixArrayMatcherSuite>(pattern_path,
+                                                                                  cache_path,
+                                                                                  false) {
+        Err(BuildError::InPattern(_, _)) => (),
+        Err(error) => panic!("Unexpected error: {}", error),
+        Ok(_) => panic!("A cache written while ignoring errors was loaded in strict mode"),
+    }
+}
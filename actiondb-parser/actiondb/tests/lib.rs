@@ -1,4 +1,6 @@
 extern crate actiondb;
+extern crate regex;
 
+mod export;
 mod file;
 mod matcher;
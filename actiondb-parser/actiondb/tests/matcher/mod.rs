@@ -1,6 +1,7 @@
 extern crate actiondb;
 
-use actiondb::matcher::PatternLoader;
+use actiondb::matcher::{Matcher, PatternLoader};
+use actiondb::matcher::suffix_array::SuffixArrayMatcherFactory;
 use actiondb::matcher::trie::factory::TrieMatcherFactory;
 
 #[test]
@@ -90,3 +91,111 @@ fn test_given_json_file_when_we_check_the_test_messages_then_the_resulting_patte
                    .expect("The UUID of the resulting pattern should be the same as the freshly \
                             inserted one");
 }
+
+#[test]
+fn test_given_json_file_when_a_pattern_contains_number_parsers_then_out_of_range_values_do_not_match
+    () {
+    let pattern_file_path = "tests/matcher/number_values_are_checked.json";
+    let matcher = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path)
+                      .ok()
+                      .expect("Failed to create a Matcher from a pattern file with NUMBER parsers");
+    assert_eq!(matcher.parse("request served in 12 ms, load: 0.5").is_some(), true);
+    assert_eq!(matcher.parse("request served in 12 ms, load: 2.5").is_none(), true);
+    assert_eq!(matcher.parse("request served in -12 ms, load: 0.5").is_none(), true);
+}
+
+#[test]
+fn test_given_json_file_when_a_pattern_contains_qstring_parsers_then_the_unquoted_values_are_checked
+    () {
+    let pattern_file_path = "tests/matcher/qstring_values_are_unquoted.json";
+    let matcher = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path);
+    println!("matcher: {:?}", &matcher);
+    let _ = matcher.ok().expect("Failed to create a Matcher from a pattern file with QSTRING parsers");
+}
+
+#[test]
+fn test_given_json_file_when_a_pattern_contains_timestamp_parsers_then_the_normalized_values_are_checked
+    () {
+    let pattern_file_path = "tests/matcher/timestamps_are_normalized.json";
+    let matcher = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path);
+    println!("matcher: {:?}", &matcher);
+    let _ = matcher.ok().expect("Failed to create a Matcher from a pattern file with TIMESTAMP parsers");
+}
+
+#[test]
+fn test_given_json_file_when_a_pattern_contains_a_kv_parser_then_the_extracted_pairs_are_checked() {
+    let pattern_file_path = "tests/matcher/kv_pairs_are_extracted.json";
+    let matcher = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path);
+    println!("matcher: {:?}", &matcher);
+    let _ = matcher.ok().expect("Failed to create a Matcher from a pattern file with a KV parser");
+}
+
+#[test]
+fn test_given_json_file_when_a_pattern_contains_groups_then_every_variant_is_matched_by_the_same_pattern
+    () {
+    let pattern_file_path = "tests/matcher/ssh_groups_cover_all_variants.json";
+    let matcher = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path);
+    println!("matcher: {:?}", &matcher);
+    let _ = matcher.ok().expect("Failed to create a Matcher from a pattern file with groups");
+}
+
+#[test]
+fn test_given_json_file_when_a_greedy_parser_is_followed_by_a_group_then_it_ends_at_the_group() {
+    let pattern_file_path = "tests/matcher/greedy_before_group_ends_at_the_group.json";
+    let matcher = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path);
+    let _ = matcher.ok().expect("Failed to create a Matcher when GREEDY is followed by a group");
+    let matcher = PatternLoader::from_file::<SuffixArrayMatcherFactory>(pattern_file_path);
+    let _ = matcher.ok().expect("Failed to create a Matcher when GREEDY is followed by a group");
+}
+
+#[test]
+fn test_given_json_file_when_test_messages_contain_typed_values_then_their_types_are_checked() {
+    let pattern_file_path = "tests/matcher/typed_values_are_checked.json";
+    let matcher = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path);
+    println!("matcher: {:?}", &matcher);
+    let _ = matcher.ok().expect("Failed to create a Matcher from a pattern file with typed values");
+}
+
+#[test]
+fn test_given_json_file_when_the_type_of_a_value_is_not_the_expected_one_then_we_fail() {
+    let pattern_file_path = "tests/matcher/typed_values_with_wrong_type_fail.json";
+    let matcher = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path);
+    println!("matcher: {:?}", &matcher);
+    let _ = matcher.err().expect("A value had a wrong type but we created the Matcher object");
+}
+
+fn collect_every_match(matcher: &Matcher, message: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut matches = matcher.parse_all(message)
+                             .iter()
+                             .map(|result| {
+                                 let values = result.values()
+                                                    .iter()
+                                                    .map(|(key, value)| {
+                                                        (key.to_string(), value.to_string())
+                                                    })
+                                                    .collect();
+                                 (result.pattern().uuid().hyphenated().to_string(), values)
+                             })
+                             .collect::<Vec<(String, Vec<(String, String)>)>>();
+    matches.sort();
+    matches
+}
+
+#[test]
+fn test_given_overlapping_patterns_when_every_match_is_requested_then_both_suites_agree
+    () {
+    let pattern_file_path = "tests/matcher/overlapping_patterns.json";
+    let trie = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path)
+                   .expect("Failed to create a trie from overlapping patterns");
+    let suffix_table = PatternLoader::from_file::<SuffixArrayMatcherFactory>(pattern_file_path)
+                           .expect("Failed to create a suffix table from overlapping patterns");
+
+    for &(message, expected_count) in &[("user loggedin", 4),
+                                        ("user 42 logged in", 4),
+                                        ("user 43 logged in", 3),
+                                        ("user nobody", 1)] {
+        let matches = collect_every_match(&trie, message);
+        assert_eq!(expected_count, matches.len());
+        assert_eq!(matches, collect_every_match(&suffix_table, message));
+    }
+}
@@ -1,7 +1,6 @@
 #![feature(test)]
 extern crate test;
 extern crate actiondb_parser;
-extern crate actiondb;
 extern crate syslog_ng_common;
 
 use test::Bencher;
@@ -10,7 +9,6 @@ use syslog_ng_common::{LogMessage, Parser, ParserBuilder};
 use syslog_ng_common::{syslog_ng_global_init, SYSLOG_NG_INITIALIZED, GlobalConfig};
 use syslog_ng_common::mock::MockPipe;
 use actiondb_parser::ActiondbParserBuilder;
-use actiondb::matcher::suffix_array::{SuffixArrayMatcherSuite};
 
 #[bench]
 fn bench_parse(b: &mut Bencher) {
@@ -21,8 +19,9 @@ fn bench_parse(b: &mut Bencher) {
     let cfg = GlobalConfig::new(0x0308);
     let pattern_file_path = "benches/loggen.json";
     let mut pipe = MockPipe::new();
-    let mut builder: ActiondbParserBuilder<SuffixArrayMatcherSuite> = ParserBuilder::new(cfg);
-    builder.set_pattern_file(pattern_file_path);
+    let mut builder: ActiondbParserBuilder = ParserBuilder::new(cfg);
+    builder.option("matcher".to_owned(), "suffix-array".to_owned()).unwrap();
+    builder.set_pattern_file(pattern_file_path).unwrap();
     let mut parser = ParserBuilder::build(builder).unwrap();
     let mut logmsg = LogMessage::new();
     let input = "seq: 0000000000, thread: 0000, runid: 1456947132, stamp: 2016-03-02T20:32:12 PAD";
@@ -5,24 +5,56 @@ extern crate log;
 
 mod logger;
 mod parse;
+mod lint;
+mod explain;
+mod output;
+mod stats;
+mod suggest;
+mod import;
+mod export;
 
 use clap::{Arg, App, SubCommand, ArgMatches};
 use actiondb::matcher::PatternLoader;
 use actiondb::matcher::suffix_array::SuffixArrayMatcherSuite;
-use actiondb::matcher::MatcherSuite;
+use actiondb::matcher::trie::TrieMatcherSuite;
+use actiondb::matcher::{MatcherSuite, MatcherKind};
+use actiondb::matcher::lint::PatternTree;
+use actiondb::matcher::cache::Cacheable;
+use actiondb::matcher::suggest::Suggester;
 use log::LogLevelFilter;
-use self::logger::StdoutLogger;
+use self::logger::StderrLogger;
+use self::output::{OutputFormat, PatternFileFormat};
+use self::export::ExportFormat;
 
 const AUTHOR: &'static str = "Tibor Benke <tibor.benke@balabit.com>";
 const APPNAME: &'static str = "adbtool";
 const DEBUG: &'static str = "debug";
+const MATCHER: &'static str = "matcher";
 
 const PATTERN_FILE: &'static str = "pattern file";
 const VALIDATE: &'static str = "validate";
 const PARSE: &'static str = "parse";
+const LINT: &'static str = "lint";
+const EXPLAIN: &'static str = "explain";
+const COMPILE: &'static str = "compile";
+const STATS: &'static str = "stats";
+const SUGGEST: &'static str = "suggest";
+const IMPORT_PATTERNDB: &'static str = "import-patterndb";
+const EXPORT: &'static str = "export";
 const IGNORE_ERRORS: &'static str = "ignore-errors";
 const INPUT_FILE: &'static str = "input file";
 const OUTPUT_FILE: &'static str = "output file";
+const LINE: &'static str = "line";
+const CACHE_FILE: &'static str = "cache file";
+const FORMAT: &'static str = "format";
+const TOP: &'static str = "top";
+const DEFAULT_TOP: usize = 10;
+const SIMILARITY: &'static str = "similarity";
+const SAMPLES: &'static str = "samples";
+const PATTERNDB_FILE: &'static str = "patterndb file";
+const TO: &'static str = "to";
+const RULESET: &'static str = "ruleset";
+const DEFAULT_RULESET: &'static str = "actiondb";
 
 fn build_command_line_argument_parser<'a, 'b>() -> App<'a, 'b> {
     let version = env!("CARGO_PKG_VERSION");
@@ -33,6 +65,12 @@ fn build_command_line_argument_parser<'a, 'b>() -> App<'a, 'b> {
         .arg(Arg::with_name(DEBUG)
                  .short("d")
                  .help("Enable debug messages"))
+        .arg(Arg::with_name(MATCHER)
+                 .short("m")
+                 .long(MATCHER)
+                 .takes_value(true)
+                 .possible_values(MatcherKind::names())
+                 .help("The matcher implementation (suffix-array by default)"))
         .subcommand(SubCommand::with_name(VALIDATE)
                         .about("validates pattern file")
                         .version(version)
@@ -40,7 +78,7 @@ fn build_command_line_argument_parser<'a, 'b>() -> App<'a, 'b> {
                         .arg(Arg::with_name(PATTERN_FILE)
                                  .required(true)
                                  .index(1)
-                                 .help("The pattern file to be validated"))
+                                 .help("The pattern file or directory to be validated"))
                         .arg(Arg::with_name(IGNORE_ERRORS)
                                  .short("i")
                                  .help("Don't stop at the first test message error")))
@@ -51,15 +89,147 @@ fn build_command_line_argument_parser<'a, 'b>() -> App<'a, 'b> {
                         .arg(Arg::with_name(PATTERN_FILE)
                                  .required(true)
                                  .index(1)
-                                 .help("The pattern file which contains predefined patterns"))
+                                 .help("The pattern file or directory with the patterns"))
                         .arg(Arg::with_name(INPUT_FILE)
                                  .required(true)
                                  .index(2)
-                                 .help("The input file to be parsed"))
+                                 .help("The input file to be parsed, - for the standard input"))
                         .arg(Arg::with_name(OUTPUT_FILE)
-                                 .required(true)
                                  .index(3)
-                                 .help("The output file where the results are written")))
+                                 .help("The output file where the results are written, - (the \
+                                        default) for the standard output"))
+                        .arg(Arg::with_name(FORMAT)
+                                 .short("f")
+                                 .long(FORMAT)
+                                 .takes_value(true)
+                                 .possible_values(OutputFormat::names())
+                                 .help("The format of the results (json by default)")))
+        .subcommand(SubCommand::with_name(LINT)
+                        .about("finds ambiguous and unreachable patterns in a pattern file")
+                        .version(version)
+                        .author(AUTHOR)
+                        .arg(Arg::with_name(PATTERN_FILE)
+                                 .required(true)
+                                 .index(1)
+                                 .help("The pattern file or directory to be checked")))
+        .subcommand(SubCommand::with_name(EXPLAIN)
+                        .about("shows why a line matches or doesn't match the patterns")
+                        .version(version)
+                        .author(AUTHOR)
+                        .arg(Arg::with_name(PATTERN_FILE)
+                                 .required(true)
+                                 .index(1)
+                                 .help("The pattern file or directory with the patterns"))
+                        .arg(Arg::with_name(LINE)
+                                 .required(true)
+                                 .index(2)
+                                 .help("The line to be explained")))
+        .subcommand(SubCommand::with_name(COMPILE)
+                        .about("compiles the patterns into a cache file which loads faster")
+                        .version(version)
+                        .author(AUTHOR)
+                        .arg(Arg::with_name(PATTERN_FILE)
+                                 .required(true)
+                                 .index(1)
+                                 .help("The pattern file or directory to be compiled"))
+                        .arg(Arg::with_name(CACHE_FILE)
+                                 .required(true)
+                                 .index(2)
+                                 .help("The cache file where the compiled patterns are written")))
+        .subcommand(SubCommand::with_name(STATS)
+                        .about("reports how the patterns classify the lines of a file")
+                        .version(version)
+                        .author(AUTHOR)
+                        .arg(Arg::with_name(PATTERN_FILE)
+                                 .required(true)
+                                 .index(1)
+                                 .help("The pattern file or directory with the patterns"))
+                        .arg(Arg::with_name(INPUT_FILE)
+                                 .required(true)
+                                 .index(2)
+                                 .help("The input file to be parsed, - for the standard input"))
+                        .arg(Arg::with_name(TOP)
+                                 .short("n")
+                                 .long(TOP)
+                                 .takes_value(true)
+                                 .help("The number of the most frequent unmatched lines to be \
+                                        reported (10 by default)")))
+        .subcommand(SubCommand::with_name(SUGGEST)
+                        .about("suggests patterns for the lines of a file")
+                        .version(version)
+                        .author(AUTHOR)
+                        .arg(Arg::with_name(INPUT_FILE)
+                                 .required(true)
+                                 .index(1)
+                                 .help("The file with the lines (e.g. the unmatched ones), - for \
+                                        the standard input"))
+                        .arg(Arg::with_name(OUTPUT_FILE)
+                                 .index(2)
+                                 .help("The pattern file where the suggested patterns are \
+                                        written, - (the default) for the standard output"))
+                        .arg(Arg::with_name(FORMAT)
+                                 .short("f")
+                                 .long(FORMAT)
+                                 .takes_value(true)
+                                 .possible_values(PatternFileFormat::names())
+                                 .help("The format of the pattern file (json by default)"))
+                        .arg(Arg::with_name(SIMILARITY)
+                                 .short("s")
+                                 .long(SIMILARITY)
+                                 .takes_value(true)
+                                 .help("The minimal ratio of the equal tokens of the lines in the \
+                                        same pattern, between 0 and 1 (0.5 by default)"))
+                        .arg(Arg::with_name(SAMPLES)
+                                 .short("n")
+                                 .long(SAMPLES)
+                                 .takes_value(true)
+                                 .help("The number of lines added to a pattern as test messages \
+                                        (3 by default)")))
+        .subcommand(SubCommand::with_name(IMPORT_PATTERNDB)
+                        .about("converts a syslog-ng patterndb XML file into a pattern file")
+                        .version(version)
+                        .author(AUTHOR)
+                        .arg(Arg::with_name(PATTERNDB_FILE)
+                                 .required(true)
+                                 .index(1)
+                                 .help("The patterndb XML file to be converted, - for the \
+                                        standard input"))
+                        .arg(Arg::with_name(OUTPUT_FILE)
+                                 .index(2)
+                                 .help("The pattern file where the converted patterns are \
+                                        written, - (the default) for the standard output"))
+                        .arg(Arg::with_name(FORMAT)
+                                 .short("f")
+                                 .long(FORMAT)
+                                 .takes_value(true)
+                                 .possible_values(PatternFileFormat::names())
+                                 .help("The format of the pattern file (json by default)")))
+        .subcommand(SubCommand::with_name(EXPORT)
+                        .about("converts the patterns into a syslog-ng patterndb XML or a Grok \
+                                pattern file")
+                        .version(version)
+                        .author(AUTHOR)
+                        .arg(Arg::with_name(PATTERN_FILE)
+                                 .required(true)
+                                 .index(1)
+                                 .help("The pattern file or directory to be converted"))
+                        .arg(Arg::with_name(OUTPUT_FILE)
+                                 .index(2)
+                                 .help("The file where the converted patterns are written, - \
+                                        (the default) for the standard output"))
+                        .arg(Arg::with_name(TO)
+                                 .short("t")
+                                 .long(TO)
+                                 .takes_value(true)
+                                 .required(true)
+                                 .possible_values(ExportFormat::names())
+                                 .help("The format of the converted patterns"))
+                        .arg(Arg::with_name(RULESET)
+                                 .short("r")
+                                 .long(RULESET)
+                                 .takes_value(true)
+                                 .help("The name of the patterndb ruleset (actiondb by \
+                                        default)")))
 }
 
 fn handle_validate<MS: MatcherSuite>(matches: &ArgMatches) {
@@ -80,18 +250,148 @@ fn handle_validate<MS: MatcherSuite>(matches: &ArgMatches) {
 fn handle_parse<MS: MatcherSuite>(matches: &ArgMatches) {
     let pattern_file = matches.value_of(PATTERN_FILE).unwrap();
     let input_file = matches.value_of(INPUT_FILE).unwrap();
-    let output_file = matches.value_of(OUTPUT_FILE).unwrap();
+    let output_file = matches.value_of(OUTPUT_FILE).unwrap_or(parse::STANDARD_STREAM);
+    let format = matches.value_of(FORMAT)
+                        .and_then(OutputFormat::from_str)
+                        .unwrap_or(OutputFormat::Json);
 
-    if let Err(e) = parse::parse::<MS>(pattern_file, input_file, output_file) {
+    if let Err(e) = parse::parse::<MS>(pattern_file, input_file, output_file, format) {
         error!("{}", e);
         std::process::exit(1);
     }
 }
 
-fn setup_stdout_logger(log_level: LogLevelFilter) {
+fn handle_lint<MS: MatcherSuite>(matches: &ArgMatches)
+    where MS::Matcher: PatternTree
+{
+    let pattern_file = matches.value_of(PATTERN_FILE).unwrap();
+
+    match lint::lint::<MS>(pattern_file) {
+        Ok(issues) => {
+            for issue in &issues {
+                warn!("{}", issue);
+            }
+            info!("Total number of issues: {}", issues.len());
+            if !issues.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_explain<MS: MatcherSuite>(matches: &ArgMatches) {
+    let pattern_file = matches.value_of(PATTERN_FILE).unwrap();
+    let line = matches.value_of(LINE).unwrap();
+
+    match explain::explain::<MS>(pattern_file, line) {
+        Ok(explanation) => println!("{}", explanation),
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_compile<MS: MatcherSuite>(matches: &ArgMatches)
+    where MS::Matcher: Cacheable
+{
+    let pattern_file = matches.value_of(PATTERN_FILE).unwrap();
+    let cache_file = matches.value_of(CACHE_FILE).unwrap();
+
+    if let Err(e) = PatternLoader::compile::<MS, _>(&[pattern_file], cache_file) {
+        error!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn handle_stats<MS: MatcherSuite>(matches: &ArgMatches) {
+    let pattern_file = matches.value_of(PATTERN_FILE).unwrap();
+    let input_file = matches.value_of(INPUT_FILE).unwrap();
+    let top = match matches.value_of(TOP).map(|top| top.parse::<usize>()) {
+        Some(Ok(top)) => top,
+        Some(Err(e)) => {
+            error!("Invalid value of --{}: {}", TOP, e);
+            std::process::exit(1);
+        }
+        None => DEFAULT_TOP,
+    };
+
+    match stats::stats::<MS>(pattern_file, input_file, top) {
+        Ok(stats) => println!("{}", stats),
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_suggest(matches: &ArgMatches) {
+    let input_file = matches.value_of(INPUT_FILE).unwrap();
+    let output_file = matches.value_of(OUTPUT_FILE).unwrap_or(parse::STANDARD_STREAM);
+    let format = matches.value_of(FORMAT)
+                        .and_then(PatternFileFormat::from_str)
+                        .unwrap_or(PatternFileFormat::Json);
+
+    let mut suggester = Suggester::new();
+    match matches.value_of(SIMILARITY).map(|similarity| similarity.parse::<f64>()) {
+        Some(Ok(similarity)) if similarity >= 0.0 && similarity <= 1.0 => {
+            suggester.set_similarity(similarity)
+        }
+        Some(_) => {
+            error!("Invalid value of --{}: it must be a number between 0 and 1",
+                   SIMILARITY);
+            std::process::exit(1);
+        }
+        None => {}
+    }
+    match matches.value_of(SAMPLES).map(|samples| samples.parse::<usize>()) {
+        Some(Ok(samples)) => suggester.set_samples(samples),
+        Some(Err(e)) => {
+            error!("Invalid value of --{}: {}", SAMPLES, e);
+            std::process::exit(1);
+        }
+        None => {}
+    }
+
+    if let Err(e) = suggest::suggest(input_file, output_file, format, &mut suggester) {
+        error!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn handle_import_patterndb(matches: &ArgMatches) {
+    let patterndb_file = matches.value_of(PATTERNDB_FILE).unwrap();
+    let output_file = matches.value_of(OUTPUT_FILE).unwrap_or(parse::STANDARD_STREAM);
+    let format = matches.value_of(FORMAT)
+                        .and_then(PatternFileFormat::from_str)
+                        .unwrap_or(PatternFileFormat::Json);
+
+    if let Err(e) = import::import_patterndb(patterndb_file, output_file, format) {
+        error!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn handle_export(matches: &ArgMatches) {
+    let pattern_file = matches.value_of(PATTERN_FILE).unwrap();
+    let output_file = matches.value_of(OUTPUT_FILE).unwrap_or(parse::STANDARD_STREAM);
+    let format = matches.value_of(TO).and_then(ExportFormat::from_str).unwrap();
+    let ruleset = matches.value_of(RULESET).unwrap_or(DEFAULT_RULESET);
+
+    if let Err(e) = export::export(pattern_file, output_file, format, ruleset) {
+        error!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn setup_stderr_logger(log_level: LogLevelFilter) {
     let _ = log::set_logger(|max_log_level| {
         max_log_level.set(log_level);
-        Box::new(StdoutLogger)
+        Box::new(StderrLogger)
     });
 }
 
@@ -103,11 +403,27 @@ fn choose_log_level<'a>(matches: &ArgMatches<'a>) -> LogLevelFilter {
     }
 }
 
-fn process_command_line_args<'a, MS: MatcherSuite>(matches: ArgMatches<'a>) {
+fn process_command_line_args<'a, MS: MatcherSuite>(matches: ArgMatches<'a>)
+    where MS::Matcher: PatternTree + Cacheable
+{
     if let Some(matches) = matches.subcommand_matches(VALIDATE) {
         handle_validate::<MS>(&matches);
     } else if let Some(matches) = matches.subcommand_matches(PARSE) {
         handle_parse::<MS>(&matches);
+    } else if let Some(matches) = matches.subcommand_matches(LINT) {
+        handle_lint::<MS>(&matches);
+    } else if let Some(matches) = matches.subcommand_matches(EXPLAIN) {
+        handle_explain::<MS>(&matches);
+    } else if let Some(matches) = matches.subcommand_matches(COMPILE) {
+        handle_compile::<MS>(&matches);
+    } else if let Some(matches) = matches.subcommand_matches(STATS) {
+        handle_stats::<MS>(&matches);
+    } else if let Some(matches) = matches.subcommand_matches(SUGGEST) {
+        handle_suggest(&matches);
+    } else if let Some(matches) = matches.subcommand_matches(IMPORT_PATTERNDB) {
+        handle_import_patterndb(&matches);
+    } else if let Some(matches) = matches.subcommand_matches(EXPORT) {
+        handle_export(&matches);
     } else {
         error!("{}", matches.usage.as_ref().unwrap());
     }
@@ -116,6 +432,12 @@ fn process_command_line_args<'a, MS: MatcherSuite>(matches: ArgMatches<'a>) {
 fn main() {
     let matches = build_command_line_argument_parser().get_matches();
     let log_level = choose_log_level(&matches);
-    setup_stdout_logger(log_level);
-    process_command_line_args::<SuffixArrayMatcherSuite>(matches);
+    setup_stderr_logger(log_level);
+    let matcher_kind = matches.value_of(MATCHER)
+                              .and_then(|kind| kind.parse::<MatcherKind>().ok())
+                              .unwrap_or_default();
+    match matcher_kind {
+        MatcherKind::Trie => process_command_line_args::<TrieMatcherSuite>(matches),
+        MatcherKind::SuffixArray => process_command_line_args::<SuffixArrayMatcherSuite>(matches),
+    }
 }
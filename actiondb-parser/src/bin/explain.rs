@@ -0,0 +1,18 @@
+use std::io::{Error, ErrorKind};
+use actiondb::Matcher;
+use actiondb::matcher::{PatternLoader, MatcherSuite};
+use actiondb::matcher::explain::Explanation;
+
+pub fn explain<MS: MatcherSuite>(pattern_file_path: &str,
+                                 line: &str)
+                                 -> Result<Explanation, Error> {
+    // the errors of the pattern file are logged, so the patterns can be
+    // debugged while they are broken
+    match PatternLoader::from_file_ignore_errors::<MS::MatcherFactory>(pattern_file_path) {
+        Ok(matcher) => Ok(matcher.explain(line)),
+        Err(err) => {
+            Err(Error::new(ErrorKind::Other,
+                           format!("Failed to load the patterns from the pattern file: {:?}", err)))
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use std::fs::File;
+use std::io::{self, Error, ErrorKind, Write};
+
+use actiondb::matcher::PatternLoader;
+use actiondb::matcher::pattern::file::{grok, patterndb};
+
+use parse::STANDARD_STREAM;
+
+// The formats the patterns can be exported to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Patterndb,
+    Grok,
+}
+
+impl ExportFormat {
+    pub fn from_str(format: &str) -> Option<ExportFormat> {
+        match format {
+            "patterndb" => Some(ExportFormat::Patterndb),
+            "grok" => Some(ExportFormat::Grok),
+            _ => None,
+        }
+    }
+
+    pub fn names() -> &'static [&'static str] {
+        &["patterndb", "grok"]
+    }
+}
+
+// Converts the patterns of a pattern file or directory into a patterndb XML
+// ruleset called `ruleset` or into a Grok pattern file. The constructs
+// without an equivalent are logged as warnings.
+pub fn export(pattern_file_path: &str,
+              output_file_path: &str,
+              format: ExportFormat,
+              ruleset: &str)
+              -> Result<(), Error> {
+    let results = try!(PatternLoader::load_paths(&[pattern_file_path]).map_err(|err| {
+        Error::new(ErrorKind::Other, format!("Failed to load the patterns: {}", err))
+    }));
+    let mut patterns = Vec::new();
+    for result in results {
+        patterns.push(try!(result.map_err(|err| {
+            Error::new(ErrorKind::Other, format!("Failed to load a pattern: {}", err))
+        })));
+    }
+
+    let (content, issues) = match format {
+        ExportFormat::Patterndb => patterndb::export(&patterns, ruleset),
+        ExportFormat::Grok => grok::export(&patterns),
+    };
+    for issue in &issues {
+        warn!("{}", issue);
+    }
+    info!("Total number of patterns: {} issues: {}",
+          patterns.len(),
+          issues.len());
+
+    let mut output: Box<Write> = if output_file_path == STANDARD_STREAM {
+        Box::new(io::stdout())
+    } else {
+        Box::new(try!(File::create(output_file_path)))
+    };
+    try!(output.write_all(content.as_bytes()));
+    output.write_all(b"\n")
+}
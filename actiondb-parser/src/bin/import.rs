@@ -0,0 +1,27 @@
+use std::io::{Error, ErrorKind};
+
+use actiondb::matcher::pattern::file::patterndb;
+
+use output::{self, PatternFileFormat};
+use parse;
+
+// Converts a patterndb XML file into a pattern file. The rule constructs
+// without an equivalent are logged as warnings.
+pub fn import_patterndb(input_file_path: &str,
+                        output_file_path: &str,
+                        format: PatternFileFormat)
+                        -> Result<(), Error> {
+    let input = try!(parse::open_input(input_file_path));
+    let (file, issues) = try!(patterndb::import(input).map_err(|err| {
+        Error::new(ErrorKind::Other,
+                   format!("Failed to read the patterndb file: {}", err))
+    }));
+
+    for issue in &issues {
+        warn!("{}", issue);
+    }
+    info!("Total number of imported patterns: {} issues: {}",
+          file.patterns().len(),
+          issues.len());
+    output::write_pattern_file(output_file_path, &file, format)
+}
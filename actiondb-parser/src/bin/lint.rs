@@ -0,0 +1,19 @@
+use std::io::{Error, ErrorKind};
+use actiondb::matcher::{PatternLoader, MatcherSuite};
+use actiondb::matcher::lint::{self, Issue, PatternTree};
+
+pub fn lint<MS: MatcherSuite>(pattern_file_path: &str) -> Result<Vec<Issue>, Error>
+    where MS::Matcher: PatternTree
+{
+    let to_error = |err| {
+        Error::new(ErrorKind::Other,
+                   format!("Failed to load the patterns from the pattern file: {}", err))
+    };
+    let results = try!(PatternLoader::load_paths(&[pattern_file_path]).map_err(&to_error));
+    let mut patterns = Vec::new();
+
+    for result in results {
+        patterns.push(try!(result.map_err(&to_error)));
+    }
+    Ok(lint::lint::<MS::MatcherFactory>(patterns))
+}
@@ -1,17 +1,20 @@
 extern crate log;
 
+use std::io::{self, Write};
 use log::{LogRecord, LogLevel, LogMetadata};
 
-pub struct StdoutLogger;
+// The messages are written to the standard error, so they are not mixed
+// with the output of the subcommands.
+pub struct StderrLogger;
 
-impl log::Log for StdoutLogger {
+impl log::Log for StderrLogger {
     fn enabled(&self, metadata: &LogMetadata) -> bool {
         metadata.level() <= LogLevel::Trace
     }
 
     fn log(&self, record: &LogRecord) {
         if self.enabled(record.metadata()) {
-            println!("{} - {}", record.level(), record.args());
+            let _ = writeln!(io::stderr(), "{} - {}", record.level(), record.args());
         }
     }
 }
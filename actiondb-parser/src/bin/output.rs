@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Error, ErrorKind, Result, Write};
+
+use actiondb::matcher::pattern::file::PatternFile;
+use actiondb::matcher::result::MatchResult;
+
+use parse::STANDARD_STREAM;
+
+pub const CSV_HEADER: &'static str = "matched,uuid,name,tags,values,message";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    Json,
+    Csv,
+    Kv,
+    Debug,
+}
+
+impl OutputFormat {
+    pub fn from_str(format: &str) -> Option<OutputFormat> {
+        match format {
+            "json" => Some(OutputFormat::Json),
+            "csv" => Some(OutputFormat::Csv),
+            "kv" => Some(OutputFormat::Kv),
+            "debug" => Some(OutputFormat::Debug),
+            _ => None,
+        }
+    }
+
+    pub fn names() -> &'static [&'static str] {
+        &["json", "csv", "kv", "debug"]
+    }
+}
+
+// The formats of the written pattern files.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PatternFileFormat {
+    Json,
+    Yaml,
+}
+
+impl PatternFileFormat {
+    pub fn from_str(format: &str) -> Option<PatternFileFormat> {
+        match format {
+            "json" => Some(PatternFileFormat::Json),
+            "yaml" => Some(PatternFileFormat::Yaml),
+            _ => None,
+        }
+    }
+
+    pub fn names() -> &'static [&'static str] {
+        &["json", "yaml"]
+    }
+}
+
+pub fn write_pattern_file(output_file_path: &str,
+                          file: &PatternFile,
+                          format: PatternFileFormat)
+                          -> Result<()> {
+    let content = match format {
+        PatternFileFormat::Json => file.to_json().map_err(|err| err.to_string()),
+        PatternFileFormat::Yaml => file.to_yaml().map_err(|err| err.to_string()),
+    };
+    let content = try!(content.map_err(|err| {
+        Error::new(ErrorKind::Other,
+                   format!("Failed to serialize the patterns: {}", err))
+    }));
+    let mut output: Box<Write> = if output_file_path == STANDARD_STREAM {
+        Box::new(io::stdout())
+    } else {
+        Box::new(try!(File::create(output_file_path)))
+    };
+    try!(output.write_all(content.as_bytes()));
+    output.write_all(b"\n")
+}
+
+// Writes the result of a line. The unmatched lines are written with
+// `matched` set to false and the original message.
+pub fn write_result<W: Write>(writer: &mut W,
+                              format: OutputFormat,
+                              line: &str,
+                              result: Option<&MatchResult>)
+                              -> Result<()> {
+    match format {
+        OutputFormat::Json => write_json(writer, line, result),
+        OutputFormat::Csv => write_csv(writer, line, result),
+        OutputFormat::Kv => write_kv(writer, line, result),
+        OutputFormat::Debug => write!(writer, "{:?}\n", result),
+    }
+}
+
+// The pattern's own values are merged into the parsed ones, like in the
+// syslog-ng parser.
+fn collect_values<'a>(result: &'a MatchResult) -> BTreeMap<&'a str, &'a str> {
+    let mut values = BTreeMap::new();
+    for (key, value) in result.values() {
+        values.insert(&key[..], &value[..]);
+    }
+    if let Some(pattern_values) = result.pattern().values() {
+        for (key, value) in pattern_values {
+            values.insert(&key[..], &value[..]);
+        }
+    }
+    values
+}
+
+fn tags<'a>(result: &'a MatchResult) -> &'a [String] {
+    result.pattern().tags().unwrap_or(&[])
+}
+
+fn write_json<W: Write>(writer: &mut W, line: &str, result: Option<&MatchResult>) -> Result<()> {
+    let result = match result {
+        Some(result) => result,
+        None => return write!(writer, "{{\"matched\":false,\"message\":{}}}\n", json_string(line)),
+    };
+
+    let name = result.pattern().name().map_or("null".to_owned(), json_string);
+    let tags = tags(result).iter().map(|tag| json_string(tag)).collect::<Vec<_>>();
+    let values = collect_values(result)
+                     .into_iter()
+                     .map(|(key, value)| format!("{}:{}", json_string(key), json_string(value)))
+                     .collect::<Vec<_>>();
+    write!(writer,
+           "{{\"matched\":true,\"uuid\":\"{}\",\"name\":{},\"tags\":[{}],\"values\":{{{}}}}}\n",
+           result.pattern().uuid().hyphenated(),
+           name,
+           tags.join(","),
+           values.join(","))
+}
+
+// The tags are separated by commas, the values are written in the kv
+// format.
+fn write_csv<W: Write>(writer: &mut W, line: &str, result: Option<&MatchResult>) -> Result<()> {
+    let result = match result {
+        Some(result) => result,
+        None => return write!(writer, "false,,,,,{}\n", csv_field(line)),
+    };
+
+    let values = collect_values(result)
+                     .into_iter()
+                     .map(|(key, value)| format!("{}={}", key, kv_value(value)))
+                     .collect::<Vec<_>>();
+    write!(writer,
+           "true,{},{},{},{},\n",
+           result.pattern().uuid().hyphenated(),
+           csv_field(result.pattern().name().unwrap_or("")),
+           csv_field(&tags(result).join(",")),
+           csv_field(&values.join(" ")))
+}
+
+fn write_kv<W: Write>(writer: &mut W, line: &str, result: Option<&MatchResult>) -> Result<()> {
+    let result = match result {
+        Some(result) => result,
+        None => return write!(writer, "matched=false message={}\n", kv_value(line)),
+    };
+
+    try!(write!(writer, "matched=true uuid={}", result.pattern().uuid().hyphenated()));
+    if let Some(name) = result.pattern().name() {
+        try!(write!(writer, " name={}", kv_value(name)));
+    }
+    if !tags(result).is_empty() {
+        try!(write!(writer, " tags={}", kv_value(&tags(result).join(","))));
+    }
+    for (key, value) in collect_values(result) {
+        try!(write!(writer, " {}={}", key, kv_value(value)));
+    }
+    write!(writer, "\n")
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", value.replace("\"", "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+fn kv_value(value: &str) -> String {
+    if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '"' || c == '=') {
+        format!("\"{}\"", value.replace("\\", "\\\\").replace("\"", "\\\""))
+    } else {
+        value.to_owned()
+    }
+}
@@ -1,18 +1,28 @@
 use std::fs::File;
-use std::io::{BufReader, BufRead, Error, ErrorKind, BufWriter, Write};
+use std::io::{self, BufReader, BufRead, Error, ErrorKind, BufWriter, Read, Write};
 use actiondb::Matcher;
 use actiondb::matcher::{PatternLoader, MatcherSuite};
+use actiondb::matcher::result::MatchResult;
+
+use output::{self, OutputFormat};
+
+// The standard input or output is used instead of this file name.
+pub const STANDARD_STREAM: &'static str = "-";
 
 pub fn parse<MS: MatcherSuite>(pattern_file_path: &str,
-             input_file_path: &str,
-             output_file_path: &str)
-             -> Result<(), Error> {
+                               input_file_path: &str,
+                               output_file_path: &str,
+                               format: OutputFormat)
+                               -> Result<(), Error> {
     match PatternLoader::from_file::<MS::MatcherFactory>(pattern_file_path) {
         Ok(matcher) => {
-            let input_file = try!(File::open(input_file_path));
-            let mut output_file = try!(File::create(output_file_path));
-            parse_file::<MS>(&input_file, &mut output_file, &matcher);
-            Ok(())
+            let input = try!(open_input(input_file_path));
+            let output: Box<Write> = if output_file_path == STANDARD_STREAM {
+                Box::new(io::stdout())
+            } else {
+                Box::new(try!(File::create(output_file_path)))
+            };
+            parse_lines(input, BufWriter::new(output), &matcher, format)
         }
         Err(err) => {
             Err(Error::new(ErrorKind::Other,
@@ -21,20 +31,55 @@ pub fn parse<MS: MatcherSuite>(pattern_file_path: &str,
     }
 }
 
-fn parse_file<MS: MatcherSuite>(input_file: &File, output_file: &mut File, matcher: &MS::Matcher) {
-    let reader = BufReader::new(input_file);
-    let mut writer = BufWriter::new(output_file);
-    let mut count: usize = 0;
+pub fn open_input(input_file_path: &str) -> Result<BufReader<Box<Read>>, Error> {
+    let input: Box<Read> = if input_file_path == STANDARD_STREAM {
+        Box::new(io::stdin())
+    } else {
+        Box::new(try!(File::open(input_file_path)))
+    };
+    Ok(BufReader::new(input))
+}
 
+// Parses every line of `reader` and calls `handle` with the line and its
+// result. Returns the number of lines.
+pub fn for_each_line<R, M, F>(reader: R, matcher: &M, mut handle: F) -> Result<usize, Error>
+    where R: BufRead,
+          M: Matcher,
+          F: FnMut(&str, Option<&MatchResult>) -> Result<(), Error>
+{
+    let mut count: usize = 0;
     for line in reader.lines() {
-        if let Ok(l) = line {
-            let parse_result = matcher.parse(&l);
-            let _ = write!(&mut writer, "{:?}\n", parse_result);
-            count += 1;
-        } else {
-            break;
-        }
+        let line = try!(line);
+        let parse_result = matcher.parse(&line);
+        try!(handle(&line, parse_result.as_ref()));
+        count += 1;
     }
+    Ok(count)
+}
+
+fn parse_lines<R, W, M>(reader: R,
+                        mut writer: W,
+                        matcher: &M,
+                        format: OutputFormat)
+                        -> Result<(), Error>
+    where R: BufRead,
+          W: Write,
+          M: Matcher
+{
+    let mut matched: usize = 0;
+
+    if format == OutputFormat::Csv {
+        try!(write!(&mut writer, "{}\n", output::CSV_HEADER));
+    }
+
+    let count = try!(for_each_line(reader, matcher, |line, result| {
+        if result.is_some() {
+            matched += 1;
+        }
+        output::write_result(&mut writer, format, line, result)
+    }));
+    try!(writer.flush());
 
-    info!("Total number of lines: {}", count);
+    info!("Total number of lines: {} matched: {}", count, matched);
+    Ok(())
 }
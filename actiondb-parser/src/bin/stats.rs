@@ -0,0 +1,146 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::{Error, ErrorKind};
+use std::time::{Duration, Instant};
+
+use actiondb::matcher::{PatternLoader, MatcherSuite, FromPatternSource};
+use actiondb::matcher::result::MatchResult;
+
+use parse;
+
+const NUMBER_PLACEHOLDER: &'static str = "<*>";
+
+// Statistics of parsing a corpus with the patterns: how many lines were
+// classified, how often each pattern matched and which unmatched lines are
+// the most frequent.
+pub struct Stats {
+    lines: usize,
+    matched: usize,
+    // the names of every pattern by their UUID
+    patterns: BTreeMap<String, Option<String>>,
+    hits: HashMap<String, usize>,
+    // the number of the unmatched lines and an example by their skeleton
+    unmatched: HashMap<String, (usize, String)>,
+    elapsed: Duration,
+    top: usize,
+}
+
+impl Stats {
+    fn new(patterns: BTreeMap<String, Option<String>>, top: usize) -> Stats {
+        Stats {
+            lines: 0,
+            matched: 0,
+            patterns: patterns,
+            hits: HashMap::new(),
+            unmatched: HashMap::new(),
+            elapsed: Duration::from_secs(0),
+            top: top,
+        }
+    }
+
+    fn add(&mut self, line: &str, result: Option<&MatchResult>) {
+        self.lines += 1;
+        match result {
+            Some(result) => {
+                self.matched += 1;
+                let uuid = result.pattern().uuid().hyphenated().to_string();
+                *self.hits.entry(uuid).or_insert(0) += 1;
+            }
+            None => {
+                let entry = self.unmatched.entry(skeleton(line)).or_insert((0, line.to_owned()));
+                entry.0 += 1;
+            }
+        }
+    }
+
+    fn percent(&self, count: usize) -> f64 {
+        if self.lines == 0 {
+            0.0
+        } else {
+            count as f64 * 100.0 / self.lines as f64
+        }
+    }
+
+    fn name(&self, uuid: &str) -> &str {
+        self.patterns.get(uuid).and_then(|name| name.as_ref()).map_or("", |name| &name[..])
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let unmatched = self.lines - self.matched;
+        let seconds = self.elapsed.as_secs() as f64 +
+                      self.elapsed.subsec_nanos() as f64 / 1_000_000_000.0;
+        let throughput = if seconds > 0.0 { self.lines as f64 / seconds } else { 0.0 };
+
+        try!(writeln!(fmt, "Lines: {}", self.lines));
+        try!(writeln!(fmt, "Matched: {} ({:.2}%)", self.matched, self.percent(self.matched)));
+        try!(writeln!(fmt, "Unmatched: {} ({:.2}%)", unmatched, self.percent(unmatched)));
+        try!(writeln!(fmt, "Throughput: {:.0} lines/s ({:.3} s)", throughput, seconds));
+
+        let mut hits = self.hits.iter().collect::<Vec<_>>();
+        hits.sort_by(|a, b| (b.1, a.0).cmp(&(a.1, b.0)));
+        try!(writeln!(fmt, "\nPattern hits:"));
+        for (uuid, count) in hits {
+            try!(writeln!(fmt, "  {:>10} {} {}", count, uuid, self.name(uuid)));
+        }
+
+        try!(writeln!(fmt, "\nPatterns without hits:"));
+        for uuid in self.patterns.keys().filter(|uuid| !self.hits.contains_key(*uuid)) {
+            try!(writeln!(fmt, "  {} {}", uuid, self.name(uuid)));
+        }
+
+        let mut unmatched = self.unmatched.iter().collect::<Vec<_>>();
+        unmatched.sort_by(|a, b| ((b.1).0, a.0).cmp(&((a.1).0, b.0)));
+        try!(write!(fmt, "\nMost frequent unmatched lines (top {}):", self.top));
+        for (skeleton, &(count, ref example)) in unmatched.into_iter().take(self.top) {
+            try!(write!(fmt, "\n  {:>10} {}\n             e.g. {}", count, skeleton, example));
+        }
+        Ok(())
+    }
+}
+
+// The tokens which contain a digit are replaced with a placeholder, so the
+// lines which differ only in numbers, addresses or dates are grouped.
+fn skeleton(line: &str) -> String {
+    line.split_whitespace()
+        .map(|token| {
+            if token.bytes().any(|c| c >= b'0' && c <= b'9') {
+                NUMBER_PLACEHOLDER
+            } else {
+                token
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn stats<MS: MatcherSuite>(pattern_file_path: &str,
+                               input_file_path: &str,
+                               top: usize)
+                               -> Result<Stats, Error> {
+    let to_error = |err| {
+        Error::new(ErrorKind::Other,
+                   format!("Failed to load the patterns: {}", err))
+    };
+    let patterns = try!(PatternLoader::load_paths(&[pattern_file_path]).map_err(&to_error));
+    let names = patterns.iter()
+                        .filter_map(|pattern| pattern.as_ref().ok())
+                        .map(|pattern| {
+                            (pattern.uuid().hyphenated().to_string(),
+                             pattern.name().map(|name| name.to_owned()))
+                        })
+                        .collect();
+    let matcher = try!(MS::Matcher::from_source::<MS::MatcherFactory>(&mut patterns.into_iter())
+                           .map_err(&to_error));
+
+    let input = try!(parse::open_input(input_file_path));
+    let mut stats = Stats::new(names, top);
+    let start = Instant::now();
+    try!(parse::for_each_line(input, &matcher, |line, result| {
+        stats.add(line, result);
+        Ok(())
+    }));
+    stats.elapsed = start.elapsed();
+    Ok(stats)
+}
@@ -0,0 +1,33 @@
+use std::io::{BufRead, Error};
+
+use actiondb::matcher::pattern::file::PatternFile;
+use actiondb::matcher::suggest::Suggester;
+
+use output::{self, PatternFileFormat};
+use parse;
+
+pub fn suggest(input_file_path: &str,
+               output_file_path: &str,
+               format: PatternFileFormat,
+               suggester: &mut Suggester)
+               -> Result<(), Error> {
+    let input = try!(parse::open_input(input_file_path));
+    let mut count: usize = 0;
+    for line in input.lines() {
+        suggester.add_line(&try!(line));
+        count += 1;
+    }
+
+    let suggestions = suggester.suggestions();
+    for suggestion in &suggestions {
+        debug!("Suggested pattern: uuid={} lines={}",
+               suggestion.pattern().uuid().hyphenated(),
+               suggestion.count());
+    }
+    info!("Total number of lines: {} suggested patterns: {}",
+          count,
+          suggestions.len());
+
+    let patterns = suggestions.into_iter().map(|suggestion| suggestion.into_pattern()).collect();
+    output::write_pattern_file(output_file_path, &PatternFile::new(patterns), format)
+}
@@ -8,3 +8,35 @@
 
 pub const PATTERN_UUID: &'static str = ".classifier.uuid";
 pub const PATTERN_NAME: &'static str = ".classifier.class";
+pub const TYPE_HINT_SUFFIX: &'static str = ".type";
+
+// The keys of the values which are inserted besides the parsed ones. The
+// pattern file and the number of test messages are inserted only if their
+// key is set.
+#[derive(Clone, Debug)]
+pub struct Keys {
+    pub uuid: String,
+    pub class: String,
+    pub pattern_file: Option<String>,
+    pub test_message_count: Option<String>,
+}
+
+impl Default for Keys {
+    fn default() -> Keys {
+        Keys {
+            uuid: PATTERN_UUID.to_owned(),
+            class: PATTERN_NAME.to_owned(),
+            pattern_file: None,
+            test_message_count: None,
+        }
+    }
+}
+
+// The values and tags which are set on the messages which don't match any
+// pattern. If `pass` is set, these messages are not dropped by the parser.
+#[derive(Clone, Debug, Default)]
+pub struct Unmatched {
+    pub values: Vec<(String, String)>,
+    pub tags: Vec<String>,
+    pub pass: bool,
+}
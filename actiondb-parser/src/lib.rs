@@ -13,43 +13,258 @@ extern crate syslog_ng_common;
 extern crate actiondb;
 
 use std::borrow::Borrow;
+use std::sync::Arc;
+use std::time::Duration;
 
-use actiondb::matcher::{Matcher, PatternLoader, MatcherSuite};
+use actiondb::matcher::{Matcher, PatternLoader, MatcherSuite, MatcherKind, BuildError};
+use actiondb::matcher::cache::{Cacheable, Sources};
+use actiondb::matcher::trie::TrieMatcherSuite;
+use actiondb::matcher::suffix_array::SuffixArrayMatcherSuite;
 use syslog_ng_common::{Parser, ParserBuilder, Error, LogMessage, MessageFormatter, Pipe, GlobalConfig};
 
 mod msgfilller;
 mod keys;
 mod options;
+mod reloader;
 
 use self::msgfilller::MessageFiller;
+use self::keys::{Keys, Unmatched};
+use self::reloader::Reloader;
+
+const DEFAULT_RELOAD_INTERVAL: u64 = 5;
+
+// The options which are used when the pattern file is loaded.
+#[derive(Clone, PartialEq)]
+struct LoadOptions {
+    cache_file: Option<String>,
+    backtracking_budget: Option<usize>,
+    strict: bool,
+    matcher_kind: MatcherKind,
+}
+
+// A matcher loaded by `option()`, it's used by `build()` if the options
+// weren't changed since then.
+struct LoadedMatcher {
+    options: LoadOptions,
+    matcher: Box<Matcher + Send>,
+    sources: Sources,
+}
 
 #[derive(Clone)]
-pub struct ActiondbParserBuilder<MS> where MS: MatcherSuite, MS::Matcher: Clone {
-    matcher: Option<MS::Matcher>,
-    formatter: MessageFormatter
+pub struct ActiondbParserBuilder {
+    pattern_file: Option<String>,
+    load_options: LoadOptions,
+    loaded: Option<Arc<LoadedMatcher>>,
+    formatter: MessageFormatter,
+    type_hints: bool,
+    watch: bool,
+    reload_interval: Duration,
+    keys: Keys,
+    unmatched: Unmatched,
 }
 
-impl<MS> ActiondbParserBuilder<MS> where MS: MatcherSuite, MS::Matcher: Clone {
-    pub fn set_pattern_file(&mut self, path: &str) {
-        match PatternLoader::from_file::<MS::MatcherFactory>(path) {
-            Ok(matcher) => self.matcher = Some(matcher),
-            Err(err) => {
-                error!("ActiondbParser: failed to set 'pattern_file': {}", err);
+impl ActiondbParserBuilder {
+    // The pattern file is loaded with the options which are set before it,
+    // so the invalid patterns are reported by this option.
+    pub fn set_pattern_file(&mut self, path: &str) -> Result<(), Error> {
+        let (matcher, sources) = try!(Self::load_matcher(path, &self.load_options)
+                                          .map_err(|error| Self::load_error(path, error)));
+        self.pattern_file = Some(path.to_owned());
+        self.loaded = Some(Arc::new(LoadedMatcher {
+            options: self.load_options.clone(),
+            matcher: matcher,
+            sources: sources,
+        }));
+        Ok(())
+    }
+
+    // The compiled patterns are loaded from this file if it's up to date,
+    // otherwise it's written again after the pattern file is loaded.
+    pub fn set_cache_file(&mut self, path: &str) {
+        self.load_options.cache_file = Some(path.to_owned());
+    }
+
+    fn load_matcher(pattern_file: &str,
+                    options: &LoadOptions)
+                    -> Result<(Box<Matcher + Send>, Sources), BuildError> {
+        let cache_file = options.cache_file.as_ref().map(|cache_file| &cache_file[..]);
+        let strict = options.strict;
+        let (mut matcher, sources) = try!(match options.matcher_kind {
+            MatcherKind::Trie => {
+                Self::load_suite::<TrieMatcherSuite>(pattern_file, cache_file, strict)
+            }
+            MatcherKind::SuffixArray => {
+                Self::load_suite::<SuffixArrayMatcherSuite>(pattern_file, cache_file, strict)
             }
+        });
+        if let Some(budget) = options.backtracking_budget {
+            matcher.set_backtracking_budget(budget);
         }
+        Ok((matcher, sources))
+    }
+
+    fn load_error(pattern_file: &str, error: BuildError) -> Error {
+        Error::verbatim_error(format!("ActiondbParser: failed to load the pattern file: \
+                                       pattern_file={} error={}",
+                                      pattern_file,
+                                      error))
+    }
+
+    fn load_suite<MS>(pattern_file: &str,
+                      cache_file: Option<&str>,
+                      strict: bool)
+                      -> Result<(Box<Matcher + Send>, Sources), BuildError>
+        where MS: MatcherSuite,
+              MS::Matcher: Cacheable + Send + 'static
+    {
+        let ignore_errors = !strict;
+        let (matcher, sources) = try!(match cache_file {
+            Some(cache_file) => {
+                PatternLoader::from_file_cached_with_sources::<MS>(pattern_file,
+                                                                   cache_file,
+                                                                   ignore_errors)
+            }
+            None => {
+                PatternLoader::from_file_with_sources::<MS::MatcherFactory>(pattern_file,
+                                                                            ignore_errors)
+            }
+        });
+        Ok((Box::new(matcher), sources))
     }
 
     pub fn set_prefix(&mut self, prefix: String) {
         self.formatter.set_prefix(prefix);
     }
+
+    pub fn set_type_hints(&mut self, value: &str) -> Result<(), Error> {
+        match value {
+            "yes" | "true" => self.type_hints = true,
+            "no" | "false" => self.type_hints = false,
+            _ => return Err(Error::invalid_value(options::TYPE_HINTS, value, "yes or no")),
+        }
+        Ok(())
+    }
+
+    pub fn set_backtracking_budget(&mut self, value: &str) -> Result<(), Error> {
+        match value.parse::<usize>() {
+            Ok(budget) => {
+                self.load_options.backtracking_budget = Some(budget);
+                Ok(())
+            }
+            Err(_) => {
+                Err(Error::invalid_value(options::BACKTRACKING_BUDGET,
+                                         value,
+                                         "a non-negative integer"))
+            }
+        }
+    }
+
+    // If it's set, the pattern files are checked periodically and they are
+    // loaded again in the background when they are changed.
+    pub fn set_watch(&mut self, value: &str) -> Result<(), Error> {
+        match value {
+            "yes" | "true" => self.watch = true,
+            "no" | "false" => self.watch = false,
+            _ => return Err(Error::invalid_value(options::WATCH, value, "yes or no")),
+        }
+        Ok(())
+    }
+
+    // The pattern files are checked in every `value` seconds if they are
+    // watched.
+    pub fn set_reload_interval(&mut self, value: &str) -> Result<(), Error> {
+        match value.parse::<u64>() {
+            Ok(seconds) if seconds > 0 => {
+                self.reload_interval = Duration::from_secs(seconds);
+                Ok(())
+            }
+            _ => Err(Error::invalid_value(options::RELOAD_INTERVAL, value, "a positive integer")),
+        }
+    }
+
+    // If it's not set, the invalid patterns are logged and skipped instead
+    // of failing the configuration.
+    pub fn set_strict(&mut self, value: &str) -> Result<(), Error> {
+        match value {
+            "yes" | "true" => self.load_options.strict = true,
+            "no" | "false" => self.load_options.strict = false,
+            _ => return Err(Error::invalid_value(options::STRICT, value, "yes or no")),
+        }
+        Ok(())
+    }
+
+    pub fn set_uuid_key(&mut self, key: String) {
+        self.keys.uuid = key;
+    }
+
+    pub fn set_class_key(&mut self, key: String) {
+        self.keys.class = key;
+    }
+
+    pub fn set_pattern_file_key(&mut self, key: String) {
+        self.keys.pattern_file = Some(key);
+    }
+
+    pub fn set_test_message_count_key(&mut self, key: String) {
+        self.keys.test_message_count = Some(key);
+    }
+
+    // `value` is in `key=value` format. The option can be used more times.
+    pub fn add_unmatched_value(&mut self, value: &str) -> Result<(), Error> {
+        match value.find('=') {
+            Some(position) if position > 0 => {
+                let (key, value) = (&value[..position], &value[position + 1..]);
+                self.unmatched.values.push((key.to_owned(), value.to_owned()));
+                Ok(())
+            }
+            _ => Err(Error::invalid_value(options::UNMATCHED_VALUE, value, "key=value")),
+        }
+    }
+
+    pub fn add_unmatched_tag(&mut self, tag: String) {
+        self.unmatched.tags.push(tag);
+    }
+
+    // If it's set, the parser doesn't drop the messages which don't match
+    // any pattern.
+    pub fn set_pass_unmatched(&mut self, value: &str) -> Result<(), Error> {
+        match value {
+            "yes" | "true" => self.unmatched.pass = true,
+            "no" | "false" => self.unmatched.pass = false,
+            _ => return Err(Error::invalid_value(options::PASS_UNMATCHED, value, "yes or no")),
+        }
+        Ok(())
+    }
+
+    pub fn set_matcher(&mut self, value: &str) -> Result<(), Error> {
+        match value.parse::<MatcherKind>() {
+            Ok(matcher_kind) => {
+                self.load_options.matcher_kind = matcher_kind;
+                Ok(())
+            }
+            Err(()) => Err(Error::invalid_value(options::MATCHER, value, "trie or suffix-array")),
+        }
+    }
 }
 
-impl<MS> ParserBuilder for ActiondbParserBuilder<MS> where MS: MatcherSuite + Clone, MS::Matcher: Clone {
-    type Parser = ActiondbParser<MS::Matcher>;
+impl ParserBuilder for ActiondbParserBuilder {
+    type Parser = ActiondbParser;
     fn new(_: GlobalConfig) -> Self {
         ActiondbParserBuilder {
-            matcher: None,
+            pattern_file: None,
+            load_options: LoadOptions {
+                cache_file: None,
+                backtracking_budget: None,
+                strict: true,
+                matcher_kind: MatcherKind::default(),
+            },
+            loaded: None,
             formatter: MessageFormatter::new(),
+            type_hints: false,
+            watch: false,
+            reload_interval: Duration::from_secs(DEFAULT_RELOAD_INTERVAL),
+            keys: Keys::default(),
+            unmatched: Unmatched::default(),
         }
     }
     fn option(&mut self, name: String, value: String) -> Result<(), Error> {
@@ -59,53 +274,149 @@ impl<MS> ParserBuilder for ActiondbParserBuilder<MS> where MS: MatcherSuite + Cl
 
         match name.borrow() {
             options::PATTERN_FILE => {
-                self.set_pattern_file(&value);
+                self.set_pattern_file(&value)
+            }
+            options::CACHE_FILE => {
+                self.set_cache_file(&value);
                 Ok(())
             }
             options::PREFIX => {
                 self.set_prefix(value);
                 Ok(())
             }
+            options::TYPE_HINTS => {
+                self.set_type_hints(&value)
+            }
+            options::BACKTRACKING_BUDGET => {
+                self.set_backtracking_budget(&value)
+            }
+            options::WATCH => {
+                self.set_watch(&value)
+            }
+            options::RELOAD_INTERVAL => {
+                self.set_reload_interval(&value)
+            }
+            options::STRICT => {
+                self.set_strict(&value)
+            }
+            options::MATCHER => {
+                self.set_matcher(&value)
+            }
+            options::UUID_KEY => {
+                self.set_uuid_key(value);
+                Ok(())
+            }
+            options::CLASS_KEY => {
+                self.set_class_key(value);
+                Ok(())
+            }
+            options::PATTERN_FILE_KEY => {
+                self.set_pattern_file_key(value);
+                Ok(())
+            }
+            options::TEST_MESSAGE_COUNT_KEY => {
+                self.set_test_message_count_key(value);
+                Ok(())
+            }
+            options::UNMATCHED_VALUE => {
+                self.add_unmatched_value(&value)
+            }
+            options::UNMATCHED_TAG => {
+                self.add_unmatched_tag(value);
+                Ok(())
+            }
+            options::PASS_UNMATCHED => {
+                self.set_pass_unmatched(&value)
+            }
             _ => {
                 Err(Error::unknown_option(name))
             }
         }
     }
     fn build(self) -> Result<Self::Parser, Error> {
-        let ActiondbParserBuilder {matcher, formatter} = self;
+        let ActiondbParserBuilder {pattern_file, load_options, loaded, formatter, type_hints, watch,
+                                   reload_interval, keys, unmatched} = self;
         debug!("ActiondbParser: building");
-        let matcher =
-            try!(matcher.ok_or(Error::missing_required_option(options::PATTERN_FILE)));
+        let pattern_file =
+            try!(pattern_file.ok_or(Error::missing_required_option(options::PATTERN_FILE)));
+        // The clones of the builder share the loaded matcher, so they load
+        // the pattern file again.
+        let loaded = loaded.and_then(|loaded| Arc::try_unwrap(loaded).ok())
+                           .and_then(|loaded| {
+                               if loaded.options == load_options {
+                                   Some((loaded.matcher, loaded.sources))
+                               } else {
+                                   None
+                               }
+                           });
+        let (matcher, sources) = match loaded {
+            Some(loaded) => loaded,
+            None => {
+                try!(Self::load_matcher(&pattern_file, &load_options)
+                         .map_err(|error| Self::load_error(&pattern_file, error)))
+            }
+        };
+
+        let reloader = if watch {
+            let load = move || Self::load_matcher(&pattern_file, &load_options);
+            Some(Reloader::new(Arc::new(load), sources, reload_interval))
+        } else {
+            None
+        };
+
         Ok(ActiondbParser {
             matcher: matcher,
             formatter: formatter,
+            type_hints: type_hints,
+            keys: keys,
+            unmatched: unmatched,
+            reloader: reloader,
         })
     }
 }
 
-pub struct ActiondbParser<M> where M: Matcher + Clone {
-    pub matcher: M,
+pub struct ActiondbParser {
+    pub matcher: Box<Matcher + Send>,
     pub formatter: MessageFormatter,
+    pub type_hints: bool,
+    pub keys: Keys,
+    pub unmatched: Unmatched,
+    // the new matchers are built in the reloader's thread
+    reloader: Option<Reloader<Box<Matcher + Send>>>,
 }
 
-impl<M> Parser for ActiondbParser<M> where M: Matcher + Clone {
+impl Parser for ActiondbParser {
+    fn init(&mut self) -> bool {
+        if let Some(ref mut reloader) = self.reloader {
+            reloader.start();
+        }
+        true
+    }
+
+    fn deinit(&mut self) -> bool {
+        if let Some(ref mut reloader) = self.reloader {
+            reloader.stop();
+        }
+        true
+    }
+
     fn parse(&mut self, _: &mut Pipe, msg: &mut LogMessage, input: &str) -> bool {
+        if let Some(matcher) = self.reloader.as_ref().and_then(|reloader| reloader.take_matcher()) {
+            debug!("ActiondbParser: using the reloaded patterns");
+            self.matcher = matcher;
+        }
         if let Some(result) = self.matcher.parse(input) {
-            MessageFiller::fill_logmsg(&mut self.formatter, msg, &result);
+            MessageFiller::fill_logmsg(&mut self.formatter,
+                                       msg,
+                                       &result,
+                                       self.type_hints,
+                                       &self.keys);
             true
         } else {
-            false
+            MessageFiller::fill_unmatched(&mut self.formatter, msg, &self.unmatched);
+            self.unmatched.pass
         }
     }
 }
 
-
-// You can change the matcher implementation by uncommening these lines
-// and commenting out the last lines.
-
-//pub use actiondb::matcher::trie::TrieMatcherSuite;
-//parser_plugin!(ActiondbParserBuilder<TrieMatcherSuite>);
-
-// Note, that it sould be publicly reexported
-pub use actiondb::matcher::suffix_array::SuffixArrayMatcherSuite;
-parser_plugin!(ActiondbParserBuilder<SuffixArrayMatcherSuite>);
+parser_plugin!(ActiondbParserBuilder);
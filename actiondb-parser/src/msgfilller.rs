@@ -9,32 +9,67 @@
 use syslog_ng_common::MessageFormatter;
 use syslog_ng_common::LogMessage;
 use actiondb::matcher::result::MatchResult;
+use actiondb::parsers::ValueType;
 
-use keys;
+use keys::{self, Keys, Unmatched};
 
 pub struct MessageFiller;
 
 impl MessageFiller {
     pub fn fill_logmsg(formatter: &mut MessageFormatter,
                        msg: &mut LogMessage,
-                       result: &MatchResult) {
-        MessageFiller::fill_values(formatter, msg, result);
-        MessageFiller::fill_name(formatter, msg, result);
-        MessageFiller::fill_uuid(formatter, msg, result);
+                       result: &MatchResult,
+                       type_hints: bool,
+                       keys: &Keys) {
+        MessageFiller::fill_values(formatter, msg, result, type_hints);
+        MessageFiller::fill_name(formatter, msg, result, &keys.class);
+        MessageFiller::fill_uuid(formatter, msg, result, &keys.uuid);
+        if let Some(ref key) = keys.pattern_file {
+            MessageFiller::fill_pattern_file(formatter, msg, result, key);
+        }
+        if let Some(ref key) = keys.test_message_count {
+            MessageFiller::fill_test_message_count(formatter, msg, result, key);
+        }
         MessageFiller::fill_tags(msg, result);
     }
 
-    fn fill_values(formatter: &mut MessageFormatter, msg: &mut LogMessage, result: &MatchResult) {
-        MessageFiller::fill_parsed_values(formatter, msg, result);
+    pub fn fill_unmatched(formatter: &mut MessageFormatter,
+                          msg: &mut LogMessage,
+                          unmatched: &Unmatched) {
+        for &(ref key, ref value) in &unmatched.values {
+            let (key, value) = formatter.format(key, value);
+            msg.insert(key, value.as_bytes());
+        }
+        for tag in &unmatched.tags {
+            msg.set_tag(tag.as_bytes());
+        }
+    }
+
+    fn fill_values(formatter: &mut MessageFormatter,
+                   msg: &mut LogMessage,
+                   result: &MatchResult,
+                   type_hints: bool) {
+        MessageFiller::fill_parsed_values(formatter, msg, result, type_hints);
         MessageFiller::fill_additional_values(formatter, msg, result);
     }
 
+    // syslog-ng doesn't have typed name-value pairs, so the types of the
+    // non-string values are inserted with the `.type` suffix if it's enabled
     fn fill_parsed_values(formatter: &mut MessageFormatter,
                           msg: &mut LogMessage,
-                          result: &MatchResult) {
+                          result: &MatchResult,
+                          type_hints: bool) {
         for (key, value) in result.values() {
-            let (key, value) = formatter.format(key, value);
-            msg.insert(key, value.as_bytes());
+            {
+                let (key, value) = formatter.format(key, value);
+                msg.insert(key, value.as_bytes());
+            }
+            let value_type = result.value_type(key);
+            if type_hints && value_type != ValueType::String {
+                let type_key = format!("{}{}", key, keys::TYPE_HINT_SUFFIX);
+                let (type_key, value_type) = formatter.format(&type_key, value_type.as_str());
+                msg.insert(type_key, value_type.as_bytes());
+            }
         }
     }
 
@@ -49,16 +84,41 @@ impl MessageFiller {
         }
     }
 
-    fn fill_name(formatter: &mut MessageFormatter, msg: &mut LogMessage, result: &MatchResult) {
+    fn fill_name(formatter: &mut MessageFormatter,
+                 msg: &mut LogMessage,
+                 result: &MatchResult,
+                 key: &str) {
         if let Some(name) = result.pattern().name() {
-            let (key, value) = formatter.format(keys::PATTERN_NAME, name);
+            let (key, value) = formatter.format(key, name);
             msg.insert(key, value.as_bytes());
         }
     }
 
-    fn fill_uuid(formatter: &mut MessageFormatter, msg: &mut LogMessage, result: &MatchResult) {
+    fn fill_uuid(formatter: &mut MessageFormatter,
+                 msg: &mut LogMessage,
+                 result: &MatchResult,
+                 key: &str) {
         let uuid = result.pattern().uuid().hyphenated().to_string();
-        let (key, value) = formatter.format(keys::PATTERN_UUID, &uuid);
+        let (key, value) = formatter.format(key, &uuid);
+        msg.insert(key, value.as_bytes());
+    }
+
+    fn fill_pattern_file(formatter: &mut MessageFormatter,
+                         msg: &mut LogMessage,
+                         result: &MatchResult,
+                         key: &str) {
+        if let Some(location) = result.pattern().location() {
+            let (key, value) = formatter.format(key, location.file());
+            msg.insert(key, value.as_bytes());
+        }
+    }
+
+    fn fill_test_message_count(formatter: &mut MessageFormatter,
+                               msg: &mut LogMessage,
+                               result: &MatchResult,
+                               key: &str) {
+        let count = result.pattern().test_message_count().to_string();
+        let (key, value) = formatter.format(key, &count);
         msg.insert(key, value.as_bytes());
     }
 
@@ -7,4 +7,18 @@
 // modified, or distributed except according to those terms.
 
 pub const PATTERN_FILE: &'static str = "pattern_file";
+pub const CACHE_FILE: &'static str = "cache_file";
 pub const PREFIX: &'static str = "prefix";
+pub const TYPE_HINTS: &'static str = "type_hints";
+pub const BACKTRACKING_BUDGET: &'static str = "backtracking_budget";
+pub const WATCH: &'static str = "watch";
+pub const RELOAD_INTERVAL: &'static str = "reload_interval";
+pub const STRICT: &'static str = "strict";
+pub const MATCHER: &'static str = "matcher";
+pub const UUID_KEY: &'static str = "uuid_key";
+pub const CLASS_KEY: &'static str = "class_key";
+pub const PATTERN_FILE_KEY: &'static str = "pattern_file_key";
+pub const TEST_MESSAGE_COUNT_KEY: &'static str = "test_message_count_key";
+pub const UNMATCHED_VALUE: &'static str = "unmatched_value";
+pub const UNMATCHED_TAG: &'static str = "unmatched_tag";
+pub const PASS_UNMATCHED: &'static str = "pass_unmatched";
@@ -0,0 +1,112 @@
+// Copyright (c) 2016 Tibor Benke <ihrwein@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. All files in the project carrying such notice may not be copied,
+// modified, or distributed except according to those terms.
+
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use actiondb::matcher::BuildError;
+use actiondb::matcher::cache::Sources;
+
+pub type LoadFn<M> = Fn() -> Result<(M, Sources), BuildError> + Send + Sync;
+
+// Watches the pattern files in a background thread and builds a new matcher
+// when one of them is changed. The parser takes the new matchers between two
+// messages, so parsing isn't blocked by the reloads.
+pub struct Reloader<M> {
+    load: Arc<LoadFn<M>>,
+    sources: Sources,
+    interval: Duration,
+    matchers: Option<Receiver<M>>,
+    // the watcher thread stops when this is dropped
+    stop: Option<Sender<()>>,
+}
+
+impl<M> Reloader<M> where M: Send + 'static {
+    pub fn new(load: Arc<LoadFn<M>>, sources: Sources, interval: Duration) -> Reloader<M> {
+        Reloader {
+            load: load,
+            sources: sources,
+            interval: interval,
+            matchers: None,
+            stop: None,
+        }
+    }
+
+    pub fn start(&mut self) {
+        if self.stop.is_some() {
+            return;
+        }
+
+        let (matcher_sender, matcher_receiver) = mpsc::channel();
+        let (stop_sender, stop_receiver) = mpsc::channel();
+        let load = self.load.clone();
+        let sources = self.sources.clone();
+        let interval = self.interval;
+        thread::spawn(move || watch(load, sources, interval, stop_receiver, matcher_sender));
+
+        self.matchers = Some(matcher_receiver);
+        self.stop = Some(stop_sender);
+    }
+
+    pub fn stop(&mut self) {
+        self.stop = None;
+        self.matchers = None;
+    }
+
+    // Returns the newest matcher which was built since the last call.
+    pub fn take_matcher(&self) -> Option<M> {
+        let mut newest = None;
+        if let Some(ref matchers) = self.matchers {
+            while let Ok(matcher) = matchers.try_recv() {
+                newest = Some(matcher);
+            }
+        }
+        newest
+    }
+}
+
+// If the new pattern files can't be loaded, the error is logged and the
+// previous matcher is kept. They are loaded again only after the next change.
+fn watch<M>(load: Arc<LoadFn<M>>,
+            mut sources: Sources,
+            interval: Duration,
+            stop: Receiver<()>,
+            matchers: Sender<M>) {
+    let mut modification_times = sources.modification_times();
+
+    loop {
+        match stop.recv_timeout(interval) {
+            Err(RecvTimeoutError::Timeout) => (),
+            _ => return,
+        }
+
+        let current = sources.modification_times();
+        if current == modification_times {
+            continue;
+        }
+        modification_times = current;
+
+        info!("ActiondbParser: the pattern files were changed, reloading them");
+        match load() {
+            Ok((matcher, new_sources)) => {
+                modification_times = new_sources.modification_times();
+                sources = new_sources;
+                if matchers.send(matcher).is_err() {
+                    return;
+                }
+            }
+            Err(err) => {
+                error!("ActiondbParser: failed to reload the pattern files, keeping the \
+                        previous patterns: {}",
+                       err);
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+extern crate actiondb_parser;
+extern crate syslog_ng_common;
+
+use syslog_ng_common::{LogMessage, Parser, ParserBuilder, Error};
+use syslog_ng_common::{syslog_ng_global_init, SYSLOG_NG_INITIALIZED, GlobalConfig};
+use syslog_ng_common::mock::MockPipe;
+use actiondb_parser::{ActiondbParser, ActiondbParserBuilder};
+
+const PATTERN_FILE: &'static str = "tests/patterns.json";
+const FAILING_PATTERN_FILE: &'static str = "tests/failing_patterns.yaml";
+const MATCHING_MESSAGE: &'static str = "Accepted password for joe from 1.2.3.4";
+const UNMATCHED_MESSAGE: &'static str = "Failed password for joe from 1.2.3.4";
+
+fn create_builder(options: &[(&str, &str)]) -> Result<ActiondbParserBuilder, Error> {
+    SYSLOG_NG_INITIALIZED.call_once(|| {
+        unsafe { syslog_ng_global_init(); }
+    });
+
+    let cfg = GlobalConfig::new(0x0308);
+    let mut builder: ActiondbParserBuilder = ParserBuilder::new(cfg);
+    try!(builder.set_pattern_file(PATTERN_FILE));
+    for &(name, value) in options {
+        try!(builder.option(name.to_owned(), value.to_owned()));
+    }
+    Ok(builder)
+}
+
+fn create_parser(options: &[(&str, &str)]) -> ActiondbParser {
+    let builder = create_builder(options).expect("Failed to set the options of the parser");
+    ParserBuilder::build(builder).expect("Failed to build the parser")
+}
+
+fn create_builder_without_pattern_file() -> ActiondbParserBuilder {
+    SYSLOG_NG_INITIALIZED.call_once(|| {
+        unsafe { syslog_ng_global_init(); }
+    });
+
+    let cfg = GlobalConfig::new(0x0308);
+    ParserBuilder::new(cfg)
+}
+
+#[test]
+fn test_given_parser_builder_when_the_pattern_file_is_invalid_then_the_option_is_rejected() {
+    let mut builder = create_builder_without_pattern_file();
+    assert!(builder.option("pattern_file".to_owned(), FAILING_PATTERN_FILE.to_owned()).is_err());
+    assert!(builder.option("pattern_file".to_owned(), "tests/non_existing.json".to_owned())
+                   .is_err());
+}
+
+#[test]
+fn test_given_parser_builder_when_strict_is_not_set_then_only_the_valid_patterns_are_loaded() {
+    let mut builder = create_builder_without_pattern_file();
+    builder.option("strict".to_owned(), "no".to_owned()).expect("Failed to set strict");
+    builder.option("pattern_file".to_owned(), FAILING_PATTERN_FILE.to_owned())
+           .expect("Failed to load the valid patterns");
+    let mut parser = ParserBuilder::build(builder).expect("Failed to build the parser");
+    let mut pipe = MockPipe::new();
+    let mut msg = LogMessage::new();
+
+    assert!(!parser.parse(&mut pipe, &mut msg, "service sshd started"));
+    assert!(parser.parse(&mut pipe, &mut msg, "service sshd stopped"));
+}
+
+#[test]
+fn test_given_parser_when_a_message_does_not_match_then_the_unmatched_values_and_tags_are_set() {
+    let mut parser = create_parser(&[("unmatched_value", "reason=unknown"),
+                                     ("unmatched_value", "severity=low=ish"),
+                                     ("unmatched_tag", "unmatched"),
+                                     ("pass_unmatched", "yes")]);
+    let mut pipe = MockPipe::new();
+    let mut msg = LogMessage::new();
+
+    assert!(parser.parse(&mut pipe, &mut msg, UNMATCHED_MESSAGE));
+    assert_eq!(msg.get("reason"), Some(&b"unknown"[..]));
+    assert_eq!(msg.get("severity"), Some(&b"low=ish"[..]));
+    assert!(msg.tags().contains(&b"unmatched".to_vec()));
+}
+
+#[test]
+fn test_given_parser_when_pass_unmatched_is_not_set_then_the_unmatched_messages_are_dropped() {
+    let mut parser = create_parser(&[("unmatched_tag", "unmatched")]);
+    let mut pipe = MockPipe::new();
+    let mut msg = LogMessage::new();
+
+    assert!(!parser.parse(&mut pipe, &mut msg, UNMATCHED_MESSAGE));
+    assert!(msg.tags().contains(&b"unmatched".to_vec()));
+
+    let mut parser = create_parser(&[("pass_unmatched", "no")]);
+    assert!(!parser.parse(&mut pipe, &mut msg, UNMATCHED_MESSAGE));
+}
+
+#[test]
+fn test_given_parser_when_a_message_matches_then_the_unmatched_values_and_tags_are_not_set() {
+    let mut parser = create_parser(&[("unmatched_value", "reason=unknown"),
+                                     ("unmatched_tag", "unmatched")]);
+    let mut pipe = MockPipe::new();
+    let mut msg = LogMessage::new();
+
+    assert!(parser.parse(&mut pipe, &mut msg, MATCHING_MESSAGE));
+    assert_eq!(msg.get("user"), Some(&b"joe"[..]));
+    assert_eq!(msg.get("reason"), None);
+    assert!(!msg.tags().contains(&b"unmatched".to_vec()));
+}
+
+#[test]
+fn test_given_parser_builder_when_an_unmatched_value_is_not_a_key_value_pair_then_it_is_rejected() {
+    assert!(create_builder(&[("unmatched_value", "reason")]).is_err());
+    assert!(create_builder(&[("unmatched_value", "=unknown")]).is_err());
+    assert!(create_builder(&[("unmatched_value", "reason=")]).is_ok());
+}
+
+#[test]
+fn test_given_parser_builder_when_pass_unmatched_is_not_a_boolean_then_it_is_rejected() {
+    assert!(create_builder(&[("pass_unmatched", "maybe")]).is_err());
+    assert!(create_builder(&[("pass_unmatched", "true")]).is_ok());
+}
+
+#[test]
+fn test_given_parser_when_the_pattern_file_and_test_message_count_keys_are_set_then_they_are_filled
+    () {
+    let mut parser = create_parser(&[("pattern_file_key", ".classifier.file"),
+                                     ("test_message_count_key", ".classifier.tests")]);
+    let mut pipe = MockPipe::new();
+    let mut msg = LogMessage::new();
+
+    assert!(parser.parse(&mut pipe, &mut msg, MATCHING_MESSAGE));
+    assert_eq!(msg.get(".classifier.file"), Some(PATTERN_FILE.as_bytes()));
+    assert_eq!(msg.get(".classifier.tests"), Some(&b"2"[..]));
+}
+
+#[test]
+fn test_given_parser_when_the_pattern_file_and_test_message_count_keys_are_not_set_then_they_are_not_filled
+    () {
+    let mut parser = create_parser(&[]);
+    let mut pipe = MockPipe::new();
+    let mut msg = LogMessage::new();
+
+    assert!(parser.parse(&mut pipe, &mut msg, MATCHING_MESSAGE));
+    assert_eq!(msg.get(".classifier.uuid"), Some(&b"9f4c2b1e-5d3a-4e7f-8a6b-0c1d2e3f4a07"[..]));
+    assert_eq!(msg.get(".classifier.file"), None);
+    assert_eq!(msg.get(".classifier.tests"), None);
+}